@@ -1,32 +1,402 @@
 account = Account
+tailnet = Tailnet
+tailnet-devices = Devices
+tailnet-devices-count =
+    { $count ->
+        [one] 1 device in tailnet ({ $online } online)
+       *[other] { $count } devices in tailnet ({ $online } online)
+    }
+show-all-peers = List all devices
 tailscale-address = Tailscale Address
+tailscale-ipv6-address = IPv6 Address
+copy-address = Copy address
+copied = Copied
+show-ipv6-address = Show IPv6 address
+magicdns-name = MagicDNS Name
+copy-dns-name = Copy MagicDNS name
 connection-status = Connection Status
+traffic-path = Traffic Path
+traffic-path-summary = DNS: { $dns } · Egress: { $egress }
+relay = Relay
+relay-region = { $code } ({ $name })
+dns-tailscale = Tailscale
+dns-system = System
+egress-direct = Direct
+egress-exit-node = Exit node { $node }
 connected = Tailscale Connected
 disconnected = Tailscale Disconnected
+backend-no-state = Tailscale Not Ready
+backend-needs-machine-auth = Tailscale Waiting for Approval
+backend-starting = Tailscale Connecting...
+machine-auth-hint = This device is logged in but must be approved by a tailnet admin before it can connect.
+key-expiry = Node Key
+key-expires-in-days =
+    { $days ->
+        [one] Expires in 1 day
+       *[other] Expires in { $days } days
+    }
+key-expires-in-hours =
+    { $hours ->
+        [one] Expires in 1 hour
+       *[other] Expires in { $hours } hours
+    }
+key-expired = Expired
+key-expiry-disabled = Expiry disabled
+funnel = Funnel
+funnel-off = Off
+funnel-on = On for { $hosts }
 connected-label = Connected
+connecting-label = Connecting
+disconnected-label = Disconnected
+tooltip-exit-node = exit node: { $node }
+tooltip-peers-online = { $online }/{ $count } peers online
+host-offline = This computer is offline
+host-offline-hint = Tailscale needs a network connection to connect. Connect to Wi-Fi or Ethernet first.
+check-again = Check again
 enable-ssh = Enable SSH
 accept-routes = Accept Routes
 tail-drop = Tail Drop
+tail-drop-waiting =
+    { $count ->
+        [one] Tail Drop (1 file waiting)
+       *[other] Tail Drop ({ $count } files waiting)
+    }
+not-enabled-for-tailnet = Not enabled for your tailnet
 select-files = Select File(s)
+own-devices-only = My devices only
 select-files-tooltip = Select the file(s) to send.
 send-files = Send File(s)
 send-files-tooltip = Send the selected file(s).
+confirm-send =
+    { $count ->
+        [one] Send 1 file ({ $size }) to { $device }?
+       *[other] Send { $count } files ({ $size }) to { $device }?
+    }
 receive-files = Receive File(s)
 receive-files-tooltip = Receive files waiting in the Tail Drop inbox.
 send-receive-status = Send/Receive Status
-files-sent-success = File(s) were sent successfully!
+files-sent-success =
+    { $count ->
+        [one] Sent 1 file to { $device }!
+       *[other] Sent { $count } files to { $device }!
+    }
 choose-device-first = Choose a device first, then reselect your file(s)!
 choose-files-title = Choose a file or files...
 exit-node = Exit Node
 selected-node = Selected Node
+search-exit-nodes = Search by name, owner or location
+exit-node-entry = { $node } ({ $details })
+exit-node-locations = Mullvad and other location-based exit nodes
+exit-node-status = { $node }: { $status }
+exit-node-online = online
+exit-node-offline = offline
+exit-node-rtt-direct = { $ms } ms direct
+exit-node-rtt-relayed = { $ms } ms via DERP { $region }
+exit-node-no-reply = no ping reply
 enable-host-exit-node = Enable Host Exit Node
 disable-host-exit-node = Disable Host Exit Node
+changed-externally = Changed externally
+set-by-policy = Set by policy
+edit-settings-hint = Change several settings at once
+edit-settings = Edit
+staged-changes =
+    { $count ->
+        [one] 1 change staged
+       *[other] { $count } changes staged
+    }
+apply-changes = Apply
+discard-changes = Discard
 allow-lan-access = Allow LAN Access
 loading = Loading...
 not-available = N/A
 exit-node-is-host = Can't select an exit node while host is an exit node!
 no-exit-nodes = No exit nodes found!
 send-file-bad-path = Something went wrong sending the file! Possible bad file path!
-send-files-partial-fail = One or more files were not sent successfully!
+send-files-partial-fail =
+    { $failed } of { $count ->
+        [one] 1 file
+       *[other] { $count } files
+    } could not be sent to { $device }!
 no-downloads-dir = Could not determine Downloads directory!
-received-files-success = Received file(s) in Downloads!
+received-files-success =
+    { $count ->
+        [0] No files were waiting in the Tail Drop inbox.
+        [one] Received 1 file in Downloads!
+       *[other] Received { $count } files in Downloads!
+    }
+daemon-not-running = Tailscale daemon not running
+start-daemon = Start Service
+daemon-starting = Starting tailscaled...
+daemon-started = tailscaled started successfully!
+daemon-start-failed = Failed to start tailscaled!
+play-sounds = Play Status Sounds
+notifications = Desktop Notifications
+notify-connected = Tailscale connected
+notify-disconnected = Tailscale disconnected
+notify-peer-online = { $peer } came online
+notify-peer-offline = { $peer } went offline
+notify-files-sent =
+    { $count ->
+        [one] Sent 1 file to { $device }
+       *[other] Sent { $count } files to { $device }
+    }
+notify-files-received =
+    { $count ->
+        [one] Received 1 file
+       *[other] Received { $count } files
+    }
+notify-summary = { $count } Tailscale events
+notify-more = …and { $count } more
+auto-connect = Connect Automatically at Startup
+pause-when-locked = Pause While Locked
+confirm-disconnect = Confirm Before Disconnecting
+check-clock = Check System Clock
+read-only-mode = Read-only mode
+read-only-mode-hint = You are not the Tailscale operator, so settings can't be changed.
+grant-operator = Grant Access
+operator-granting = Requesting operator access...
+operator-granted = Operator access granted!
+operator-grant-failed = Failed to grant operator access!
+logged-out = Logged out of Tailscale
+log-in = Log In
+auth-expired = Authentication expired
+reauthenticate = Re-authenticate
+login-starting = Requesting a login link...
+login-failed = Login failed: { $error }
+login-waiting = Waiting for you to finish logging in in the browser...
+login-success = Logged in
+login-timed-out = The login timed out. Try again.
+login-url-hint = Open this link to log in:
+open-browser = Open Browser
+copy-link = Copy Link
+login-qr-hint = Or scan this code with your phone:
+peer-note-placeholder = Add a note for this device...
+save-note = Save Note
+pin-during-transfers = Keep open during transfers
+subnet-routes =
+    { $count ->
+        [one] 1 subnet route offered
+       *[other] { $count } subnet routes offered
+    }
+route-in-use = In use
+route-standby = Standby
+active-peers = Active Peers
+all-peers = All Peers
+show-active-peers = Show active peers only
+favorite-peers = Favorites
+star-peer = Add to favorites
+unstar-peer = Remove from favorites
+search-peers = Search by name, owner, IP, tag or note
+sort-peers = Sort by
+hide-stale-peers = Hide expired peers and those offline over { $days ->
+        [one] 1 day
+       *[other] { $days } days
+    }
+stale-peers-hidden = { $count ->
+        [one] 1 stale peer hidden
+       *[other] { $count } stale peers hidden
+    }
+group-peers-by-owner = Group by owner
+peer-group-header = { $group } ({ $count })
+peer-group-mine = Me
+peer-group-unknown = Unknown owner
+peer-group-tagged = Tagged devices
+sort-by-name = Name
+sort-by-online = Online first
+sort-by-last-seen = Last seen
+sort-by-owner = Owner
+peer-came-online = Came online
+peer-went-offline = Went offline
+peer-traffic = Traffic burst
+peer-details = Details
+ping-peer = Ping
+copy-ipv4 = Copy IPv4
+copy-machine-name = Copy machine name
+open-ssh-session = Open SSH session
+connect-action = Connect: { $action }
+open-in-browser = Open in browser
+peer-tags = Tags
+use-as-exit-node = Use as exit node
+exit-node-in-use = Exit node in use
+wake-on-lan = Wake-on-LAN
+mac-address-placeholder = MAC address, e.g. 00:11:22:aa:bb:cc
+save-mac-address = Save
+mac-address-invalid = Not a MAC address
+wake-peer = Wake
+wake-sent = Magic packet sent
+wake-failed = Could not send the magic packet: { $error }
+send-files-to-peer = Send files
+ping-reply-direct = { $ms } ms · direct via { $endpoint }
+ping-reply-relayed = { $ms } ms · relayed via DERP { $region }
+path-direct = Direct via { $endpoint }
+path-relayed = Relayed via DERP { $region }
+ping-failed = Ping failed: { $error }
+ping-stats = { $replies }/{ $sent } replies · min { $min } ms · avg { $avg } ms · max { $max } ms
+handshake-seconds =
+    { $count ->
+        [one] Handshake 1 second ago
+       *[other] Handshake { $count } seconds ago
+    }
+handshake-ago = Handshake { $age }
+handshake-never = No handshake yet
+handshake-stale = { $handshake } (stale, connectivity may be one-way)
+route-offline = Router offline
+route-not-accepted = Not accepted
+advertised-routes = Advertised Routes
+no-advertised-routes = This device advertises no subnet routes
+remove-route = Stop advertising this route
+route-placeholder = e.g. 192.168.1.0/24
+add-route = Add Route
+route-presets = Presets:
+route-preset-lan = This LAN ({ $subnet })
+invalid-route = Not a valid route. Use CIDR notation with the host bits set to zero, e.g. 192.168.1.0/24.
+advanced = Advanced
+web-client = Web Interface
+auto-update = Automatic Updates
+posture-checking = Posture Checking
+netfilter-mode = Netfilter Mode
+sending-files =
+    { $count ->
+        [one] Sending 1 file to { $device }...
+       *[other] Sending { $count } files to { $device }...
+    }
+receive-timed-out = No files received (timed out after { $seconds }s)
+receive-failed = Failed to receive files: { $error }
+monitor-only-hint = Monitor-only mode: settings can be viewed but not changed.
+limited-info = Limited information - tailscale output not understood
+health-warnings = Tailscale warnings
+clock-ahead =
+    { $count ->
+        [one] The system clock is 1 minute ahead, so Tailscale may reject keys as not yet valid. Turn on automatic time to fix it.
+       *[other] The system clock is { $count } minutes ahead, so Tailscale may reject keys as not yet valid. Turn on automatic time to fix it.
+    }
+clock-behind =
+    { $count ->
+        [one] The system clock is 1 minute behind, so Tailscale may reject keys as expired. Turn on automatic time to fix it.
+       *[other] The system clock is { $count } minutes behind, so Tailscale may reject keys as expired. Turn on automatic time to fix it.
+    }
+diagnostics = Diagnostics
+generate-diagnostics = Generate Diagnostics
+generate-diagnostics-tooltip = Save a redacted bundle of logs and status for bug reports.
+diagnostics-generating = Collecting diagnostics...
+diagnostics-saved = Diagnostics saved to { $path }
+diagnostics-failed = Failed to generate diagnostics!
+export-peers = Export peer list
+export-peers-saving = Exporting peers...
+export-peers-saved = Peer list saved to { $path }
+export-peers-failed = Could not export peers: { $error }
+netcheck = Connectivity Check
+netcheck-run = Run Diagnostics
+netcheck-failed = Connectivity check failed: { $error }
+netcheck-udp = UDP
+netcheck-ipv4 = IPv4
+netcheck-ipv6 = IPv6
+netcheck-nat = NAT Type
+nat-easy = Easy (direct connections likely)
+nat-hard = Hard (may need a relay)
+netcheck-port-mapping = Port Mapping
+netcheck-none = None
+netcheck-nearest-relay = Nearest Relay
+netcheck-latency = Relay Latency
+latency-ms = { $ms } ms
+whois = Look up a tailnet address
+whois-placeholder = 100.x.y.z or machine name
+whois-lookup = Look up
+whois-failed = Lookup failed: { $error }
+whois-address = Address
+whois-machine = Machine
+whois-owner = Owner
+whois-tags = Tags
+whois-no-tags = None
+yes = Yes
+no = No
+daemon-socket = Daemon Socket
+daemon-version = Daemon Version
+client-version = Tailscale { $version }
+update-available = Update available: { $version }
+update-tailscale = Update Tailscale
+update-starting = Starting update...
+update-finished = Tailscale was updated.
+update-failed = Update failed: { $error }
+operator = Operator
+operator-unset = Not set
+operator-you = { $user } (you)
+raw-status = Raw Status
+raw-status-show = Show
+raw-status-refresh = Refresh
+raw-status-copy = Copy
+last-error = Last error: { $error }
+refresh-failed = Could not read the Tailscale status: { $error }
+setting-failed = Could not change { $setting }: { $error }
+config-not-saved = Settings are not being saved: { $error }
+retry = Retry
+command-slow = "{ $setting }" is taking longer than expected.
+keep-waiting = Keep waiting
+command-cancelled = The command was cancelled; the toggle shows what Tailscale reports.
+dismiss = Dismiss
+search-accounts = Search accounts...
+switch-waits-for-transfer = Switching accounts once the file transfer finishes.
+switch-waits-for-settings = Switching accounts once the current settings change finishes.
+account-entry = { $tailnet } ({ $account })
+account-entry-current = { $tailnet } ({ $account }) - current
+
+# Captive portals
+captive-portal-detected = This network requires a browser login
+suspend-exit-node = Disable Exit Node
+captive-portal-hint = The exit node blocks the login page. It will be turned back on after you log in.
+exit-node-suspended = Exit node { $node } paused
+exit-node-suspended-hint = Log into the network in your browser; the exit node is restored automatically.
+restore-exit-node = Restore Now
+exit-node-restore-failed = Couldn't restore exit node { $node }, it is no longer available
+
+# Exit node quick switch
+exit-node-via = Exit node: { $node }
+no-exit-node = No exit node
+exit-node-turn-off = Turn Off
+exit-node-use-last = Use { $node }
+no-recent-exit-node = No recently used exit node
+
+# Transfer history
+transfer-tab = Transfer
+history-tab = History
+history-empty = No transfers yet
+history-sent = Sent to { $device }
+history-sent-unknown = Sent
+history-received = Received
+history-more-files =
+    { $count ->
+        [one] and 1 more file
+       *[other] and { $count } more files
+    }
+history-ok = Completed
+history-partial = Partially failed: { $error }
+history-failed = Failed: { $error }
+clear-history = Clear History
+age-just-now = just now
+age-minutes =
+    { $count ->
+        [one] 1 minute ago
+       *[other] { $count } minutes ago
+    }
+age-hours =
+    { $count ->
+        [one] 1 hour ago
+       *[other] { $count } hours ago
+    }
+age-days =
+    { $count ->
+        [one] 1 day ago
+       *[other] { $count } days ago
+    }
+computing-checksums = Computing SHA-256 checksums...
+copy-checksum = Copy SHA-256 checksum
+checksum-failed = Couldn't compute checksum: { $error }
+
+# SSH confirmation
+ssh-warning = Enabling Tailscale SSH lets tailnet users allowed by your ACLs log into this device without a password.
+ssh-access-rules = Currently allowed (user or device -> local accounts):
+ssh-access-unknown = The SSH access list isn't available until SSH is on. Check the SSH section of your tailnet policy to see who can log in.
+enable-ssh-confirm = Enable SSH
+disconnect-warning = Disconnecting drops SSH sessions and anything else running over Tailscale.
+disconnect-confirm = Disconnect
+cancel = Cancel
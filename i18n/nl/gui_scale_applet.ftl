@@ -1,32 +1,402 @@
 account = Account
+tailnet = Tailnet
+tailnet-devices = Apparaten
+tailnet-devices-count =
+    { $count ->
+        [one] 1 apparaat in tailnet ({ $online } online)
+       *[other] { $count } apparaten in tailnet ({ $online } online)
+    }
+show-all-peers = Alle apparaten tonen
 tailscale-address = Tailscale-adres
+tailscale-ipv6-address = IPv6-adres
+copy-address = Adres kopiëren
+copied = Gekopieerd
+show-ipv6-address = IPv6-adres tonen
+magicdns-name = MagicDNS-naam
+copy-dns-name = MagicDNS-naam kopiëren
 connection-status = Verbindingsstatus
+traffic-path = Verkeersroute
+traffic-path-summary = DNS: { $dns } · Uitgang: { $egress }
+relay = Relay
+relay-region = { $code } ({ $name })
+dns-tailscale = Tailscale
+dns-system = Systeem
+egress-direct = Direct
+egress-exit-node = Exit-node { $node }
 connected = Tailscale verbonden
 disconnected = Tailscale niet verbonden
+backend-no-state = Tailscale niet gereed
+backend-needs-machine-auth = Tailscale wacht op goedkeuring
+backend-starting = Tailscale verbinden...
+machine-auth-hint = Dit apparaat is aangemeld, maar moet door een tailnet-beheerder worden goedgekeurd voordat het kan verbinden.
+key-expiry = Node-sleutel
+key-expires-in-days =
+    { $days ->
+        [one] Verloopt over 1 dag
+       *[other] Verloopt over { $days } dagen
+    }
+key-expires-in-hours =
+    { $hours ->
+        [one] Verloopt over 1 uur
+       *[other] Verloopt over { $hours } uur
+    }
+key-expired = Verlopen
+key-expiry-disabled = Verloop uitgeschakeld
+funnel = Funnel
+funnel-off = Uit
+funnel-on = Aan voor { $hosts }
 connected-label = Verbonden
+connecting-label = Verbinden
+disconnected-label = Niet verbonden
+tooltip-exit-node = exit-node: { $node }
+tooltip-peers-online = { $online }/{ $count } peers online
+host-offline = Deze computer is offline
+host-offline-hint = Tailscale heeft een netwerkverbinding nodig. Maak eerst verbinding met wifi of ethernet.
+check-again = Opnieuw controleren
 enable-ssh = SSH inschakelen
 accept-routes = Routes accepteren
 tail-drop = Tail Drop
+tail-drop-waiting =
+    { $count ->
+        [one] Tail Drop (1 bestand wacht)
+       *[other] Tail Drop ({ $count } bestanden wachten)
+    }
+not-enabled-for-tailnet = Niet ingeschakeld voor je tailnet
 select-files = Bestanden selecteren
+own-devices-only = Alleen mijn apparaten
 select-files-tooltip = Selecteer de bestanden om te verzenden.
 send-files = Bestanden verzenden
 send-files-tooltip = Verzend de geselecteerde bestanden.
+confirm-send =
+    { $count ->
+        [one] 1 bestand ({ $size }) naar { $device } verzenden?
+       *[other] { $count } bestanden ({ $size }) naar { $device } verzenden?
+    }
 receive-files = Bestanden ontvangen
 receive-files-tooltip = Ontvang bestanden die in de Tail Drop-inbox wachten.
 send-receive-status = Verzend/ontvangststatus
-files-sent-success = Bestand(en) succesvol verzonden!
+files-sent-success =
+    { $count ->
+        [one] 1 bestand verzonden naar { $device }!
+       *[other] { $count } bestanden verzonden naar { $device }!
+    }
 choose-device-first = Kies eerst een apparaat en selecteer vervolgens uw bestanden opnieuw!
 choose-files-title = Kies een bestand of bestanden...
 exit-node = Exit-node
 selected-node = Geselecteerde node
+search-exit-nodes = Zoek op naam, eigenaar of locatie
+exit-node-entry = { $node } ({ $details })
+exit-node-locations = Mullvad en andere exit nodes per locatie
+exit-node-status = { $node }: { $status }
+exit-node-online = online
+exit-node-offline = offline
+exit-node-rtt-direct = { $ms } ms direct
+exit-node-rtt-relayed = { $ms } ms via DERP { $region }
+exit-node-no-reply = geen antwoord op ping
 enable-host-exit-node = Host exit-node inschakelen
 disable-host-exit-node = Host exit-node uitschakelen
+changed-externally = Extern gewijzigd
+set-by-policy = Ingesteld door beleid
+edit-settings-hint = Meerdere instellingen tegelijk wijzigen
+edit-settings = Bewerken
+staged-changes =
+    { $count ->
+        [one] 1 wijziging klaargezet
+       *[other] { $count } wijzigingen klaargezet
+    }
+apply-changes = Toepassen
+discard-changes = Verwerpen
 allow-lan-access = LAN-toegang toestaan
 loading = Laden...
 not-available = N.v.t.
 exit-node-is-host = Kan geen exit-node selecteren terwijl de host een exit-node is!
 no-exit-nodes = Geen exit-nodes gevonden!
 send-file-bad-path = Er ging iets mis bij het verzenden! Mogelijk ongeldig bestandspad!
-send-files-partial-fail = Een of meer bestanden zijn niet succesvol verzonden!
+send-files-partial-fail =
+    { $failed } van { $count ->
+        [one] 1 bestand
+       *[other] { $count } bestanden
+    } kon niet naar { $device } worden verzonden!
 no-downloads-dir = Kan de map Downloads niet bepalen!
-received-files-success = Bestand(en) ontvangen in Downloads!
+received-files-success =
+    { $count ->
+        [0] Er stonden geen bestanden klaar in de Tail Drop-inbox.
+        [one] 1 bestand ontvangen in Downloads!
+       *[other] { $count } bestanden ontvangen in Downloads!
+    }
+daemon-not-running = Tailscale-daemon draait niet
+start-daemon = Service starten
+daemon-starting = tailscaled wordt gestart...
+daemon-started = tailscaled is succesvol gestart!
+daemon-start-failed = Starten van tailscaled mislukt!
+play-sounds = Statusgeluiden afspelen
+notifications = Bureaubladmeldingen
+notify-connected = Tailscale verbonden
+notify-disconnected = Tailscale verbroken
+notify-peer-online = { $peer } is online gekomen
+notify-peer-offline = { $peer } is offline gegaan
+notify-files-sent =
+    { $count ->
+        [one] 1 bestand verzonden naar { $device }
+       *[other] { $count } bestanden verzonden naar { $device }
+    }
+notify-files-received =
+    { $count ->
+        [one] 1 bestand ontvangen
+       *[other] { $count } bestanden ontvangen
+    }
+notify-summary = { $count } Tailscale-gebeurtenissen
+notify-more = …en nog { $count }
+auto-connect = Automatisch verbinden bij opstarten
+pause-when-locked = Pauzeren tijdens vergrendeling
+confirm-disconnect = Bevestigen voor verbreken
+check-clock = Systeemklok controleren
+read-only-mode = Alleen-lezen modus
+read-only-mode-hint = Je bent niet de Tailscale-operator, dus instellingen kunnen niet worden gewijzigd.
+grant-operator = Toegang verlenen
+operator-granting = Operator-toegang aanvragen...
+operator-granted = Operator-toegang verleend!
+operator-grant-failed = Verlenen van operator-toegang mislukt!
+logged-out = Afgemeld bij Tailscale
+log-in = Aanmelden
+auth-expired = Authenticatie verlopen
+reauthenticate = Opnieuw authenticeren
+login-starting = Aanmeldlink aanvragen...
+login-failed = Aanmelden mislukt: { $error }
+login-waiting = Wachten tot je het aanmelden in de browser afrondt...
+login-success = Aangemeld
+login-timed-out = Het aanmelden is verlopen. Probeer het opnieuw.
+login-url-hint = Open deze link om je aan te melden:
+open-browser = Browser openen
+copy-link = Link kopiëren
+login-qr-hint = Of scan deze code met je telefoon:
+peer-note-placeholder = Voeg een notitie toe voor dit apparaat...
+save-note = Notitie opslaan
+pin-during-transfers = Openhouden tijdens overdrachten
+subnet-routes =
+    { $count ->
+        [one] 1 subnetroute aangeboden
+       *[other] { $count } subnetroutes aangeboden
+    }
+route-in-use = In gebruik
+route-standby = Stand-by
+active-peers = Actieve peers
+all-peers = Alle peers
+show-active-peers = Alleen actieve peers tonen
+favorite-peers = Favorieten
+star-peer = Toevoegen aan favorieten
+unstar-peer = Verwijderen uit favorieten
+search-peers = Zoek op naam, eigenaar, IP, tag of notitie
+sort-peers = Sorteren op
+hide-stale-peers = Verlopen peers en peers die langer dan { $days ->
+        [one] 1 dag
+       *[other] { $days } dagen
+    } offline zijn verbergen
+stale-peers-hidden = { $count ->
+        [one] 1 verouderde peer verborgen
+       *[other] { $count } verouderde peers verborgen
+    }
+group-peers-by-owner = Groeperen op eigenaar
+peer-group-header = { $group } ({ $count })
+peer-group-mine = Ik
+peer-group-unknown = Onbekende eigenaar
+peer-group-tagged = Getagde apparaten
+sort-by-name = Naam
+sort-by-online = Online eerst
+sort-by-last-seen = Laatst gezien
+sort-by-owner = Eigenaar
+peer-came-online = Kwam online
+peer-went-offline = Ging offline
+peer-traffic = Verkeerspiek
+peer-details = Details
+ping-peer = Pingen
+copy-ipv4 = IPv4 kopiëren
+copy-machine-name = Machinenaam kopiëren
+open-ssh-session = SSH-sessie openen
+connect-action = Verbinden: { $action }
+open-in-browser = Openen in browser
+peer-tags = Tags
+use-as-exit-node = Als exit-node gebruiken
+exit-node-in-use = Exit-node in gebruik
+wake-on-lan = Wake-on-LAN
+mac-address-placeholder = MAC-adres, bijv. 00:11:22:aa:bb:cc
+save-mac-address = Opslaan
+mac-address-invalid = Geen geldig MAC-adres
+wake-peer = Wekken
+wake-sent = Magic packet verstuurd
+wake-failed = Kon het magic packet niet versturen: { $error }
+send-files-to-peer = Bestanden versturen
+ping-reply-direct = { $ms } ms · direct via { $endpoint }
+ping-reply-relayed = { $ms } ms · doorgestuurd via DERP { $region }
+path-direct = Direct via { $endpoint }
+path-relayed = Doorgestuurd via DERP { $region }
+ping-failed = Pingen mislukt: { $error }
+ping-stats = { $replies }/{ $sent } antwoorden · min { $min } ms · gem { $avg } ms · max { $max } ms
+handshake-seconds =
+    { $count ->
+        [one] Handshake 1 seconde geleden
+       *[other] Handshake { $count } seconden geleden
+    }
+handshake-ago = Handshake { $age }
+handshake-never = Nog geen handshake
+handshake-stale = { $handshake } (verouderd, verbinding werkt mogelijk maar in één richting)
+route-offline = Router offline
+route-not-accepted = Niet geaccepteerd
+advertised-routes = Geadverteerde routes
+no-advertised-routes = Dit apparaat adverteert geen subnetroutes
+remove-route = Deze route niet meer adverteren
+route-placeholder = bijv. 192.168.1.0/24
+add-route = Route toevoegen
+route-presets = Voorinstellingen:
+route-preset-lan = Dit LAN ({ $subnet })
+invalid-route = Geen geldige route. Gebruik CIDR-notatie met de hostbits op nul, bijv. 192.168.1.0/24.
+advanced = Geavanceerd
+web-client = Webinterface
+auto-update = Automatische updates
+posture-checking = Posture-controle
+netfilter-mode = Netfilter-modus
+sending-files =
+    { $count ->
+        [one] 1 bestand wordt verzonden naar { $device }...
+       *[other] { $count } bestanden worden verzonden naar { $device }...
+    }
+receive-timed-out = Geen bestanden ontvangen (time-out na { $seconds }s)
+receive-failed = Ontvangen van bestanden mislukt: { $error }
+monitor-only-hint = Alleen-bekijken modus: instellingen kunnen worden bekeken maar niet gewijzigd.
+limited-info = Beperkte informatie - tailscale-uitvoer niet begrepen
+health-warnings = Tailscale-waarschuwingen
+clock-ahead =
+    { $count ->
+        [one] De systeemklok loopt 1 minuut voor, waardoor Tailscale sleutels als nog niet geldig kan weigeren. Zet automatische tijd aan om dit op te lossen.
+       *[other] De systeemklok loopt { $count } minuten voor, waardoor Tailscale sleutels als nog niet geldig kan weigeren. Zet automatische tijd aan om dit op te lossen.
+    }
+clock-behind =
+    { $count ->
+        [one] De systeemklok loopt 1 minuut achter, waardoor Tailscale sleutels als verlopen kan weigeren. Zet automatische tijd aan om dit op te lossen.
+       *[other] De systeemklok loopt { $count } minuten achter, waardoor Tailscale sleutels als verlopen kan weigeren. Zet automatische tijd aan om dit op te lossen.
+    }
+diagnostics = Diagnostiek
+generate-diagnostics = Diagnostiek genereren
+generate-diagnostics-tooltip = Sla een geanonimiseerde bundel met logs en status op voor bugrapporten.
+diagnostics-generating = Diagnostiek verzamelen...
+diagnostics-saved = Diagnostiek opgeslagen in { $path }
+diagnostics-failed = Genereren van diagnostiek mislukt!
+export-peers = Lijst met peers exporteren
+export-peers-saving = Peers exporteren...
+export-peers-saved = Lijst met peers opgeslagen in { $path }
+export-peers-failed = Kon peers niet exporteren: { $error }
+netcheck = Verbindingscontrole
+netcheck-run = Diagnose uitvoeren
+netcheck-failed = Verbindingscontrole mislukt: { $error }
+netcheck-udp = UDP
+netcheck-ipv4 = IPv4
+netcheck-ipv6 = IPv6
+netcheck-nat = NAT-type
+nat-easy = Eenvoudig (directe verbindingen waarschijnlijk)
+nat-hard = Moeilijk (relay mogelijk nodig)
+netcheck-port-mapping = Poorttoewijzing
+netcheck-none = Geen
+netcheck-nearest-relay = Dichtstbijzijnde relay
+netcheck-latency = Relay-latentie
+latency-ms = { $ms } ms
+whois = Tailnet-adres opzoeken
+whois-placeholder = 100.x.y.z of apparaatnaam
+whois-lookup = Opzoeken
+whois-failed = Opzoeken mislukt: { $error }
+whois-address = Adres
+whois-machine = Apparaat
+whois-owner = Eigenaar
+whois-tags = Tags
+whois-no-tags = Geen
+yes = Ja
+no = Nee
+daemon-socket = Daemon-socket
+daemon-version = Daemonversie
+client-version = Tailscale { $version }
+update-available = Update beschikbaar: { $version }
+update-tailscale = Tailscale bijwerken
+update-starting = Update starten...
+update-finished = Tailscale is bijgewerkt.
+update-failed = Bijwerken mislukt: { $error }
+operator = Operator
+operator-unset = Niet ingesteld
+operator-you = { $user } (jij)
+raw-status = Ruwe status
+raw-status-show = Tonen
+raw-status-refresh = Vernieuwen
+raw-status-copy = Kopiëren
+last-error = Laatste fout: { $error }
+refresh-failed = Kan de Tailscale-status niet lezen: { $error }
+setting-failed = Kan { $setting } niet wijzigen: { $error }
+config-not-saved = Instellingen worden niet opgeslagen: { $error }
+retry = Opnieuw
+command-slow = "{ $setting }" duurt langer dan verwacht.
+keep-waiting = Blijven wachten
+command-cancelled = De opdracht is geannuleerd; de schakelaar toont wat Tailscale meldt.
+dismiss = Sluiten
+search-accounts = Accounts zoeken...
+switch-waits-for-transfer = Account wordt gewisseld zodra de bestandsoverdracht klaar is.
+switch-waits-for-settings = Account wordt gewisseld zodra de huidige instellingswijziging klaar is.
+account-entry = { $tailnet } ({ $account })
+account-entry-current = { $tailnet } ({ $account }) - huidig
+
+# Captive portals
+captive-portal-detected = Dit netwerk vereist een aanmelding in de browser
+suspend-exit-node = Exit-node uitschakelen
+captive-portal-hint = De exit-node blokkeert de aanmeldpagina. Hij wordt weer ingeschakeld nadat je bent aangemeld.
+exit-node-suspended = Exit-node { $node } gepauzeerd
+exit-node-suspended-hint = Meld je aan bij het netwerk in je browser; de exit-node wordt automatisch hersteld.
+restore-exit-node = Nu herstellen
+exit-node-restore-failed = Kon exit-node { $node } niet herstellen, deze is niet meer beschikbaar
+
+# Exit node quick switch
+exit-node-via = Exit-node: { $node }
+no-exit-node = Geen exit-node
+exit-node-turn-off = Uitschakelen
+exit-node-use-last = { $node } gebruiken
+no-recent-exit-node = Geen recent gebruikte exit-node
+
+# Transfer history
+transfer-tab = Overdracht
+history-tab = Geschiedenis
+history-empty = Nog geen overdrachten
+history-sent = Verzonden naar { $device }
+history-sent-unknown = Verzonden
+history-received = Ontvangen
+history-more-files =
+    { $count ->
+        [one] en nog 1 bestand
+       *[other] en nog { $count } bestanden
+    }
+history-ok = Voltooid
+history-partial = Gedeeltelijk mislukt: { $error }
+history-failed = Mislukt: { $error }
+clear-history = Geschiedenis wissen
+age-just-now = zojuist
+age-minutes =
+    { $count ->
+        [one] 1 minuut geleden
+       *[other] { $count } minuten geleden
+    }
+age-hours =
+    { $count ->
+        [one] 1 uur geleden
+       *[other] { $count } uur geleden
+    }
+age-days =
+    { $count ->
+        [one] 1 dag geleden
+       *[other] { $count } dagen geleden
+    }
+computing-checksums = SHA-256-controlesommen berekenen...
+copy-checksum = SHA-256-controlesom kopiëren
+checksum-failed = Kon controlesom niet berekenen: { $error }
+
+# SSH confirmation
+ssh-warning = Met Tailscale SSH kunnen tailnet-gebruikers die je ACL's toestaan zonder wachtwoord op dit apparaat inloggen.
+ssh-access-rules = Momenteel toegestaan (gebruiker of apparaat -> lokale accounts):
+ssh-access-unknown = De SSH-toegangslijst is pas beschikbaar als SSH aan staat. Bekijk het SSH-gedeelte van je tailnet-beleid om te zien wie kan inloggen.
+enable-ssh-confirm = SSH inschakelen
+disconnect-warning = Verbreken sluit SSH-sessies en alles wat verder via Tailscale loopt.
+disconnect-confirm = Verbreken
+cancel = Annuleren
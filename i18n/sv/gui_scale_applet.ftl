@@ -1,32 +1,402 @@
 account = Konto
+tailnet = Tailnet
+tailnet-devices = Enheter
+tailnet-devices-count =
+    { $count ->
+        [one] 1 enhet i tailnet ({ $online } online)
+       *[other] { $count } enheter i tailnet ({ $online } online)
+    }
+show-all-peers = Visa alla enheter
 tailscale-address = Tailscale-adress
+tailscale-ipv6-address = IPv6-adress
+copy-address = Kopiera adress
+copied = Kopierad
+show-ipv6-address = Visa IPv6-adress
+magicdns-name = MagicDNS-namn
+copy-dns-name = Kopiera MagicDNS-namn
 connection-status = Anslutningsstatus
+traffic-path = Trafikväg
+traffic-path-summary = DNS: { $dns } · Utgång: { $egress }
+relay = Relä
+relay-region = { $code } ({ $name })
+dns-tailscale = Tailscale
+dns-system = System
+egress-direct = Direkt
+egress-exit-node = Exit-nod { $node }
 connected = Tailscale ansluten
 disconnected = Tailscale frånkopplad
+backend-no-state = Tailscale inte redo
+backend-needs-machine-auth = Tailscale väntar på godkännande
+backend-starting = Tailscale ansluter...
+machine-auth-hint = Enheten är inloggad men måste godkännas av en tailnet-administratör innan den kan ansluta.
+key-expiry = Nodnyckel
+key-expires-in-days =
+    { $days ->
+        [one] Går ut om 1 dag
+       *[other] Går ut om { $days } dagar
+    }
+key-expires-in-hours =
+    { $hours ->
+        [one] Går ut om 1 timme
+       *[other] Går ut om { $hours } timmar
+    }
+key-expired = Har gått ut
+key-expiry-disabled = Utgång inaktiverad
+funnel = Funnel
+funnel-off = Av
+funnel-on = På för { $hosts }
 connected-label = Ansluten
+connecting-label = Ansluter
+disconnected-label = Frånkopplad
+tooltip-exit-node = exit-nod: { $node }
+tooltip-peers-online = { $online }/{ $count } enheter online
+host-offline = Den här datorn är offline
+host-offline-hint = Tailscale behöver en nätverksanslutning. Anslut till wifi eller Ethernet först.
+check-again = Kontrollera igen
 enable-ssh = Aktivera SSH
 accept-routes = Acceptera rutter
 tail-drop = Tail Drop
+tail-drop-waiting =
+    { $count ->
+        [one] Tail Drop (1 fil väntar)
+       *[other] Tail Drop ({ $count } filer väntar)
+    }
+not-enabled-for-tailnet = Inte aktiverat för ditt tailnet
 select-files = Välj fil(er)
+own-devices-only = Endast mina enheter
 select-files-tooltip = Välj fil(er) att skicka.
 send-files = Skicka fil(er)
 send-files-tooltip = Skicka valda fil(er).
+confirm-send =
+    { $count ->
+        [one] Skicka 1 fil ({ $size }) till { $device }?
+       *[other] Skicka { $count } filer ({ $size }) till { $device }?
+    }
 receive-files = Ta emot fil(er)
 receive-files-tooltip = Ta emot filer som väntar i Tail Drop-inkorgen.
 send-receive-status = Skicka/ta emot-status
-files-sent-success = Fil(er) skickades!
+files-sent-success =
+    { $count ->
+        [one] Skickade 1 fil till { $device }!
+       *[other] Skickade { $count } filer till { $device }!
+    }
 choose-device-first = Välj en enhet först och välj sedan dina filer igen!
 choose-files-title = Välj en fil eller filer...
 exit-node = Exit-nod
 selected-node = Vald nod
+search-exit-nodes = Sök på namn, ägare eller plats
+exit-node-entry = { $node } ({ $details })
+exit-node-locations = Mullvad och andra platsbaserade exit nodes
+exit-node-status = { $node }: { $status }
+exit-node-online = online
+exit-node-offline = offline
+exit-node-rtt-direct = { $ms } ms direkt
+exit-node-rtt-relayed = { $ms } ms via DERP { $region }
+exit-node-no-reply = inget svar på ping
 enable-host-exit-node = Aktivera värd-exit-nod
 disable-host-exit-node = Inaktivera värd-exit-nod
+changed-externally = Ändrad externt
+set-by-policy = Styrs av policy
+edit-settings-hint = Ändra flera inställningar på en gång
+edit-settings = Redigera
+staged-changes =
+    { $count ->
+        [one] 1 ändring förberedd
+       *[other] { $count } ändringar förberedda
+    }
+apply-changes = Verkställ
+discard-changes = Förkasta
 allow-lan-access = Tillåt LAN-åtkomst
 loading = Laddar...
 not-available = Ej tillgänglig
 exit-node-is-host = Kan inte välja exit-nod medan värden är en exit-nod!
 no-exit-nodes = Inga exit-noder hittades!
 send-file-bad-path = Något gick fel vid sändning! Möjligen ogiltig filsökväg!
-send-files-partial-fail = En eller flera filer skickades inte!
+send-files-partial-fail =
+    { $failed } av { $count ->
+        [one] 1 fil
+       *[other] { $count } filer
+    } kunde inte skickas till { $device }!
 no-downloads-dir = Kunde inte hitta mappen Nedladdningar!
-received-files-success = Fil(er) mottagna i Nedladdningar!
+received-files-success =
+    { $count ->
+        [0] Inga filer väntade i Tail Drop-inkorgen.
+        [one] Tog emot 1 fil i Hämtade filer!
+       *[other] Tog emot { $count } filer i Hämtade filer!
+    }
+daemon-not-running = Tailscale-tjänsten körs inte
+start-daemon = Starta tjänsten
+daemon-starting = Startar tailscaled...
+daemon-started = tailscaled startades!
+daemon-start-failed = Kunde inte starta tailscaled!
+play-sounds = Spela statusljud
+notifications = Skrivbordsaviseringar
+notify-connected = Tailscale anslutet
+notify-disconnected = Tailscale frånkopplat
+notify-peer-online = { $peer } kom online
+notify-peer-offline = { $peer } gick offline
+notify-files-sent =
+    { $count ->
+        [one] Skickade 1 fil till { $device }
+       *[other] Skickade { $count } filer till { $device }
+    }
+notify-files-received =
+    { $count ->
+        [one] Tog emot 1 fil
+       *[other] Tog emot { $count } filer
+    }
+notify-summary = { $count } Tailscale-händelser
+notify-more = …och { $count } till
+auto-connect = Anslut automatiskt vid start
+pause-when-locked = Pausa när skärmen är låst
+confirm-disconnect = Bekräfta innan frånkoppling
+check-clock = Kontrollera systemklockan
+read-only-mode = Skrivskyddat läge
+read-only-mode-hint = Du är inte Tailscale-operatör, så inställningarna kan inte ändras.
+grant-operator = Bevilja åtkomst
+operator-granting = Begär operatörsåtkomst...
+operator-granted = Operatörsåtkomst beviljad!
+operator-grant-failed = Kunde inte bevilja operatörsåtkomst!
+logged-out = Utloggad från Tailscale
+log-in = Logga in
+auth-expired = Autentiseringen har gått ut
+reauthenticate = Autentisera igen
+login-starting = Begär en inloggningslänk...
+login-failed = Inloggningen misslyckades: { $error }
+login-waiting = Väntar på att du slutför inloggningen i webbläsaren...
+login-success = Inloggad
+login-timed-out = Inloggningen tog för lång tid. Försök igen.
+login-url-hint = Öppna den här länken för att logga in:
+open-browser = Öppna webbläsare
+copy-link = Kopiera länk
+login-qr-hint = Eller skanna koden med telefonen:
+peer-note-placeholder = Lägg till en anteckning för enheten...
+save-note = Spara anteckning
+pin-during-transfers = Håll öppen under överföringar
+subnet-routes =
+    { $count ->
+        [one] 1 subnätsrutt erbjuds
+       *[other] { $count } subnätsrutter erbjuds
+    }
+route-in-use = Används
+route-standby = Vänteläge
+active-peers = Aktiva noder
+all-peers = Alla noder
+show-active-peers = Visa endast aktiva noder
+favorite-peers = Favoriter
+star-peer = Lägg till i favoriter
+unstar-peer = Ta bort från favoriter
+search-peers = Sök på namn, ägare, IP, tagg eller anteckning
+sort-peers = Sortera efter
+hide-stale-peers = Dölj utgångna enheter och enheter offline i över { $days ->
+        [one] 1 dag
+       *[other] { $days } dagar
+    }
+stale-peers-hidden = { $count ->
+        [one] 1 inaktuell enhet dold
+       *[other] { $count } inaktuella enheter dolda
+    }
+group-peers-by-owner = Gruppera efter ägare
+peer-group-header = { $group } ({ $count })
+peer-group-mine = Jag
+peer-group-unknown = Okänd ägare
+peer-group-tagged = Taggade enheter
+sort-by-name = Namn
+sort-by-online = Online först
+sort-by-last-seen = Senast sedd
+sort-by-owner = Ägare
+peer-came-online = Kom online
+peer-went-offline = Gick offline
+peer-traffic = Trafiktopp
+peer-details = Detaljer
+ping-peer = Pinga
+copy-ipv4 = Kopiera IPv4
+copy-machine-name = Kopiera datornamn
+open-ssh-session = Öppna SSH-session
+connect-action = Anslut: { $action }
+open-in-browser = Öppna i webbläsaren
+peer-tags = Taggar
+use-as-exit-node = Använd som exit-nod
+exit-node-in-use = Exit-nod används
+wake-on-lan = Wake-on-LAN
+mac-address-placeholder = MAC-adress, t.ex. 00:11:22:aa:bb:cc
+save-mac-address = Spara
+mac-address-invalid = Inte en MAC-adress
+wake-peer = Väck
+wake-sent = Magiskt paket skickat
+wake-failed = Kunde inte skicka det magiska paketet: { $error }
+send-files-to-peer = Skicka filer
+ping-reply-direct = { $ms } ms · direkt via { $endpoint }
+ping-reply-relayed = { $ms } ms · vidarebefordrad via DERP { $region }
+path-direct = Direkt via { $endpoint }
+path-relayed = Vidarebefordrad via DERP { $region }
+ping-failed = Ping misslyckades: { $error }
+ping-stats = { $replies }/{ $sent } svar · min { $min } ms · medel { $avg } ms · max { $max } ms
+handshake-seconds =
+    { $count ->
+        [one] Handskakning för 1 sekund sedan
+       *[other] Handskakning för { $count } sekunder sedan
+    }
+handshake-ago = Handskakning { $age }
+handshake-never = Ingen handskakning än
+handshake-stale = { $handshake } (inaktuell, anslutningen kan vara enkelriktad)
+route-offline = Router offline
+route-not-accepted = Inte accepterad
+advertised-routes = Annonserade rutter
+no-advertised-routes = Den här enheten annonserar inga subnätsrutter
+remove-route = Sluta annonsera den här rutten
+route-placeholder = t.ex. 192.168.1.0/24
+add-route = Lägg till rutt
+route-presets = Förval:
+route-preset-lan = Detta LAN ({ $subnet })
+invalid-route = Ogiltig rutt. Använd CIDR-notation med värdbitarna satta till noll, t.ex. 192.168.1.0/24.
+advanced = Avancerat
+web-client = Webbgränssnitt
+auto-update = Automatiska uppdateringar
+posture-checking = Enhetskontroll
+netfilter-mode = Netfilter-läge
+sending-files =
+    { $count ->
+        [one] Skickar 1 fil till { $device }...
+       *[other] Skickar { $count } filer till { $device }...
+    }
+receive-timed-out = Inga filer mottogs (tidsgränsen på { $seconds }s nåddes)
+receive-failed = Kunde inte ta emot filer: { $error }
+monitor-only-hint = Endast övervakning: inställningarna kan visas men inte ändras.
+limited-info = Begränsad information - tailscale-utdata förstods inte
+health-warnings = Tailscale-varningar
+clock-ahead =
+    { $count ->
+        [one] Systemklockan går 1 minut före, så Tailscale kan avvisa nycklar som ännu inte giltiga. Slå på automatisk tid för att åtgärda det.
+       *[other] Systemklockan går { $count } minuter före, så Tailscale kan avvisa nycklar som ännu inte giltiga. Slå på automatisk tid för att åtgärda det.
+    }
+clock-behind =
+    { $count ->
+        [one] Systemklockan går 1 minut efter, så Tailscale kan avvisa nycklar som utgångna. Slå på automatisk tid för att åtgärda det.
+       *[other] Systemklockan går { $count } minuter efter, så Tailscale kan avvisa nycklar som utgångna. Slå på automatisk tid för att åtgärda det.
+    }
+diagnostics = Diagnostik
+generate-diagnostics = Skapa diagnostik
+generate-diagnostics-tooltip = Spara ett anonymiserat paket med loggar och status för felrapporter.
+diagnostics-generating = Samlar in diagnostik...
+diagnostics-saved = Diagnostik sparad i { $path }
+diagnostics-failed = Kunde inte skapa diagnostik!
+export-peers = Exportera peer-lista
+export-peers-saving = Exporterar peers...
+export-peers-saved = Peer-listan sparades i { $path }
+export-peers-failed = Kunde inte exportera peers: { $error }
+netcheck = Anslutningskontroll
+netcheck-run = Kör diagnostik
+netcheck-failed = Anslutningskontrollen misslyckades: { $error }
+netcheck-udp = UDP
+netcheck-ipv4 = IPv4
+netcheck-ipv6 = IPv6
+netcheck-nat = NAT-typ
+nat-easy = Enkel (direkta anslutningar troliga)
+nat-hard = Svår (kan behöva relä)
+netcheck-port-mapping = Portmappning
+netcheck-none = Ingen
+netcheck-nearest-relay = Närmaste relä
+netcheck-latency = Relälatens
+latency-ms = { $ms } ms
+whois = Slå upp en tailnet-adress
+whois-placeholder = 100.x.y.z eller enhetsnamn
+whois-lookup = Slå upp
+whois-failed = Uppslagningen misslyckades: { $error }
+whois-address = Adress
+whois-machine = Enhet
+whois-owner = Ägare
+whois-tags = Taggar
+whois-no-tags = Inga
+yes = Ja
+no = Nej
+daemon-socket = Daemon-socket
+daemon-version = Daemonversion
+client-version = Tailscale { $version }
+update-available = Uppdatering tillgänglig: { $version }
+update-tailscale = Uppdatera Tailscale
+update-starting = Startar uppdatering...
+update-finished = Tailscale har uppdaterats.
+update-failed = Uppdateringen misslyckades: { $error }
+operator = Operatör
+operator-unset = Inte angiven
+operator-you = { $user } (du)
+raw-status = Rå status
+raw-status-show = Visa
+raw-status-refresh = Uppdatera
+raw-status-copy = Kopiera
+last-error = Senaste fel: { $error }
+refresh-failed = Kunde inte läsa Tailscale-status: { $error }
+setting-failed = Kunde inte ändra { $setting }: { $error }
+config-not-saved = Inställningarna sparas inte: { $error }
+retry = Försök igen
+command-slow = "{ $setting }" tar längre tid än väntat.
+keep-waiting = Fortsätt vänta
+command-cancelled = Kommandot avbröts; reglaget visar vad Tailscale rapporterar.
+dismiss = Stäng
+search-accounts = Sök konton...
+switch-waits-for-transfer = Byter konto när filöverföringen är klar.
+switch-waits-for-settings = Byter konto när den pågående inställningsändringen är klar.
+account-entry = { $tailnet } ({ $account })
+account-entry-current = { $tailnet } ({ $account }) - aktuellt
+
+# Captive portals
+captive-portal-detected = Det här nätverket kräver inloggning i webbläsaren
+suspend-exit-node = Inaktivera exit-nod
+captive-portal-hint = Exit-noden blockerar inloggningssidan. Den slås på igen efter att du har loggat in.
+exit-node-suspended = Exit-nod { $node } pausad
+exit-node-suspended-hint = Logga in på nätverket i webbläsaren; exit-noden återställs automatiskt.
+restore-exit-node = Återställ nu
+exit-node-restore-failed = Kunde inte återställa exit-nod { $node }, den är inte längre tillgänglig
+
+# Exit node quick switch
+exit-node-via = Exit-nod: { $node }
+no-exit-node = Ingen exit-nod
+exit-node-turn-off = Stäng av
+exit-node-use-last = Använd { $node }
+no-recent-exit-node = Ingen nyligen använd exit-nod
+
+# Transfer history
+transfer-tab = Överföring
+history-tab = Historik
+history-empty = Inga överföringar ännu
+history-sent = Skickat till { $device }
+history-sent-unknown = Skickat
+history-received = Mottaget
+history-more-files =
+    { $count ->
+        [one] och 1 fil till
+       *[other] och { $count } filer till
+    }
+history-ok = Slutförd
+history-partial = Delvis misslyckad: { $error }
+history-failed = Misslyckad: { $error }
+clear-history = Rensa historik
+age-just-now = just nu
+age-minutes =
+    { $count ->
+        [one] för 1 minut sedan
+       *[other] för { $count } minuter sedan
+    }
+age-hours =
+    { $count ->
+        [one] för 1 timme sedan
+       *[other] för { $count } timmar sedan
+    }
+age-days =
+    { $count ->
+        [one] för 1 dag sedan
+       *[other] för { $count } dagar sedan
+    }
+computing-checksums = Beräknar SHA-256-kontrollsummor...
+copy-checksum = Kopiera SHA-256-kontrollsumma
+checksum-failed = Kunde inte beräkna kontrollsumma: { $error }
+
+# SSH confirmation
+ssh-warning = Med Tailscale SSH kan tailnet-användare som dina ACL:er tillåter logga in på den här enheten utan lösenord.
+ssh-access-rules = Tillåts för närvarande (användare eller enhet -> lokala konton):
+ssh-access-unknown = SSH-åtkomstlistan är inte tillgänglig förrän SSH är på. Se SSH-avsnittet i din tailnet-policy för att se vem som kan logga in.
+enable-ssh-confirm = Aktivera SSH
+disconnect-warning = Frånkoppling avbryter SSH-sessioner och allt annat som går över Tailscale.
+disconnect-confirm = Koppla från
+cancel = Avbryt
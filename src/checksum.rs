@@ -0,0 +1,44 @@
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+use tokio::fs::File;
+use tokio::io::AsyncReadExt;
+
+use crate::error::AppError;
+
+/// Files are hashed in chunks of this size so large transfers don't fill memory.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// SHA-256 of a received file, or why it couldn't be computed.
+#[derive(Debug, Clone)]
+pub struct FileChecksum {
+  pub name: String,
+  pub sha256: Result<String, String>,
+}
+
+/// Hex-encoded SHA-256 digest of the file at `path`.
+pub async fn sha256_file(path: &Path) -> Result<String, AppError> {
+  let mut file = File::open(path).await?;
+  let mut hasher = Sha256::new();
+  let mut buf = vec![0; CHUNK_SIZE];
+
+  loop {
+    let read = file.read(&mut buf).await?;
+    if read == 0 {
+      break;
+    }
+    hasher.update(&buf[..read]);
+  }
+
+  Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Checksum each of `names` inside `dir`, one file at a time.
+pub async fn sha256_files(dir: &Path, names: Vec<String>) -> Vec<FileChecksum> {
+  let mut checksums = Vec::with_capacity(names.len());
+  for name in names {
+    let sha256 = sha256_file(&dir.join(&name)).await.map_err(|e| e.to_string());
+    checksums.push(FileChecksum { name, sha256 });
+  }
+  checksums
+}
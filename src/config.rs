@@ -1,13 +1,222 @@
+use crate::ids::PeerName;
 use cosmic::cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, CosmicConfigEntry};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+/// Taildrop batches with more files than this ask for confirmation first.
+const DEFAULT_CONFIRM_SEND_FILES: usize = 20;
+/// Taildrop batches larger than this many megabytes ask for confirmation first.
+const DEFAULT_CONFIRM_SEND_MB: u64 = 1000;
+/// Offline peers not seen for more days than this count as stale.
+const DEFAULT_STALE_PEER_DAYS: u64 = 30;
+
+/// Order of the peer list.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PeerSort {
+  #[default]
+  Name,
+  /// Online peers first.
+  Online,
+  /// Most recently seen first, with online peers counting as seen now.
+  LastSeen,
+  Owner,
+}
+
+impl PeerSort {
+  pub const ALL: [PeerSort; 4] = [
+    PeerSort::Name,
+    PeerSort::Online,
+    PeerSort::LastSeen,
+    PeerSort::Owner,
+  ];
+}
+
+/// A "Connect" button in peer details, such as a remote desktop client.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectAction {
+  /// Button label, e.g. "RDP".
+  pub name: String,
+  /// Program and arguments; `{host}` is replaced by the peer's machine name,
+  /// which is appended if there is no placeholder.
+  pub command: Vec<String>,
+  /// Hostnames of the peers offering the action; empty offers it for all.
+  #[serde(default)]
+  pub peers: BTreeSet<PeerName>,
+}
 
 #[derive(
-  Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, CosmicConfigEntry,
+  Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CosmicConfigEntry,
 )]
 #[version = 2]
 pub struct TailscaleConfig {
+  /// Exit node in use; empty for none. It replaced `exit_node_idx` without a
+  /// version bump, which would have reset every other setting: a stored index
+  /// is ignored, and the first refresh shows the node actually in use.
   #[serde(default)]
-  pub exit_node_idx: usize,
+  pub exit_node: PeerName,
   #[serde(default)]
   pub allow_lan: bool,
+  /// LAN access chosen while using each exit node, re-applied when it is selected again.
+  #[serde(default)]
+  pub exit_node_lan_access: BTreeMap<PeerName, bool>,
+  /// Most recently used exit node, for the one-click on/off switch.
+  #[serde(default)]
+  pub last_exit_node: PeerName,
+  #[serde(default)]
+  pub play_sounds: bool,
+  /// Desktop notifications for connection, peer and transfer events.
+  #[serde(default)]
+  pub notifications: bool,
+  /// Run `tailscale up` when the applet starts and Tailscale is logged in but down.
+  #[serde(default)]
+  pub auto_connect: bool,
+  /// Stop polling and status sounds while the session is locked.
+  #[serde(default)]
+  pub pause_when_locked: bool,
+  /// Ask before the connection toggle takes Tailscale down.
+  #[serde(default)]
+  pub confirm_disconnect: bool,
+  #[serde(default)]
+  pub pin_during_transfers: bool,
+  /// Show status only and never issue mutating tailscale commands.
+  #[serde(default)]
+  pub monitor_only: bool,
+  /// Also write logs to `~/.local/state/gui-scale-applet/applet.log`.
+  #[serde(default)]
+  pub log_to_file: bool,
+  /// Log sanitized tailscale command lines and how long each backend call took.
+  #[serde(default)]
+  pub verbose_logging: bool,
+  /// User notes keyed by peer hostname.
+  #[serde(default)]
+  pub peer_notes: BTreeMap<PeerName, String>,
+  /// MAC addresses for Wake-on-LAN, keyed by peer hostname.
+  #[serde(default)]
+  pub peer_macs: BTreeMap<PeerName, String>,
+  #[serde(default)]
+  pub peer_sort: PeerSort,
+  /// Leave expired peers and those offline for over `stale_peer_days` out of
+  /// the peer list.
+  #[serde(default)]
+  pub hide_stale_peers: bool,
+  /// List peers under a header per owner: this user, other users, then tagged
+  /// devices.
+  #[serde(default)]
+  pub group_peers_by_owner: bool,
+  #[serde(default = "default_stale_peer_days")]
+  pub stale_peer_days: u64,
+  /// Starred peers by hostname, shown at the top of the popup and of the
+  /// Taildrop device list.
+  #[serde(default)]
+  pub favorite_peers: BTreeSet<PeerName>,
+  /// Path to the tailscale binary; empty means `tailscale` on `PATH`.
+  #[serde(default)]
+  pub cli_path: String,
+  /// Arguments inserted before every subcommand (e.g. for wrappers).
+  #[serde(default)]
+  pub cli_extra_args: Vec<String>,
+  /// Confirm Taildrop sends of more files than this; 0 never asks.
+  #[serde(default = "default_confirm_send_files")]
+  pub confirm_send_files: usize,
+  /// Confirm Taildrop sends larger than this many megabytes; 0 never asks.
+  #[serde(default = "default_confirm_send_mb")]
+  pub confirm_send_mb: u64,
+  /// Panel icon while connected: an icon name, or a path to an image file.
+  /// Empty falls back to `icon_dir`, then the default icon.
+  #[serde(default)]
+  pub icon_connected: String,
+  #[serde(default)]
+  pub icon_disconnected: String,
+  /// Panel icon while traffic goes through an exit node.
+  #[serde(default)]
+  pub icon_exit_node: String,
+  /// Directory with `connected`, `disconnected` and `exit-node` icons, as
+  /// `.svg` or `.png`, for states without their own icon setting.
+  #[serde(default)]
+  pub icon_dir: String,
+  /// Terminal command for SSH sessions to peers; `{host}` is replaced by the
+  /// peer's machine name, which is appended if there is no placeholder.
+  #[serde(default = "default_ssh_command")]
+  pub ssh_command: Vec<String>,
+  #[serde(default)]
+  pub connect_actions: Vec<ConnectAction>,
+  /// Compare the system clock with the coordination server's once an hour.
+  /// Off by default, as it contacts a server outside the tailnet.
+  #[serde(default)]
+  pub check_clock: bool,
+}
+
+fn default_confirm_send_files() -> usize {
+  DEFAULT_CONFIRM_SEND_FILES
+}
+
+fn default_confirm_send_mb() -> u64 {
+  DEFAULT_CONFIRM_SEND_MB
+}
+
+fn default_stale_peer_days() -> u64 {
+  DEFAULT_STALE_PEER_DAYS
+}
+
+fn default_ssh_command() -> Vec<String> {
+  ["cosmic-term", "--", "ssh", "{host}"].map(String::from).to_vec()
+}
+
+impl Default for TailscaleConfig {
+  fn default() -> Self {
+    TailscaleConfig {
+      exit_node: PeerName::default(),
+      allow_lan: false,
+      exit_node_lan_access: BTreeMap::new(),
+      last_exit_node: PeerName::default(),
+      play_sounds: false,
+      notifications: false,
+      auto_connect: false,
+      pause_when_locked: false,
+      confirm_disconnect: false,
+      pin_during_transfers: false,
+      monitor_only: false,
+      log_to_file: false,
+      verbose_logging: false,
+      peer_notes: BTreeMap::new(),
+      peer_macs: BTreeMap::new(),
+      peer_sort: PeerSort::Name,
+      hide_stale_peers: false,
+      group_peers_by_owner: false,
+      stale_peer_days: DEFAULT_STALE_PEER_DAYS,
+      favorite_peers: BTreeSet::new(),
+      cli_path: String::new(),
+      cli_extra_args: Vec::new(),
+      confirm_send_files: DEFAULT_CONFIRM_SEND_FILES,
+      confirm_send_mb: DEFAULT_CONFIRM_SEND_MB,
+      icon_connected: String::new(),
+      icon_disconnected: String::new(),
+      icon_exit_node: String::new(),
+      icon_dir: String::new(),
+      ssh_command: default_ssh_command(),
+      connect_actions: Vec::new(),
+      check_clock: false,
+    }
+  }
+}
+
+impl TailscaleConfig {
+  /// Read the config without reporting errors, for use before logging is set up.
+  pub fn load_quiet() -> Self {
+    cosmic_config::Config::new(crate::window::ID, Self::VERSION)
+      .ok()
+      .map(|handler| Self::get_entry(&handler).unwrap_or_else(|(_, cfg)| cfg))
+      .unwrap_or_default()
+  }
+
+  /// Directory cosmic-config keeps this config in, one file per field.
+  pub fn path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| {
+      dir
+        .join("cosmic")
+        .join(crate::window::ID)
+        .join(format!("v{}", Self::VERSION))
+    })
+  }
 }
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use tokio::sync::mpsc::Sender;
+use tracing::{debug, warn};
+use zbus::{
+  connection, fdo::RequestNameReply, interface, object_server::SignalEmitter, proxy,
+  proxy::PropertyStream, Connection,
+};
+
+pub const DBUS_NAME: &str = "com.github.bhh32.GUIScaleApplet";
+pub const DBUS_PATH: &str = "/com/github/bhh32/GUIScaleApplet";
+
+/// Requests received over D-Bus, forwarded to the applet's update loop.
+#[derive(Debug, Clone)]
+pub enum DbusCommand {
+  Connect,
+  Disconnect,
+  SetExitNode(String),
+  SendFiles(Vec<String>, String),
+}
+
+/// The `com.github.bhh32.GUIScaleApplet` interface.
+struct AppletInterface {
+  tx: Sender<DbusCommand>,
+}
+
+impl AppletInterface {
+  async fn forward(&self, cmd: DbusCommand) {
+    if let Err(e) = self.tx.send(cmd).await {
+      warn!("Dropping D-Bus request, applet is shutting down: {e}");
+    }
+  }
+}
+
+#[interface(name = "com.github.bhh32.GUIScaleApplet")]
+impl AppletInterface {
+  /// Bring the Tailscale connection up.
+  async fn connect(&self) {
+    self.forward(DbusCommand::Connect).await;
+  }
+
+  /// Take the Tailscale connection down.
+  async fn disconnect(&self) {
+    self.forward(DbusCommand::Disconnect).await;
+  }
+
+  /// Use `name` as the exit node; an empty string clears it.
+  async fn set_exit_node(&self, name: String) {
+    self.forward(DbusCommand::SetExitNode(name)).await;
+  }
+
+  /// Send files over Taildrop to the device named `target`.
+  async fn send_files(&self, paths: Vec<String>, target: String) {
+    self.forward(DbusCommand::SendFiles(paths, target)).await;
+  }
+
+  /// `ip` is the device's first Tailscale address, IPv4 preferred.
+  #[zbus(signal)]
+  async fn state_changed(
+    emitter: &SignalEmitter<'_>,
+    connected: bool,
+    ip: &str,
+    exit_node: &str,
+  ) -> zbus::Result<()>;
+}
+
+/// Publish the applet interface on the session bus.
+///
+/// Only one instance can own the bus name. Later ones, e.g. on a second panel,
+/// queue for it and take over requests when the owner exits; until then their
+/// connection is still used for signals and notifications.
+pub async fn serve(tx: Sender<DbusCommand>) -> zbus::Result<Connection> {
+  let conn = connection::Builder::session()?
+    .serve_at(DBUS_PATH, AppletInterface { tx })?
+    .build()
+    .await?;
+  // Without `DoNotQueue`, a taken name queues the request instead of failing.
+  match conn.request_name_with_flags(DBUS_NAME, Default::default()).await? {
+    RequestNameReply::PrimaryOwner | RequestNameReply::AlreadyOwner => {}
+    RequestNameReply::InQueue => {
+      debug!("Another instance owns {DBUS_NAME}; queued to take over when it exits");
+    }
+    RequestNameReply::Exists => warn!("{DBUS_NAME} is owned by another instance"),
+  }
+  Ok(conn)
+}
+
+/// Emit the `StateChanged` signal.
+pub async fn emit_state_changed(
+  conn: &Connection,
+  connected: bool,
+  ip: &str,
+  exit_node: &str,
+) -> zbus::Result<()> {
+  let emitter = SignalEmitter::new(conn, DBUS_PATH)?;
+  AppletInterface::state_changed(&emitter, connected, ip, exit_node).await
+}
+
+/// The calling user's logind session; lock screens set `LockedHint`.
+#[proxy(
+  interface = "org.freedesktop.login1.Session",
+  default_service = "org.freedesktop.login1",
+  default_path = "/org/freedesktop/login1/session/auto",
+  gen_blocking = false
+)]
+trait LoginSession {
+  #[zbus(property)]
+  fn locked_hint(&self) -> zbus::Result<bool>;
+}
+
+/// Whether the session is locked now, and a stream of later changes.
+pub async fn watch_session_lock() -> zbus::Result<(bool, PropertyStream<'static, bool>)> {
+  let conn = Connection::system().await?;
+  let session = LoginSessionProxy::new(&conn).await?;
+  let locked = session.locked_hint().await?;
+  Ok((locked, session.receive_locked_hint_changed().await))
+}
+
+/// The desktop notification service.
+#[proxy(
+  interface = "org.freedesktop.Notifications",
+  default_service = "org.freedesktop.Notifications",
+  default_path = "/org/freedesktop/Notifications",
+  gen_blocking = false
+)]
+trait Notifications {
+  #[allow(clippy::too_many_arguments)]
+  fn notify(
+    &self,
+    app_name: &str,
+    replaces_id: u32,
+    app_icon: &str,
+    summary: &str,
+    body: &str,
+    actions: &[&str],
+    hints: HashMap<&str, zbus::zvariant::Value<'_>>,
+    expire_timeout: i32,
+  ) -> zbus::Result<u32>;
+}
+
+/// Show a desktop notification.
+pub async fn send_notification(conn: &Connection, summary: &str, body: &str) -> zbus::Result<()> {
+  let notifications = NotificationsProxy::new(conn).await?;
+  notifications
+    .notify(
+      "Tailscale",
+      0,
+      "network-vpn-symbolic",
+      summary,
+      body,
+      &[],
+      HashMap::new(),
+      -1,
+    )
+    .await?;
+  Ok(())
+}
@@ -0,0 +1,234 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use regex::Regex;
+use serde_json::Value;
+use tokio::fs;
+use tokio::process::Command;
+use tracing::{instrument, warn};
+
+use crate::error::AppError;
+use crate::logging;
+use crate::logic::{get_tailscale_version, run_netcheck, TailscaleState};
+
+static IPV4_REGEX: LazyLock<Regex> =
+  LazyLock::new(|| Regex::new(r"\b\d{1,3}\.\d{1,3}\.\d{1,3}\.\d{1,3}\b").unwrap());
+
+static IPV6_REGEX: LazyLock<Regex> =
+  LazyLock::new(|| Regex::new(r"(?i)\b(?:[0-9a-f]{0,4}:){3,7}[0-9a-f]{0,4}\b").unwrap());
+
+static EMAIL_REGEX: LazyLock<Regex> =
+  LazyLock::new(|| Regex::new(r"[\w.+-]+@[\w-]+(?:\.[\w-]+)*").unwrap());
+
+static TAILNET_DNS_REGEX: LazyLock<Regex> =
+  LazyLock::new(|| Regex::new(r"[\w-]+(?:\.[\w-]+)*\.ts\.net").unwrap());
+
+/// Interactive login links; whoever opens one can log the device into their account.
+static LOGIN_URL_REGEX: LazyLock<Regex> =
+  LazyLock::new(|| Regex::new(r"https://\S+/a/[\w-]+").unwrap());
+
+/// Everything the applet knows that goes into a diagnostics bundle.
+pub struct DiagnosticsInput {
+  pub config: Value,
+  /// Where cosmic-config stores the config, and how writes to it went.
+  pub config_path: Option<PathBuf>,
+  pub config_write_failures: usize,
+  pub config_write_error: Option<String>,
+  pub snapshots: Vec<TailscaleState>,
+}
+
+/// Replaces addresses, accounts, and peer names with placeholders.
+struct Redactor {
+  /// Matches any peer or account name as a whole token; `None` without names.
+  names: Option<Regex>,
+  placeholders: HashMap<String, String>,
+}
+
+impl Redactor {
+  /// Build a redactor that also hides every peer and account name seen in `snapshots`.
+  fn new(snapshots: &[TailscaleState]) -> Self {
+    let mut seen = BTreeSet::new();
+    for state in snapshots {
+      let peers = state
+        .peers
+        .iter()
+        .map(|peer| peer.name.as_str())
+        .chain(state.subnet_routers.iter().map(|router| router.name.as_str()))
+        .chain(std::iter::once(state.current_acct.as_str()));
+      seen.extend(peers.filter(|name| !name.is_empty()).map(str::to_string));
+    }
+
+    // Try longer names first so one name containing another stays hidden.
+    let mut names: Vec<String> = seen.into_iter().collect();
+    names.sort_by_key(|name| std::cmp::Reverse(name.len()));
+
+    // Only whole tokens, so a peer called `pi` leaves "pipe" alone. Names
+    // starting or ending in punctuation can't be anchored on that side.
+    let word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    let pattern = names
+      .iter()
+      .map(|name| {
+        format!(
+          "{}{}{}",
+          if word(name.chars().next()) { r"\b" } else { "" },
+          regex::escape(name),
+          if word(name.chars().last()) { r"\b" } else { "" },
+        )
+      })
+      .collect::<Vec<_>>()
+      .join("|");
+    let names_regex = (!names.is_empty())
+      .then(|| Regex::new(&pattern))
+      .transpose()
+      .inspect_err(|e| warn!("Failed to build the peer name redactor: {e}"))
+      .ok()
+      .flatten();
+
+    Redactor {
+      names: names_regex,
+      placeholders: names
+        .into_iter()
+        .enumerate()
+        .map(|(idx, name)| (name, format!("peer-{}", idx + 1)))
+        .collect(),
+    }
+  }
+
+  fn redact(&self, text: &str) -> String {
+    let text = LOGIN_URL_REGEX.replace_all(text, "[login-url]");
+    let text = EMAIL_REGEX.replace_all(&text, "[email]");
+    let text = TAILNET_DNS_REGEX.replace_all(&text, "[tailnet-host]");
+    let text = IPV4_REGEX.replace_all(&text, "[ipv4]");
+    let text = IPV6_REGEX.replace_all(&text, "[ipv6]");
+
+    // One pass, so placeholders are never matched as names themselves.
+    match &self.names {
+      Some(names) => names
+        .replace_all(&text, |caps: &regex::Captures<'_>| {
+          self.placeholders.get(&caps[0]).cloned().unwrap_or_default()
+        })
+        .into_owned(),
+      None => text.into_owned(),
+    }
+  }
+}
+
+/// Drop free-form user data from the config before it is written out.
+fn scrub_config(mut config: Value) -> Value {
+  if let Some(notes) = config.get_mut("peer_notes") {
+    let count = notes.as_object().map_or(0, serde_json::Map::len);
+    *notes = Value::String(format!("<{count} notes omitted>"));
+  }
+  if let Some(macs) = config.get_mut("peer_macs") {
+    let count = macs.as_object().map_or(0, serde_json::Map::len);
+    *macs = Value::String(format!("<{count} MAC addresses omitted>"));
+  }
+  if let Some(favorites) = config.get_mut("favorite_peers") {
+    let count = favorites.as_array().map_or(0, Vec::len);
+    *favorites = Value::String(format!("<{count} favorites omitted>"));
+  }
+  config
+}
+
+fn unix_secs() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_secs())
+    .unwrap_or_default()
+}
+
+/// Collect applet and Tailscale diagnostics into a redacted `.tar.gz`.
+///
+/// The bundle is written to the Downloads directory (or home as a fallback)
+/// and its path is returned.
+#[instrument(level = "debug", skip_all)]
+pub async fn generate_bundle(input: DiagnosticsInput) -> Result<PathBuf, AppError> {
+  let redactor = Redactor::new(&input.snapshots);
+  let stamp = unix_secs();
+  let name = format!("gui-scale-applet-diagnostics-{stamp}");
+
+  // Files are written here before redaction is archived, so the directory is
+  // private (0700) and freshly created rather than a guessable path in /tmp.
+  let work_dir = tempfile::Builder::new()
+    .prefix(&format!("{name}-"))
+    .tempdir_in(dirs::runtime_dir().unwrap_or_else(std::env::temp_dir))?;
+
+  let version = get_tailscale_version()
+    .await
+    .unwrap_or_else(|e| format!("unavailable: {e}"));
+  let netcheck = run_netcheck()
+    .await
+    .unwrap_or_else(|e| format!("unavailable: {e}"));
+
+  let applet = format!(
+    "applet: {} {}\ngenerated: {stamp}\n",
+    env!("CARGO_PKG_NAME"),
+    env!("CARGO_PKG_VERSION")
+  );
+
+  let config_writes = format!(
+    "path: {}\nfailed writes: {}\nlast error: {}\n",
+    input
+      .config_path
+      .as_ref()
+      .map_or_else(|| "unknown".to_string(), |path| path.display().to_string()),
+    input.config_write_failures,
+    input.config_write_error.as_deref().unwrap_or("none"),
+  );
+
+  let files = [
+    ("applet.txt", applet),
+    (
+      "config.json",
+      serde_json::to_string_pretty(&scrub_config(input.config))?,
+    ),
+    (
+      "state-snapshots.json",
+      serde_json::to_string_pretty(&input.snapshots)?,
+    ),
+    ("config-writes.txt", config_writes),
+    ("recent-errors.log", logging::recent_errors().join("\n")),
+    ("tailscale-version.txt", version),
+    ("netcheck.txt", netcheck),
+  ];
+
+  for (file, contents) in &files {
+    fs::write(work_dir.path().join(file), redactor.redact(contents)).await?;
+  }
+
+  let out_dir = dirs::download_dir()
+    .or_else(dirs::home_dir)
+    .unwrap_or_else(std::env::temp_dir);
+  let archive = out_dir.join(format!("{name}.tar.gz"));
+
+  let result = create_archive(&archive, work_dir.path()).await;
+
+  if let Err(e) = work_dir.close() {
+    warn!("Failed to clean up diagnostics work dir: {e}");
+  }
+
+  result.map(|()| archive)
+}
+
+async fn create_archive(archive: &Path, dir: &Path) -> Result<(), AppError> {
+  let output = Command::new("tar")
+    .arg("-czf")
+    .arg(archive)
+    .arg("-C")
+    .arg(dir)
+    .arg(".")
+    .output()
+    .await?;
+
+  if !output.status.success() {
+    return Err(AppError::CliFailure(format!(
+      "tar exited with {}: {}",
+      output.status,
+      String::from_utf8_lossy(&output.stderr).trim()
+    )));
+  }
+
+  Ok(())
+}
@@ -13,4 +13,10 @@ pub enum AppError {
 
   #[error("CLI command failed: {0}")]
   CliFailure(String),
+
+  #[error("tailscaled is not running")]
+  DaemonNotRunning,
+
+  #[error("Permission denied (not the Tailscale operator): {0}")]
+  PermissionDenied(String),
 }
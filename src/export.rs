@@ -0,0 +1,103 @@
+use std::fmt::Write as _;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tokio::fs;
+use tracing::instrument;
+
+use crate::error::AppError;
+use crate::ids::PeerName;
+use crate::logic::Peer;
+
+/// File format for a peer list export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+  Csv,
+  Json,
+}
+
+impl ExportFormat {
+  fn extension(self) -> &'static str {
+    match self {
+      ExportFormat::Csv => "csv",
+      ExportFormat::Json => "json",
+    }
+  }
+}
+
+/// The inventory columns of one peer, shared by both formats.
+#[derive(Serialize)]
+struct PeerRecord<'a> {
+  name: &'a PeerName,
+  ips: &'a [IpAddr],
+  os: &'a str,
+  online: bool,
+  /// RFC 3339, or `None` if control never reported it.
+  last_seen: Option<String>,
+}
+
+impl<'a> From<&'a Peer> for PeerRecord<'a> {
+  fn from(peer: &'a Peer) -> Self {
+    PeerRecord {
+      name: &peer.name,
+      ips: &peer.ips,
+      os: &peer.os,
+      online: peer.online,
+      last_seen: peer
+        .last_seen
+        .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+        .map(|time| time.to_rfc3339()),
+    }
+  }
+}
+
+/// Write `peers` to the Downloads directory (or home as a fallback) and return
+/// the file's path.
+#[instrument(level = "debug", skip(peers))]
+pub async fn export_peers(peers: &[Peer], format: ExportFormat) -> Result<PathBuf, AppError> {
+  let records: Vec<PeerRecord<'_>> = peers.iter().map(PeerRecord::from).collect();
+  let contents = match format {
+    ExportFormat::Csv => to_csv(&records),
+    ExportFormat::Json => serde_json::to_string_pretty(&records)?,
+  };
+
+  let stamp = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_secs())
+    .unwrap_or_default();
+  let out_dir = dirs::download_dir()
+    .or_else(dirs::home_dir)
+    .unwrap_or_else(std::env::temp_dir);
+  let path = out_dir.join(format!("tailscale-peers-{stamp}.{}", format.extension()));
+  fs::write(&path, contents).await?;
+  Ok(path)
+}
+
+/// One row per peer, with multiple IPs separated by spaces in one field.
+fn to_csv(records: &[PeerRecord<'_>]) -> String {
+  let mut csv = String::from("name,ips,os,online,last_seen\n");
+  for record in records {
+    let ips: Vec<String> = record.ips.iter().map(IpAddr::to_string).collect();
+    let _ = writeln!(
+      csv,
+      "{},{},{},{},{}",
+      csv_field(record.name.as_str()),
+      csv_field(&ips.join(" ")),
+      csv_field(record.os),
+      record.online,
+      record.last_seen.as_deref().unwrap_or_default(),
+    );
+  }
+  csv
+}
+
+/// Quote a field if it contains a separator, quote or line break (RFC 4180).
+fn csv_field(value: &str) -> String {
+  if value.contains([',', '"', '\n', '\r']) {
+    format!("\"{}\"", value.replace('"', "\"\""))
+  } else {
+    value.to_string()
+  }
+}
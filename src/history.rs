@@ -0,0 +1,148 @@
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use tokio::sync::Mutex;
+use tracing::debug;
+
+use crate::error::AppError;
+
+/// Oldest records are dropped once the log holds this many transfers.
+const MAX_RECORDS: usize = 200;
+
+/// Serialises the log's read-modify-write cycles, so a send and a receive
+/// finishing together, or a clear during an append, don't lose records or
+/// share the temp file.
+static HISTORY_LOCK: Mutex<()> = Mutex::const_new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransferDirection {
+  Sent,
+  Received,
+}
+
+/// A file that was part of a transfer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferFile {
+  pub name: String,
+  pub size: u64,
+  pub ok: bool,
+}
+
+/// A completed Taildrop transfer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferRecord {
+  pub direction: TransferDirection,
+  /// Receiving device for sends; `tailscale file get` doesn't report senders.
+  pub peer: Option<String>,
+  pub files: Vec<TransferFile>,
+  /// Unix time in seconds.
+  pub timestamp: u64,
+  pub error: Option<String>,
+}
+
+impl TransferRecord {
+  pub fn new(
+    direction: TransferDirection,
+    peer: Option<String>,
+    files: Vec<TransferFile>,
+    error: Option<String>,
+  ) -> Self {
+    TransferRecord {
+      direction,
+      peer,
+      files,
+      timestamp: unix_secs(),
+      error,
+    }
+  }
+}
+
+pub fn unix_secs() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_secs())
+    .unwrap_or_default()
+}
+
+/// `~/.local/state/gui-scale-applet/transfers.json`
+fn history_path() -> Option<PathBuf> {
+  dirs::state_dir().map(|dir| dir.join("gui-scale-applet").join("transfers.json"))
+}
+
+/// Load the transfer log, oldest first. A missing log is an empty history.
+pub async fn load_history() -> Result<Vec<TransferRecord>, AppError> {
+  let _guard = HISTORY_LOCK.lock().await;
+  read_history().await
+}
+
+/// Read the log; callers hold `HISTORY_LOCK`.
+async fn read_history() -> Result<Vec<TransferRecord>, AppError> {
+  let Some(path) = history_path() else {
+    return Ok(Vec::new());
+  };
+
+  match fs::read_to_string(&path).await {
+    Ok(contents) => Ok(serde_json::from_str(&contents)?),
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+    Err(e) => Err(e.into()),
+  }
+}
+
+/// Atomically replace the transfer log; callers hold `HISTORY_LOCK`.
+async fn write_history(records: &[TransferRecord]) -> Result<(), AppError> {
+  let Some(path) = history_path() else {
+    debug!("No XDG state directory, transfer history is not saved");
+    return Ok(());
+  };
+
+  if let Some(dir) = path.parent() {
+    fs::create_dir_all(dir).await?;
+  }
+
+  let tmp = path.with_extension(format!("json.{}", std::process::id()));
+  fs::write(&tmp, serde_json::to_vec(records)?).await?;
+  fs::rename(&tmp, &path).await?;
+  Ok(())
+}
+
+/// Append `record` to the log, dropping the oldest entries past the cap.
+///
+/// Returns the updated history.
+pub async fn append_record(record: TransferRecord) -> Result<Vec<TransferRecord>, AppError> {
+  let _guard = HISTORY_LOCK.lock().await;
+  let mut records = read_history().await?;
+  records.push(record);
+
+  let excess = records.len().saturating_sub(MAX_RECORDS);
+  records.drain(..excess);
+
+  write_history(&records).await?;
+  Ok(records)
+}
+
+/// Delete every record.
+pub async fn clear_history() -> Result<(), AppError> {
+  let _guard = HISTORY_LOCK.lock().await;
+  write_history(&[]).await
+}
+
+/// Human-readable size such as `1.5 MB`.
+#[allow(clippy::cast_precision_loss)]
+pub fn format_size(bytes: u64) -> String {
+  const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+  let mut size = bytes as f64;
+  let mut unit = 0;
+  while size >= 1000.0 && unit < UNITS.len() - 1 {
+    size /= 1000.0;
+    unit += 1;
+  }
+
+  if unit == 0 {
+    format!("{bytes} B")
+  } else {
+    format!("{size:.1} {}", UNITS[unit])
+  }
+}
@@ -9,7 +9,7 @@ use rust_embed::RustEmbed;
 
 pub fn init(requested_languages: &[LanguageIdentifier]) {
   if let Err(why) = localizer().select(requested_languages) {
-    eprintln!("error while loading fluent localizations: {why}");
+    tracing::error!("error while loading fluent localizations: {why}");
   }
 }
 
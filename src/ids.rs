@@ -0,0 +1,69 @@
+//! Distinct types for the identifiers Tailscale hands out, so a peer name can't
+//! be passed where a node ID or profile ID is expected.
+
+use serde::{Deserialize, Serialize};
+use std::borrow::Borrow;
+use std::fmt;
+
+/// Defines a newtype over `String` that serializes as the bare string and can
+/// be looked up in maps by `&str`.
+macro_rules! string_id {
+  ($(#[$doc:meta])* $name:ident) => {
+    $(#[$doc])*
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+    #[serde(transparent)]
+    pub struct $name(String);
+
+    // Not every ID type needs every helper.
+    #[allow(dead_code)]
+    impl $name {
+      pub fn as_str(&self) -> &str {
+        &self.0
+      }
+
+      pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+      }
+    }
+
+    impl From<String> for $name {
+      fn from(id: String) -> Self {
+        $name(id)
+      }
+    }
+
+    impl From<&str> for $name {
+      fn from(id: &str) -> Self {
+        $name(id.to_string())
+      }
+    }
+
+    impl Borrow<str> for $name {
+      fn borrow(&self) -> &str {
+        &self.0
+      }
+    }
+
+    impl fmt::Display for $name {
+      fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+      }
+    }
+  };
+}
+
+string_id!(
+  /// Stable node ID from the status JSON (`ID`), unchanged when a peer is renamed.
+  NodeId
+);
+
+string_id!(
+  /// A peer's short hostname as shown in lists and used by `tailscale file cp`.
+  PeerName
+);
+
+#[cfg(feature = "accounts")]
+string_id!(
+  /// Login profile ID from `tailscale switch --list`.
+  ProfileId
+);
@@ -0,0 +1,149 @@
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::fs::{self, File, OpenOptions};
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::{
+  fmt, fmt::format::FmtSpan, layer::Context, layer::SubscriberExt, util::SubscriberInitExt,
+  EnvFilter, Layer,
+};
+
+/// Filter used when `RUST_LOG` is unset.
+const DEFAULT_FILTER: &str = "warn,gui_scale_applet=info";
+/// Filter used when `RUST_LOG` is unset and verbose logging is on.
+const VERBOSE_FILTER: &str = "warn,gui_scale_applet=debug";
+/// The log file is rotated to `applet.log.1` once it grows past this size.
+const MAX_LOG_SIZE: u64 = 1024 * 1024;
+/// Number of warnings and errors kept in memory for diagnostics.
+const MAX_RECENT_ERRORS: usize = 50;
+
+static RECENT_ERRORS: LazyLock<Mutex<VecDeque<String>>> =
+  LazyLock::new(|| Mutex::new(VecDeque::with_capacity(MAX_RECENT_ERRORS)));
+
+/// Most recent warnings and errors, oldest first.
+pub fn recent_errors() -> Vec<String> {
+  RECENT_ERRORS
+    .lock()
+    .unwrap_or_else(PoisonError::into_inner)
+    .iter()
+    .cloned()
+    .collect()
+}
+
+/// Formats an event's message followed by its other fields.
+#[derive(Default)]
+struct EventFormatter {
+  message: String,
+  fields: String,
+}
+
+impl Visit for EventFormatter {
+  fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+    if field.name() == "message" {
+      let _ = write!(self.message, "{value:?}");
+    } else {
+      let _ = write!(self.fields, " {}={value:?}", field.name());
+    }
+  }
+}
+
+/// Keeps the last warnings and errors in memory for the diagnostics bundle.
+struct RecentErrorsLayer;
+
+impl<S: Subscriber> Layer<S> for RecentErrorsLayer {
+  fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+    let meta = event.metadata();
+    if *meta.level() > Level::WARN {
+      return;
+    }
+
+    let mut formatter = EventFormatter::default();
+    event.record(&mut formatter);
+
+    let secs = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|d| d.as_secs())
+      .unwrap_or_default();
+    let line = format!(
+      "[{secs}] {} {}: {}{}",
+      meta.level(),
+      meta.target(),
+      formatter.message,
+      formatter.fields
+    );
+
+    let mut errors = RECENT_ERRORS.lock().unwrap_or_else(PoisonError::into_inner);
+    if errors.len() == MAX_RECENT_ERRORS {
+      errors.pop_front();
+    }
+    errors.push_back(line);
+  }
+}
+
+/// Directory holding the optional log file (`~/.local/state/gui-scale-applet`).
+pub fn log_dir() -> Option<PathBuf> {
+  dirs::state_dir().map(|dir| dir.join("gui-scale-applet"))
+}
+
+/// Open the log file, rotating the previous one if it has grown too large.
+fn open_log_file() -> std::io::Result<File> {
+  let dir = log_dir().ok_or_else(|| std::io::Error::other("no XDG state directory"))?;
+  fs::create_dir_all(&dir)?;
+
+  let path = dir.join("applet.log");
+  if fs::metadata(&path).is_ok_and(|meta| meta.len() > MAX_LOG_SIZE) {
+    fs::rename(&path, dir.join("applet.log.1"))?;
+  }
+
+  OpenOptions::new().create(true).append(true).open(&path)
+}
+
+/// Install the global tracing subscriber.
+///
+/// Honors `RUST_LOG` and always logs to stderr; with `log_to_file` the same
+/// events are also appended to `applet.log` in [`log_dir`]. With `verbose`,
+/// backend calls and their command lines are logged, and every closed span
+/// reports how long it took.
+pub fn init(log_to_file: bool, verbose: bool) {
+  let default_filter = if verbose { VERBOSE_FILTER } else { DEFAULT_FILTER };
+  let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default_filter));
+  let span_events = if verbose { FmtSpan::CLOSE } else { FmtSpan::NONE };
+
+  let mut file_error = None;
+  let file_layer = if log_to_file {
+    match open_log_file() {
+      Ok(file) => Some(
+        fmt::layer()
+          .with_ansi(false)
+          .with_span_events(span_events.clone())
+          .with_writer(Mutex::new(file))
+          .boxed(),
+      ),
+      Err(e) => {
+        file_error = Some(e);
+        None
+      }
+    }
+  } else {
+    None
+  };
+
+  tracing_subscriber::registry()
+    .with(filter)
+    .with(
+      fmt::layer()
+        .with_span_events(span_events)
+        .with_writer(std::io::stderr),
+    )
+    .with(file_layer)
+    .with(RecentErrorsLayer)
+    .init();
+
+  if let Some(e) = file_error {
+    tracing::warn!("Failed to open log file: {e}");
+  }
+}
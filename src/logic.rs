@@ -1,118 +1,693 @@
+#[cfg(feature = "taildrop")]
+use std::collections::HashSet;
+#[cfg(feature = "taildrop")]
+use std::ffi::OsString;
+use std::net::IpAddr;
+use std::path::Path;
+#[cfg(feature = "taildrop")]
 use std::path::PathBuf;
-use std::sync::LazyLock;
+use std::process::Stdio;
+use std::sync::{LazyLock, PoisonError, RwLock};
+use std::time::Duration;
 
-use regex::Regex;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
-use tracing::{debug, error, warn};
+#[cfg(feature = "taildrop")]
+use tracing::error;
+use tracing::{debug, instrument, warn};
 
 use crate::error::AppError;
 use crate::fl;
+#[cfg(feature = "accounts")]
+use crate::ids::ProfileId;
+use crate::ids::{NodeId, PeerName};
+#[cfg(feature = "taildrop")]
+use crate::history::TransferFile;
 
-static IP_REGEX: LazyLock<Regex> =
-  LazyLock::new(|| Regex::new(r"\d{1,3}\.\d{1,3}\.\d{1,3}\.\d{1,3}").unwrap());
+const DEFAULT_CLI_PATH: &str = "tailscale";
+/// Where distributions, and NixOS, install the tailscale CLI. Commands run as
+/// root use the first that exists rather than the configurable path.
+const SYSTEM_CLI_PATHS: [&str; 4] = [
+  "/usr/bin/tailscale",
+  "/usr/sbin/tailscale",
+  "/usr/local/bin/tailscale",
+  "/run/current-system/sw/bin/tailscale",
+];
+/// Where the CLI looks for `tailscaled` unless `--socket` is given.
+const DEFAULT_DAEMON_SOCKET: &str = "/var/run/tailscale/tailscaled.sock";
 
-static HOSTNAME_REGEX: LazyLock<Regex> =
-  LazyLock::new(|| Regex::new(r"\w+\.[\w.]+\.ts\.net").unwrap());
+/// How the tailscale CLI is invoked: the program plus any leading arguments.
+#[derive(Debug, Clone)]
+struct CliSettings {
+  path: String,
+  extra_args: Vec<String>,
+}
+
+/// Serializes commands that change Tailscale state. The lock is fair, so queued
+/// mutations run in the order they were issued instead of racing each other.
+static MUTATION_QUEUE: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+static CLI_SETTINGS: LazyLock<RwLock<CliSettings>> = LazyLock::new(|| {
+  RwLock::new(CliSettings {
+    path: DEFAULT_CLI_PATH.to_string(),
+    extra_args: Vec::new(),
+  })
+});
+
+/// Point every CLI call at `path`, prefixing `extra_args` before the subcommand.
+///
+/// An empty path falls back to `tailscale` on `PATH`. Wrappers such as
+/// `flatpak-spawn` are configured as the path with `--host tailscale` as the
+/// extra arguments.
+pub fn configure_cli(path: &str, extra_args: &[String]) {
+  let path = path.trim();
+  let settings = CliSettings {
+    path: if path.is_empty() {
+      DEFAULT_CLI_PATH.to_string()
+    } else {
+      path.to_string()
+    },
+    extra_args: extra_args.to_vec(),
+  };
+
+  debug!("Using tailscale CLI: {} {:?}", settings.path, settings.extra_args);
+  *CLI_SETTINGS.write().unwrap_or_else(PoisonError::into_inner) = settings;
+}
+
+fn cli_settings() -> CliSettings {
+  CLI_SETTINGS
+    .read()
+    .unwrap_or_else(PoisonError::into_inner)
+    .clone()
+}
+
+/// Socket the CLI talks to `tailscaled` on: `--socket` from the extra CLI
+/// arguments, or the default.
+pub fn daemon_socket() -> String {
+  let settings = cli_settings();
+  let mut args = settings.extra_args.iter();
+  while let Some(arg) = args.next() {
+    let arg = arg.trim_start_matches('-');
+    if let Some(path) = arg.strip_prefix("socket=") {
+      return path.to_string();
+    }
+    if arg == "socket"
+      && let Some(path) = args.next()
+    {
+      return path.clone();
+    }
+  }
+  DEFAULT_DAEMON_SOCKET.to_string()
+}
+
+/// Build a `Command` for the configured tailscale CLI.
+fn tailscale_command() -> Command {
+  let settings = cli_settings();
+  let mut cmd = Command::new(&settings.path);
+  // Dropping a cancelled command's future shouldn't leave the process running.
+  cmd.args(&settings.extra_args).kill_on_drop(true);
+  cmd
+}
+
+/// Capability granted when the tailnet allows Taildrop.
+#[cfg(feature = "taildrop")]
+pub const CAP_FILE_SHARING: &str = "https://tailscale.com/cap/file-sharing";
+
+/// Node attribute granted when the tailnet has the Mullvad exit node add-on.
+pub const CAP_MULLVAD: &str = "mullvad";
+
+/// Node attribute granted when the tailnet's policy lets this node use Funnel.
+pub const CAP_FUNNEL: &str = "funnel";
+
+/// Where the daemon is in its lifecycle, from `BackendState` in the status JSON.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BackendState {
+  /// `tailscaled` isn't running or hasn't loaded a profile yet.
+  #[default]
+  NoState,
+  NeedsLogin,
+  /// Logged in, waiting for an admin to approve the device.
+  NeedsMachineAuth,
+  /// Logged in with the connection turned off.
+  Stopped,
+  Starting,
+  Running,
+}
+
+impl BackendState {
+  fn parse(state: &str) -> Self {
+    match state {
+      "NeedsLogin" => BackendState::NeedsLogin,
+      "NeedsMachineAuth" => BackendState::NeedsMachineAuth,
+      "Stopped" => BackendState::Stopped,
+      "Starting" => BackendState::Starting,
+      "Running" => BackendState::Running,
+      _ => BackendState::NoState,
+    }
+  }
+
+  /// Logged in and approved, so the connection can be turned on and off.
+  pub fn logged_in(self) -> bool {
+    matches!(
+      self,
+      BackendState::Stopped | BackendState::Starting | BackendState::Running
+    )
+  }
+}
 
 /// All Tailscale state fetched in one batch.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(clippy::struct_excessive_bools)]
 pub struct TailscaleState {
-  pub ip: String,
+  /// Tailscale addresses of this device, IPv4 first.
+  pub ips: Vec<IpAddr>,
+  /// The connection is wanted up (`WantRunning`), which `backend_state` lags behind.
   pub connected: bool,
+  pub backend_state: BackendState,
   pub ssh_enabled: bool,
   pub routes_enabled: bool,
+  /// DNS settings from the tailnet are applied (`CorpDNS`, `--accept-dns`).
+  pub accept_dns: bool,
   pub is_exit_node: bool,
-  pub devices: Vec<String>,
-  pub exit_nodes: Vec<String>,
-  pub acct_list: Vec<String>,
+  /// Subnet routes this node advertises (exit node routes excluded).
+  pub advertised_routes: Vec<String>,
+  pub advanced: AdvancedPrefs,
   pub current_acct: String,
+  /// Name of the tailnet this node is on (`CurrentTailnet.Name`), empty if unknown.
+  pub tailnet: String,
+  /// Code of this node's home DERP relay region (`Self.Relay`), e.g. `fra`.
+  pub relay: String,
+  pub subnet_routers: Vec<SubnetRouter>,
+  /// Every peer in the status JSON, sorted by name.
+  pub peers: Vec<Peer>,
+  pub daemon_running: bool,
+  pub is_operator: bool,
+  /// User set with `--operator`, empty when unset or unknown.
+  pub operator_user: String,
+  /// Version of the running `tailscaled`, which may differ from the CLI's.
+  pub daemon_version: String,
+  /// Newer stable version that control says is available, `None` when up to date.
+  pub update_available: Option<String>,
+  /// Tailscale detected a captive portal on the current network.
+  pub captive_portal: bool,
+  /// Warnings from the status `Health` array, as printed by the CLI.
+  pub health: Vec<String>,
+  /// `status --json` couldn't be understood; only connection and IPs are known.
+  pub limited: bool,
+  /// The node is logged out (`BackendState` is `NeedsLogin`) or its key expired.
+  pub needs_login: bool,
+  /// This node's key has expired and it must re-authenticate.
+  pub key_expired: bool,
+  /// Unix time at which this node's key expires, `None` if expiry is disabled
+  /// or unknown.
+  pub key_expiry: Option<i64>,
+  /// Key expiry is disabled for this node, as for servers and tagged devices.
+  pub key_expiry_disabled: bool,
+  /// Login URL of an interactive login already in progress.
+  pub auth_url: Option<String>,
+  /// Peer currently used as the exit node, empty for none.
+  pub exit_node: String,
+  /// This node's DNS name without the trailing dot, empty if unknown.
+  pub dns_name: String,
+  /// Tailnet capabilities granted to this node. Empty when the daemon doesn't
+  /// report them, in which case no feature is gated.
+  pub capabilities: Vec<String>,
+}
+
+/// A peer from `Peer` in the status JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Peer {
+  #[serde(default)]
+  pub id: NodeId,
+  pub name: PeerName,
+  /// Full machine name without the trailing dot, e.g. `laptop.tail1234.ts.net`;
+  /// empty if the tailnet has none.
+  #[serde(default)]
+  pub dns_name: String,
+  #[serde(default)]
+  pub ips: Vec<IpAddr>,
+  #[serde(default)]
+  pub os: String,
+  /// Login name of the user the peer belongs to, empty if unknown.
+  #[serde(default)]
+  pub owner: String,
+  /// ACL tags such as `tag:server`; empty for devices owned by a user.
+  #[serde(default)]
+  pub tags: Vec<String>,
+  /// Runs Tailscale SSH, going by the host keys it advertises.
+  #[serde(default)]
+  pub ssh: bool,
+  /// Offers to be an exit node and is allowed to be used as one.
+  #[serde(default)]
+  pub exit_node_option: bool,
+  /// The peer's node key has expired, so it can't connect until re-authenticated.
+  #[serde(default)]
+  pub expired: bool,
+  /// Endpoint of the direct connection, e.g. `203.0.113.5:41641`; empty while
+  /// traffic goes through a relay or there is no connection.
+  #[serde(default)]
+  pub cur_addr: String,
+  /// Code of the peer's home DERP region, which relays its traffic when there
+  /// is no direct connection.
+  #[serde(default)]
+  pub relay: String,
+  pub online: bool,
+  /// Traffic was recently exchanged with the peer.
+  pub active: bool,
+  /// Unix time of the last handshake, `None` if there never was one.
+  pub last_handshake: Option<i64>,
+  /// Unix time the peer was last connected to control, `None` if unknown.
+  #[serde(default)]
+  pub last_seen: Option<i64>,
+  /// Logged in as the same user as this device.
+  pub mine: bool,
+  /// Bytes received from and sent to the peer since `tailscaled` started.
+  pub rx_bytes: u64,
+  pub tx_bytes: u64,
+}
+
+impl Peer {
+  /// Target for `tailscale file cp`: the full machine name, which never has
+  /// spaces or other characters the display name may have, or else the first
+  /// Tailscale IP, which may be IPv6 on IPv6-only tailnets.
+  #[cfg(feature = "taildrop")]
+  pub fn taildrop_target(&self) -> Option<String> {
+    if self.dns_name.is_empty() {
+      self.ips.first().map(IpAddr::to_string)
+    } else {
+      Some(self.dns_name.clone())
+    }
+  }
+}
+
+/// An exit node at a known location, such as one of Mullvad's servers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocatedExitNode {
+  pub name: String,
+  pub country: String,
+  pub city: String,
+  /// Preference among nodes in the same city; higher is better.
+  pub priority: i64,
+}
+
+/// A peer advertising subnet routes that this device can use.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubnetRouter {
+  pub name: PeerName,
+  pub routes: Vec<String>,
+  pub online: bool,
+  /// Whether this device currently routes through the peer.
+  pub primary: bool,
+}
+
+impl TailscaleState {
+  /// State reported when `tailscaled` can't be reached.
+  fn daemon_stopped() -> Self {
+    TailscaleState {
+      ips: Vec::new(),
+      connected: false,
+      backend_state: BackendState::NoState,
+      ssh_enabled: false,
+      routes_enabled: false,
+      accept_dns: false,
+      is_exit_node: false,
+      advertised_routes: Vec::new(),
+      advanced: AdvancedPrefs::default(),
+      current_acct: String::new(),
+      tailnet: String::new(),
+      relay: String::new(),
+      subnet_routers: Vec::new(),
+      peers: Vec::new(),
+      daemon_running: false,
+      is_operator: true,
+      operator_user: String::new(),
+      daemon_version: String::new(),
+      update_available: None,
+      captive_portal: false,
+      health: Vec::new(),
+      limited: false,
+      needs_login: false,
+      key_expired: false,
+      key_expiry: None,
+      key_expiry_disabled: false,
+      auth_url: None,
+      exit_node: String::new(),
+      dns_name: String::new(),
+      capabilities: Vec::new(),
+    }
+  }
+
+  /// Minimal state used when `status --json` is missing or malformed.
+  fn limited(ips: Vec<IpAddr>, connected: bool) -> Self {
+    TailscaleState {
+      ips,
+      connected,
+      // Without status, assume the daemon does what was asked of it.
+      backend_state: if connected {
+        BackendState::Running
+      } else {
+        BackendState::Stopped
+      },
+      daemon_running: true,
+      limited: true,
+      ..Self::daemon_stopped()
+    }
+  }
 }
 
 /// Parsed preferences from `tailscale debug prefs`.
+#[derive(Default)]
 #[allow(clippy::struct_excessive_bools)]
 struct TailscalePrefs {
   want_running: bool,
   run_ssh: bool,
   route_all: bool,
+  corp_dns: bool,
   is_exit_node: bool,
+  /// Subnet routes advertised by this node, without the exit node default routes.
+  advertise_routes: Vec<String>,
+  operator_user: Option<String>,
+  advanced: AdvancedPrefs,
+}
+
+/// Less common preferences shown in the Advanced section.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct AdvancedPrefs {
+  /// The web interface on port 5252 is served (`RunWebClient`).
+  pub web_client: bool,
+  /// Updates are installed automatically (`AutoUpdate.Apply`).
+  pub auto_update: bool,
+  /// Device posture data is shared with the tailnet (`PostureChecking`).
+  pub posture_checking: bool,
+  /// `on`, `nodivert` or `off`; empty when not reported. Read-only, as changing
+  /// it needs `tailscale up`.
+  pub netfilter_mode: String,
+}
+
+/// An advanced preference that can be toggled with `tailscale set`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdvancedPref {
+  WebClient,
+  AutoUpdate,
+  PostureChecking,
+}
+
+impl AdvancedPref {
+  /// Name of the `tailscale set` flag.
+  pub fn flag(self) -> &'static str {
+    match self {
+      AdvancedPref::WebClient => "webclient",
+      AdvancedPref::AutoUpdate => "auto-update",
+      AdvancedPref::PostureChecking => "posture-checking",
+    }
+  }
+}
+
+impl AdvancedPrefs {
+  pub fn get(&self, pref: AdvancedPref) -> bool {
+    match pref {
+      AdvancedPref::WebClient => self.web_client,
+      AdvancedPref::AutoUpdate => self.auto_update,
+      AdvancedPref::PostureChecking => self.posture_checking,
+    }
+  }
+
+  pub fn set(&mut self, pref: AdvancedPref, enabled: bool) {
+    match pref {
+      AdvancedPref::WebClient => self.web_client = enabled,
+      AdvancedPref::AutoUpdate => self.auto_update = enabled,
+      AdvancedPref::PostureChecking => self.posture_checking = enabled,
+    }
+  }
 }
 
 /// Fetch all preferences from a single `tailscale debug prefs` call.
 async fn fetch_tailscale_prefs() -> Result<TailscalePrefs, AppError> {
   let output = run_tailscale_cmd(&["debug", "prefs"]).await?;
   let prefs: Value = serde_json::from_str(&output)?;
+  let routes: Vec<&str> = prefs
+    .get("AdvertiseRoutes")
+    .and_then(Value::as_array)
+    .map(|routes| routes.iter().filter_map(Value::as_str).collect())
+    .unwrap_or_default();
 
   Ok(TailscalePrefs {
     want_running: prefs.get("WantRunning").and_then(Value::as_bool).unwrap_or(false),
     run_ssh: prefs.get("RunSSH").and_then(Value::as_bool).unwrap_or(false),
     route_all: prefs.get("RouteAll").and_then(Value::as_bool).unwrap_or(false),
-    is_exit_node: prefs
-      .get("AdvertiseRoutes")
-      .is_some_and(|v| !v.is_null() && v.as_str() != Some("")),
+    corp_dns: prefs.get("CorpDNS").and_then(Value::as_bool).unwrap_or(false),
+    // Advertising an exit node adds the default routes to `AdvertiseRoutes`.
+    is_exit_node: routes.iter().any(|route| is_default_route(route)),
+    advertise_routes: routes
+      .into_iter()
+      .filter(|route| !is_default_route(route))
+      .map(str::to_string)
+      .collect(),
+    advanced: AdvancedPrefs {
+      web_client: prefs.get("RunWebClient").and_then(Value::as_bool).unwrap_or(false),
+      auto_update: prefs
+        .get("AutoUpdate")
+        .and_then(|auto_update| auto_update.get("Apply"))
+        .and_then(Value::as_bool)
+        .unwrap_or(false),
+      posture_checking: prefs.get("PostureChecking").and_then(Value::as_bool).unwrap_or(false),
+      netfilter_mode: match prefs.get("NetfilterMode").and_then(Value::as_u64) {
+        Some(0) => "off",
+        Some(1) => "nodivert",
+        Some(2) => "on",
+        _ => "",
+      }
+      .to_string(),
+    },
+    operator_user: Some(
+      prefs
+        .get("OperatorUser")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string(),
+    ),
   })
 }
 
-/// Fetch all Tailscale state in one async batch.
-pub async fn fetch_tailscale_state() -> Result<TailscaleState, AppError> {
-  let ip = get_tailscale_ip().await.unwrap_or_else(|e| {
-    warn!("Failed to get IP: {e}");
-    fl!("not-available")
-  });
+/// Name of the user running the applet.
+fn current_user() -> Option<String> {
+  std::env::var("USER")
+    .or_else(|_| std::env::var("LOGNAME"))
+    .ok()
+    .filter(|user| !user.is_empty())
+}
 
-  let prefs = fetch_tailscale_prefs().await.unwrap_or_else(|e| {
-    warn!("Failed to fetch prefs: {e}");
-    TailscalePrefs {
-      want_running: false,
-      run_ssh: false,
-      route_all: false,
-      is_exit_node: false,
+/// Whether the current user may change prefs without elevation.
+///
+/// Unknown operator state (prefs unavailable) is treated as permitted so the
+/// read-only hint only shows when we know it applies.
+fn user_is_operator(operator_user: Option<&str>) -> bool {
+  let Some(operator) = operator_user else {
+    return true;
+  };
+
+  match current_user() {
+    Some(user) => user == "root" || user == operator,
+    None => true,
+  }
+}
+
+/// Exit nodes the popup offers, fetched apart from the rest of the state
+/// since only the popup needs them.
+#[derive(Debug, Clone)]
+pub struct ExitNodeLists {
+  /// "None", the tailnet's own exit nodes, then `located` by name.
+  pub names: Vec<String>,
+  pub located: Vec<LocatedExitNode>,
+}
+
+/// List the exit nodes this device can use; while it is an exit node itself,
+/// the list only says so.
+#[instrument(level = "debug", err(level = "debug"))]
+pub async fn fetch_exit_node_lists() -> Result<ExitNodeLists, AppError> {
+  let (status, prefs) = tokio::join!(fetch_status_json(), fetch_tailscale_prefs());
+  let status = status?;
+  if prefs.is_ok_and(|prefs| prefs.is_exit_node) {
+    return Ok(ExitNodeLists {
+      names: vec![fl!("exit-node-is-host")],
+      located: Vec::new(),
+    });
+  }
+  let (names, located) = parse_exit_nodes(&status);
+  Ok(ExitNodeLists { names, located })
+}
+
+/// Fetch the connection, preferences and peers in one async batch.
+///
+/// Exit node lists are fetched separately by `fetch_exit_node_lists`; the
+/// panel doesn't need them until the popup opens.
+#[instrument(level = "debug", err(level = "debug"))]
+pub async fn fetch_tailscale_state() -> Result<TailscaleState, AppError> {
+  let status = match fetch_status_json().await {
+    // Older or partial output can parse as JSON without the fields we rely on.
+    Ok(status) if status.get("BackendState").is_some() => Some(status),
+    Ok(_) => {
+      warn!("Status output has no BackendState, showing limited information");
+      None
     }
-  });
+    Err(AppError::DaemonNotRunning) => {
+      warn!("tailscaled is not running");
+      return Ok(TailscaleState::daemon_stopped());
+    }
+    Err(e) => {
+      warn!("Failed to fetch status, showing limited information: {e}");
+      None
+    }
+  };
 
-  let devices = get_tailscale_devices().await.unwrap_or_else(|e| {
-    warn!("Failed to get devices: {e}");
-    vec!["Select".to_string()]
+  let ips = get_tailscale_ips().await.unwrap_or_else(|e| {
+    warn!("Failed to get IPs: {e}");
+    Vec::new()
   });
 
-  let exit_nodes = if prefs.is_exit_node {
-    vec![fl!("exit-node-is-host")]
-  } else {
-    get_avail_exit_nodes().await.unwrap_or_else(|e| {
-      warn!("Failed to get exit nodes: {e}");
-      vec!["None".to_string()]
-    })
+  let prefs = fetch_tailscale_prefs().await;
+
+  let Some(status) = status else {
+    let connected = prefs.map_or_else(
+      |e| {
+        warn!("Failed to fetch prefs: {e}");
+        !ips.is_empty()
+      },
+      |prefs| prefs.want_running,
+    );
+    return Ok(TailscaleState::limited(ips, connected));
   };
 
-  let acct_list = get_acct_list().await.unwrap_or_default();
-  let current_acct = get_current_acct().await.unwrap_or_default();
+  let prefs = prefs.unwrap_or_else(|e| {
+    warn!("Failed to fetch prefs: {e}");
+    TailscalePrefs::default()
+  });
+
+  let dns_name = parse_current_acct(&status);
+  let subnet_routers = parse_subnet_routers(&status);
+  let peers = parse_peers(&status);
+  let captive_portal = parse_captive_portal(&status);
+  let health = parse_health(&status);
+  let exit_node = parse_exit_node(&status);
+  let capabilities = parse_capabilities(&status);
+  let key_expired = status
+    .get("Self")
+    .and_then(|me| me.get("Expired"))
+    .and_then(Value::as_bool)
+    .unwrap_or(false);
+  let key_expiry = parse_key_expiry(&status);
+  let backend_state = parse_backend_state(&status);
+  let needs_login = key_expired || backend_state == BackendState::NeedsLogin;
 
   Ok(TailscaleState {
-    ip,
+    ips,
     connected: prefs.want_running,
+    backend_state,
     ssh_enabled: prefs.run_ssh,
     routes_enabled: prefs.route_all,
+    accept_dns: prefs.corp_dns,
     is_exit_node: prefs.is_exit_node,
-    devices,
-    exit_nodes,
-    acct_list,
-    current_acct,
+    advertised_routes: prefs.advertise_routes,
+    advanced: prefs.advanced,
+    // The account is shown as this node's DNS name.
+    current_acct: dns_name.clone(),
+    tailnet: parse_tailnet(&status),
+    relay: parse_relay(&status),
+    subnet_routers,
+    peers,
+    daemon_running: true,
+    is_operator: user_is_operator(prefs.operator_user.as_deref()),
+    operator_user: prefs.operator_user.unwrap_or_default(),
+    daemon_version: parse_daemon_version(&status),
+    update_available: parse_update_available(&status),
+    captive_portal,
+    health,
+    limited: false,
+    needs_login,
+    key_expired,
+    key_expiry,
+    // `KeyExpiry` is omitted from `Self` when expiry is disabled, but also
+    // before the node is logged in, so only trust its absence while running.
+    key_expiry_disabled: backend_state == BackendState::Running
+      && !needs_login
+      && status.get("Self").is_some_and(|me| me.get("KeyExpiry").is_none()),
+    auth_url: parse_auth_url(&status),
+    exit_node,
+    dns_name,
+    capabilities,
   })
 }
 
+/// Command line for logs and errors, with anything that could identify a user, peer, or
+/// file replaced: flag values other than booleans, and positional arguments
+/// after the first `keep`.
+fn sanitize_args(args: &[&str], keep: usize) -> String {
+  let mut positional = 0;
+  args
+    .iter()
+    .map(|arg| {
+      if let Some((flag, value)) = arg.split_once('=')
+        && flag.starts_with('-')
+      {
+        return if matches!(value, "" | "true" | "false") {
+          (*arg).to_string()
+        } else {
+          format!("{flag}=[redacted]")
+        };
+      }
+      if arg.starts_with('-') {
+        return (*arg).to_string();
+      }
+      positional += 1;
+      if positional <= keep {
+        (*arg).to_string()
+      } else {
+        "[redacted]".to_string()
+      }
+    })
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+/// Number of leading words naming the subcommand, e.g. `exit-node list`.
+fn subcommand_len(args: &[&str]) -> usize {
+  match args.first() {
+    Some(&("debug" | "exit-node" | "file")) => 2,
+    _ => 1,
+  }
+}
+
 /// Run a tailscale CLI command and return stdout, checking the exit code.
+#[instrument(
+  level = "debug",
+  skip_all,
+  fields(cmd = %sanitize_args(args, subcommand_len(args))),
+  err(level = "debug")
+)]
 async fn run_tailscale_cmd(args: &[&str]) -> Result<String, AppError> {
-  let output = Command::new("tailscale")
+  let started = std::time::Instant::now();
+  let output = tailscale_command()
     .args(args)
     .output()
     .await?;
+  debug!(status = %output.status, elapsed_ms = started.elapsed().as_millis(), "tailscale exited");
 
   if !output.status.success() {
     let stderr = String::from_utf8_lossy(&output.stderr);
+    if stderr.contains("failed to connect to local tailscaled")
+      || stderr.contains("doesn't appear to be running")
+    {
+      return Err(AppError::DaemonNotRunning);
+    }
+    if stderr.to_lowercase().contains("access denied") {
+      return Err(AppError::PermissionDenied(stderr.trim().to_string()));
+    }
     return Err(AppError::CliFailure(format!(
       "tailscale {} exited with {}: {}",
-      args.join(" "),
+      sanitize_args(args, subcommand_len(args)),
       output.status,
       stderr.trim()
     )));
@@ -121,96 +696,988 @@ async fn run_tailscale_cmd(args: &[&str]) -> Result<String, AppError> {
   Ok(String::from_utf8(output.stdout)?)
 }
 
-/// Get the IPv4 address assigned to this computer.
-pub async fn get_tailscale_ip() -> Result<String, AppError> {
-  let ip = run_tailscale_cmd(&["ip", "-4"]).await?;
-  Ok(ip.trim().to_string())
+/// Run a state-changing tailscale command once earlier mutations have finished.
+async fn run_mutation(args: &[&str]) -> Result<String, AppError> {
+  let _guard = MUTATION_QUEUE.lock().await;
+  run_tailscale_cmd(args).await
+}
+
+/// Number of leading words in a `pkexec` command line to log as is: the
+/// program and its subcommand, e.g. `tailscale set` or `systemctl start`.
+fn pkexec_subcommand_len(args: &[&str]) -> usize {
+  1 + subcommand_len(args.get(1..).unwrap_or_default())
 }
 
-pub async fn get_tailscale_devices() -> Result<Vec<String>, AppError> {
-  let out = run_tailscale_cmd(&["status"]).await?;
+/// Run a command through `pkexec` so polkit can prompt for elevation.
+#[instrument(
+  level = "debug",
+  skip_all,
+  fields(cmd = %sanitize_args(args, pkexec_subcommand_len(args))),
+  err
+)]
+async fn run_pkexec_cmd(args: &[&str]) -> Result<String, AppError> {
+  let output = Command::new("pkexec").args(args).kill_on_drop(true).output().await?;
 
-  let mut devices: Vec<String> = out
+  if !output.status.success() {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    return Err(AppError::CliFailure(format!(
+      "pkexec {} exited with {}: {}",
+      sanitize_args(args, pkexec_subcommand_len(args)),
+      output.status,
+      stderr.trim()
+    )));
+  }
+
+  Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Start the `tailscaled` service via systemd, elevating with pkexec.
+#[instrument(level = "debug", err(level = "debug"))]
+pub async fn start_daemon() -> Result<(), AppError> {
+  run_pkexec_cmd(&["systemctl", "start", "tailscaled"]).await?;
+  Ok(())
+}
+
+/// Make the current user the Tailscale operator, elevating with pkexec.
+///
+/// This runs the system tailscale binary with only `set --operator`: the
+/// configured path and extra arguments could be anything, and would run as root.
+#[instrument(level = "debug", err(level = "debug"))]
+pub async fn grant_operator() -> Result<(), AppError> {
+  let user = current_user()
+    .ok_or_else(|| AppError::CliFailure("could not determine the current user".to_string()))?;
+  let flag = format!("--operator={user}");
+  let Some(path) = SYSTEM_CLI_PATHS.into_iter().find(|path| Path::new(path).is_file()) else {
+    return Err(AppError::CliFailure("tailscale is not installed system-wide".to_string()));
+  };
+
+  run_pkexec_cmd(&[path, "set", &flag]).await?;
+  Ok(())
+}
+
+/// Progress of `tailscale update`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpdateEvent {
+  /// A line of output, such as the version being downloaded or installed.
+  Progress(String),
+  Finished(Result<(), String>),
+}
+
+/// Update the Tailscale client with `tailscale update`, elevating with pkexec,
+/// and send its output to `events` as it runs.
+#[instrument(level = "debug", skip_all)]
+pub async fn run_update(events: tokio::sync::mpsc::Sender<UpdateEvent>) {
+  let result = watch_update(&events).await.map_err(|e| e.to_string());
+  debug!(?result, "tailscale update finished");
+  let _ = events.send(UpdateEvent::Finished(result)).await;
+}
+
+/// Follow `pkexec tailscale update` output until it exits.
+async fn watch_update(events: &tokio::sync::mpsc::Sender<UpdateEvent>) -> Result<(), AppError> {
+  let _queued = MUTATION_QUEUE.lock().await;
+  let cli = cli_settings();
+  let mut child = Command::new("pkexec")
+    .arg(&cli.path)
+    .args(&cli.extra_args)
+    .args(["update", "--yes"])
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .kill_on_drop(true)
+    .spawn()?;
+
+  let (Some(stdout), Some(mut stderr)) = (child.stdout.take(), child.stderr.take()) else {
+    return Err(AppError::CliFailure("tailscale update has no output".to_string()));
+  };
+  // Progress goes to stdout; errors, and pkexec's own messages, to stderr.
+  let mut lines = BufReader::new(stdout).lines();
+  while let Some(line) = lines.next_line().await? {
+    let line = line.trim();
+    if !line.is_empty() && events.send(UpdateEvent::Progress(line.to_string())).await.is_err() {
+      // Nobody is following the update any more.
+      break;
+    }
+  }
+
+  let mut errors = String::new();
+  tokio::io::AsyncReadExt::read_to_string(&mut stderr, &mut errors).await?;
+  let status = child.wait().await?;
+  if status.success() {
+    return Ok(());
+  }
+  Err(AppError::CliFailure(match errors.lines().rfind(|line| !line.trim().is_empty()) {
+    Some(line) => line.trim().to_string(),
+    None => format!("tailscale update exited with {status}"),
+  }))
+}
+
+/// Get the output of `tailscale version`.
+#[instrument(level = "debug", err(level = "debug"))]
+pub async fn get_tailscale_version() -> Result<String, AppError> {
+  run_tailscale_cmd(&["version"]).await
+}
+
+/// Version of the tailscale CLI, e.g. `1.76.1`, from `tailscale version --json`.
+#[instrument(level = "debug", err(level = "debug"))]
+pub async fn fetch_client_version() -> Result<String, AppError> {
+  let output = run_tailscale_cmd(&["version", "--json"]).await?;
+  let version: Value = serde_json::from_str(&output)?;
+  version
+    .get("short")
+    .and_then(Value::as_str)
+    .map(str::to_string)
+    .ok_or_else(|| AppError::CliFailure("tailscale version has no short version".to_string()))
+}
+
+/// Pings sent per `tailscale ping` run from the peer details.
+pub const PING_COUNT: usize = 3;
+/// How long a whole ping run may take before it is abandoned.
+const PING_TIMEOUT_SECS: u64 = 20;
+
+/// How a ping reply reached this node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PingPath {
+  /// Straight to the peer's endpoint, e.g. `203.0.113.5:41641`.
+  Direct(String),
+  /// Through the DERP relay with this region code, e.g. `fra`.
+  Relayed(String),
+}
+
+/// One `pong` from `tailscale ping`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PingReply {
+  pub latency: Duration,
+  pub path: PingPath,
+}
+
+/// Progress of a `tailscale ping` run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PingEvent {
+  Reply(PingReply),
+  Finished(Result<(), String>),
+}
+
+/// Round-trip times from one `tailscale ping` run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PingStats {
+  pub min: Duration,
+  pub avg: Duration,
+  pub max: Duration,
+  pub replies: usize,
+  pub sent: usize,
+}
+
+impl PingStats {
+  /// Stats over `replies` out of `sent` pings; `None` without replies.
+  pub fn from_replies(replies: &[PingReply], sent: usize) -> Option<Self> {
+    let latencies = replies.iter().map(|reply| reply.latency);
+    let total: Duration = latencies.clone().sum();
+    Some(PingStats {
+      min: latencies.clone().min()?,
+      avg: total / u32::try_from(replies.len()).ok()?,
+      max: latencies.max()?,
+      replies: replies.len(),
+      sent,
+    })
+  }
+}
+
+/// Ping `peer` a few times over Tailscale, through a relay if need be, and
+/// send each reply to `events` as it arrives.
+#[instrument(level = "debug", skip_all)]
+pub async fn run_ping(peer: PeerName, events: tokio::sync::mpsc::Sender<PingEvent>) {
+  let result = tokio::time::timeout(
+    Duration::from_secs(PING_TIMEOUT_SECS),
+    watch_ping(&peer, &events),
+  )
+  .await
+  .unwrap_or_else(|_| {
+    Err(AppError::CliFailure(format!("ping timed out after {PING_TIMEOUT_SECS}s")))
+  })
+  .map_err(|e| e.to_string());
+  debug!(?result, "tailscale ping finished");
+  let _ = events.send(PingEvent::Finished(result)).await;
+}
+
+/// Follow `tailscale ping` output until it exits.
+async fn watch_ping(
+  peer: &PeerName,
+  events: &tokio::sync::mpsc::Sender<PingEvent>,
+) -> Result<(), AppError> {
+  let mut child = tailscale_command()
+    .args(["ping", "-c", &PING_COUNT.to_string(), "--until-direct=false", peer.as_str()])
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .kill_on_drop(true)
+    .spawn()?;
+
+  let (Some(stdout), Some(mut stderr)) = (child.stdout.take(), child.stderr.take()) else {
+    return Err(AppError::CliFailure("tailscale ping has no output".to_string()));
+  };
+  let mut lines = BufReader::new(stdout).lines();
+  while let Some(line) = lines.next_line().await? {
+    if let Some(reply) = parse_pong(&line)
+      && events.send(PingEvent::Reply(reply)).await.is_err()
+    {
+      break;
+    }
+  }
+
+  let mut errors = String::new();
+  tokio::io::AsyncReadExt::read_to_string(&mut stderr, &mut errors).await?;
+  let status = child.wait().await?;
+  if status.success() {
+    return Ok(());
+  }
+  Err(AppError::CliFailure(match errors.lines().rfind(|line| !line.trim().is_empty()) {
+    Some(line) => line.trim().to_string(),
+    None => format!("tailscale ping exited with {status}"),
+  }))
+}
+
+/// Ping `peer` once, through a relay if need be, e.g. to check the exit node.
+#[instrument(level = "debug", skip_all, err(level = "debug"))]
+pub async fn ping_once(peer: &PeerName) -> Result<PingReply, AppError> {
+  let args = ["ping", "-c", "1", "--until-direct=false", peer.as_str()];
+  let output =
+    tokio::time::timeout(Duration::from_secs(PING_TIMEOUT_SECS), run_tailscale_cmd(&args))
+      .await
+      .map_err(|_| AppError::CliFailure(format!("ping timed out after {PING_TIMEOUT_SECS}s")))??;
+  output
     .lines()
-    .filter(|line| IP_REGEX.is_match(line))
-    .filter_map(|line| line.split_whitespace().nth(1).map(std::string::ToString::to_string))
+    .find_map(parse_pong)
+    .ok_or_else(|| AppError::CliFailure("no reply".to_string()))
+}
+
+/// Parse `pong from host (100.64.0.2) via DERP(fra) in 34ms`, or the same
+/// with `via 203.0.113.5:41641` for a direct path.
+fn parse_pong(line: &str) -> Option<PingReply> {
+  let rest = line.trim().strip_prefix("pong from ")?;
+  let (_, rest) = rest.split_once(" via ")?;
+  let (via, latency) = rest.rsplit_once(" in ")?;
+  let latency = latency.trim();
+  let latency = if let Some(ms) = latency.strip_suffix("ms") {
+    Duration::from_secs_f64(ms.parse::<f64>().ok()? / 1000.0)
+  } else {
+    Duration::from_secs_f64(latency.strip_suffix('s')?.parse().ok()?)
+  };
+  let path = match via.strip_prefix("DERP(").and_then(|region| region.strip_suffix(')')) {
+    Some(region) => PingPath::Relayed(region.to_string()),
+    None => PingPath::Direct(via.to_string()),
+  };
+  Some(PingReply { latency, path })
+}
+
+/// A DERP relay region from the tailnet's DERP map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerpRegion {
+  pub id: u64,
+  /// Short code, e.g. `fra`.
+  pub code: String,
+  /// Display name, e.g. "Frankfurt".
+  pub name: String,
+}
+
+/// DERP regions from the tailnet's DERP map.
+#[instrument(level = "debug", err(level = "debug"))]
+pub async fn fetch_derp_regions() -> Result<Vec<DerpRegion>, AppError> {
+  let output = run_tailscale_cmd(&["debug", "derp-map"]).await?;
+  let derp_map: Value = serde_json::from_str(&output)?;
+  Ok(parse_derp_regions(&derp_map))
+}
+
+fn parse_derp_regions(derp_map: &Value) -> Vec<DerpRegion> {
+  derp_map
+    .get("Regions")
+    .and_then(Value::as_object)
+    .into_iter()
+    .flat_map(|regions| regions.values())
+    .filter_map(|region| {
+      Some(DerpRegion {
+        id: region.get("RegionID")?.as_u64()?,
+        code: region.get("RegionCode")?.as_str()?.to_string(),
+        name: region.get("RegionName")?.as_str()?.to_string(),
+      })
+    })
+    .collect()
+}
+
+/// Run `tailscale netcheck` and return its report.
+#[instrument(level = "debug", err(level = "debug"))]
+pub async fn run_netcheck() -> Result<String, AppError> {
+  run_tailscale_cmd(&["netcheck"]).await
+}
+
+/// What `tailscale netcheck` found out about this network.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct NetcheckReport {
+  pub udp: bool,
+  pub ipv4: bool,
+  pub ipv6: bool,
+  /// The NAT picks a different port per destination ("hard" NAT), which makes
+  /// direct connections unlikely; `None` when it couldn't be determined.
+  pub mapping_varies_by_dest_ip: Option<bool>,
+  pub upnp: bool,
+  pub pmp: bool,
+  pub pcp: bool,
+  /// Region ID of the nearest DERP relay, 0 if none was reachable.
+  pub preferred_derp: u64,
+  /// Round-trip time to each reachable DERP region by region ID, fastest first.
+  pub region_latency: Vec<(u64, Duration)>,
+}
+
+/// Run `tailscale netcheck --format=json` and parse its report.
+#[instrument(level = "debug", err(level = "debug"))]
+pub async fn run_netcheck_report() -> Result<NetcheckReport, AppError> {
+  let output = run_tailscale_cmd(&["netcheck", "--format=json"]).await?;
+  // Log lines may come before the JSON object.
+  let json = output.find('{').map_or(output.as_str(), |start| &output[start..]);
+  let report: Value = serde_json::from_str(json)?;
+  Ok(parse_netcheck_report(&report))
+}
+
+fn parse_netcheck_report(report: &Value) -> NetcheckReport {
+  // `opt.Bool` fields are `true`, `false`, or absent/null when unknown.
+  let opt_bool = |key: &str| report.get(key).and_then(Value::as_bool);
+  let flag = |key: &str| opt_bool(key).unwrap_or(false);
+
+  let mut region_latency: Vec<(u64, Duration)> = report
+    .get("RegionLatency")
+    .and_then(Value::as_object)
+    .into_iter()
+    .flatten()
+    .filter_map(|(id, nanos)| Some((id.parse().ok()?, Duration::from_nanos(nanos.as_u64()?))))
     .collect();
+  region_latency.sort_by_key(|(_, latency)| *latency);
+
+  NetcheckReport {
+    udp: flag("UDP"),
+    ipv4: flag("IPv4"),
+    ipv6: flag("IPv6"),
+    mapping_varies_by_dest_ip: opt_bool("MappingVariesByDestIP"),
+    upnp: flag("UPnP"),
+    pmp: flag("PMP"),
+    pcp: flag("PCP"),
+    preferred_derp: report.get("PreferredDERP").and_then(Value::as_u64).unwrap_or_default(),
+    region_latency,
+  }
+}
+
+/// The node and user behind a tailnet address, from `tailscale whois`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WhoisInfo {
+  pub address: IpAddr,
+  pub machine: String,
+  pub owner: String,
+  pub tags: Vec<String>,
+}
+
+/// Look up who owns a tailnet IP or machine name.
+///
+/// `tailscale whois` only takes addresses, so names go through `tailscale ip`
+/// first. Names may only have the characters of a DNS name, and follow `--` so
+/// they can't be read as flags.
+#[instrument(level = "debug", skip_all, err(level = "debug"))]
+pub async fn whois_lookup(query: &str) -> Result<WhoisInfo, AppError> {
+  let query = query.trim();
+  let address = match query.parse::<IpAddr>() {
+    Ok(ip) => ip,
+    Err(_) if !is_machine_name(query) => {
+      return Err(AppError::CliFailure("not an address or machine name".to_string()));
+    }
+    Err(_) => run_tailscale_cmd(&["ip", "-1", "--", query])
+      .await?
+      .trim()
+      .parse()
+      .map_err(|_| AppError::CliFailure(format!("no tailnet address for {query}")))?,
+  };
+  let output = run_tailscale_cmd(&["whois", "--json", &address.to_string()]).await?;
+  let whois: Value = serde_json::from_str(&output)?;
+  Ok(parse_whois(address, &whois))
+}
+
+/// Whether `name` looks like a machine name, short or fully qualified.
+fn is_machine_name(name: &str) -> bool {
+  !name.is_empty()
+    && !name.starts_with(['-', '.'])
+    && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_'))
+}
 
-  if !devices.is_empty() {
-    devices.remove(0);
+fn parse_whois(address: IpAddr, whois: &Value) -> WhoisInfo {
+  let node = &whois["Node"];
+  let user = &whois["UserProfile"];
+  let str_field = |value: &Value, key: &str| {
+    value
+      .get(key)
+      .and_then(Value::as_str)
+      .filter(|s| !s.is_empty())
+      .map(String::from)
+  };
+
+  WhoisInfo {
+    address,
+    machine: str_field(node, "ComputedName")
+      .or_else(|| str_field(node, "Name").map(|name| name.trim_end_matches('.').to_string()))
+      .unwrap_or_default(),
+    owner: str_field(user, "DisplayName")
+      .or_else(|| str_field(user, "LoginName"))
+      .unwrap_or_default(),
+    tags: node
+      .get("Tags")
+      .and_then(Value::as_array)
+      .into_iter()
+      .flatten()
+      .filter_map(Value::as_str)
+      .map(String::from)
+      .collect(),
   }
-  devices.insert(0, "Select".to_string());
+}
 
-  Ok(devices)
+/// Who the tailnet's SSH policy lets in, one `who -> local users` line per rule.
+///
+/// Read from the `SSHPolicy` in `tailscale debug netmap`; control may leave it
+/// out while SSH is off, in which case the list is empty.
+#[instrument(level = "debug", err(level = "debug"))]
+pub async fn fetch_ssh_access() -> Result<Vec<String>, AppError> {
+  let output = run_tailscale_cmd(&["debug", "netmap"]).await?;
+  let netmap: Value = serde_json::from_str(&output)?;
+  Ok(parse_ssh_access(&netmap))
+}
+
+fn parse_ssh_access(netmap: &Value) -> Vec<String> {
+  let Some(rules) = netmap
+    .get("SSHPolicy")
+    .and_then(|policy| policy.get("rules"))
+    .and_then(Value::as_array)
+  else {
+    return Vec::new();
+  };
+
+  rules
+    .iter()
+    .filter(|rule| {
+      rule
+        .get("action")
+        .and_then(|action| action.get("accept"))
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+    })
+    .filter_map(|rule| {
+      let principals: Vec<String> = rule
+        .get("principals")?
+        .as_array()?
+        .iter()
+        .filter_map(|principal| {
+          if principal.get("any").and_then(Value::as_bool) == Some(true) {
+            return Some("*".to_string());
+          }
+          ["userLogin", "nodeIP", "node"]
+            .iter()
+            .find_map(|key| principal.get(*key).and_then(Value::as_str))
+            .map(str::to_string)
+        })
+        .collect();
+
+      let mut users: Vec<&str> = rule
+        .get("sshUsers")
+        .and_then(Value::as_object)
+        .map(|users| users.keys().map(String::as_str).collect())
+        .unwrap_or_default();
+      users.sort_unstable();
+
+      Some(format!("{} -> {}", principals.join(", "), users.join(", ")))
+    })
+    .collect()
+}
+
+/// Addresses this node exposes to the internet with Funnel, e.g.
+/// `laptop.tail1234.ts.net:443`; empty when Funnel is off.
+#[instrument(level = "debug", err(level = "debug"))]
+pub async fn fetch_funnel_hosts() -> Result<Vec<String>, AppError> {
+  let output = run_tailscale_cmd(&["serve", "status", "--json"]).await?;
+  // Nothing is printed when nothing is served.
+  if output.trim().is_empty() {
+    return Ok(Vec::new());
+  }
+  let serve: Value = serde_json::from_str(&output)?;
+  Ok(parse_funnel_hosts(&serve))
+}
+
+fn parse_funnel_hosts(serve: &Value) -> Vec<String> {
+  serve
+    .get("AllowFunnel")
+    .and_then(Value::as_object)
+    .map(|hosts| {
+      hosts
+        .iter()
+        .filter(|(_, allowed)| allowed.as_bool().unwrap_or(false))
+        .map(|(host, _)| host.clone())
+        .collect()
+    })
+    .unwrap_or_default()
+}
+
+/// Get the Tailscale addresses assigned to this computer, IPv4 first.
+#[instrument(level = "debug", err(level = "debug"))]
+pub async fn get_tailscale_ips() -> Result<Vec<IpAddr>, AppError> {
+  let output = run_tailscale_cmd(&["ip"]).await?;
+
+  let mut ips: Vec<IpAddr> = output
+    .lines()
+    .filter_map(|line| match line.trim().parse() {
+      Ok(ip) => Some(ip),
+      Err(e) => {
+        debug!("Ignoring unparsable address {line:?}: {e}");
+        None
+      }
+    })
+    .collect();
+
+  ips.sort_by_key(IpAddr::is_ipv6);
+  Ok(ips)
 }
 
 /// Set the Tailscale connection up/down
+///
+/// Only toggles `WantRunning`: bare `up`/`down` never carry preference flags,
+/// which would otherwise reset unmentioned prefs. Use `tailscale set` for those.
+#[instrument(level = "debug", err(level = "debug"))]
 pub async fn tailscale_int_up(up: bool) -> Result<(), AppError> {
   let arg = if up { "up" } else { "down" };
-  run_tailscale_cmd(&[arg]).await?;
+  run_mutation(&[arg]).await?;
   Ok(())
 }
 
-/// Send files through Tail Drop
-pub async fn tailscale_send(file_paths: &[PathBuf], target: &str) -> Option<String> {
-  let mut errors = Vec::new();
+/// Outcome of sending files through Tail Drop.
+#[cfg(feature = "taildrop")]
+#[derive(Debug, Clone)]
+pub struct SendReport {
+  pub device: String,
+  pub files: Vec<TransferFile>,
+  pub total: usize,
+  pub failed: usize,
+  pub last_error: Option<String>,
+}
+
+/// Outcome of receiving files through Tail Drop.
+#[cfg(feature = "taildrop")]
+#[derive(Debug, Clone)]
+pub enum ReceiveReport {
+  Received(Vec<TransferFile>),
+  NoDownloadsDir,
+  TimedOut(u64),
+  Failed(String),
+}
+
+#[cfg(feature = "taildrop")]
+const RECEIVE_TIMEOUT_SECS: u64 = 30;
+#[cfg(feature = "taildrop")]
+const LOCALAPI_TIMEOUT_SECS: u64 = 5;
+
+/// Send files through Tail Drop to `target`, a machine name or IP that
+/// `tailscale file cp` resolves; `device` is the name reported back.
+#[cfg(feature = "taildrop")]
+#[instrument(level = "debug", skip_all, fields(files = file_paths.len()))]
+pub async fn tailscale_send(file_paths: &[PathBuf], device: &str, target: &str) -> SendReport {
+  let mut failed = 0;
+  let mut last_error = None;
+  let mut files = Vec::with_capacity(file_paths.len());
 
   for path in file_paths {
     let p = path.to_string_lossy();
-    match Command::new("tailscale")
+    let ok = match tailscale_command()
       .args(["file", "cp", &*p, &format!("{target}:")])
       .output()
       .await
     {
       Ok(output) => {
         if !output.status.success() || !output.stderr.is_empty() {
-          let err = String::from_utf8_lossy(&output.stderr).to_string();
+          let err = String::from_utf8_lossy(&output.stderr).trim().to_string();
           warn!("Error sending file {p}: {err}");
-          errors.push(err);
+          failed += 1;
+          last_error = Some(err);
+          false
+        } else {
+          true
         }
       }
       Err(e) => {
-        error!("Failed to execute tailscale file cp: {e}");
-        errors.push(format!("Failed to send {p}: {e}"));
+        error!("Failed to execute tailscale file cp for {p}: {e}");
+        failed += 1;
+        last_error = Some(e.to_string());
+        false
       }
-    }
+    };
+
+    files.push(TransferFile {
+      name: path
+        .file_name()
+        .map_or_else(|| p.to_string(), |name| name.to_string_lossy().to_string()),
+      size: tokio::fs::metadata(path).await.map(|m| m.len()).unwrap_or_default(),
+      ok,
+    });
   }
 
-  if !errors.is_empty() {
-    return Some(fl!("send-files-partial-fail"));
+  SendReport {
+    device: device.to_string(),
+    files,
+    total: file_paths.len(),
+    failed,
+    last_error,
   }
+}
+
+/// Names of the entries in a directory, used to tell which files arrived.
+#[cfg(feature = "taildrop")]
+async fn dir_entries(path: &Path) -> HashSet<OsString> {
+  let mut names = HashSet::new();
+  let Ok(mut entries) = tokio::fs::read_dir(path).await else {
+    return names;
+  };
+
+  while let Ok(Some(entry)) = entries.next_entry().await {
+    names.insert(entry.file_name());
+  }
+  names
+}
+
+/// Directory that received Tail Drop files are saved to.
+#[cfg(feature = "taildrop")]
+pub fn receive_dir() -> Option<PathBuf> {
+  dirs::download_dir()
+}
+
+/// Number of received Tail Drop files waiting in `tailscaled`'s inbox.
+///
+/// The CLI can only download waiting files, so this asks the daemon's local API directly.
+/// Listing them needs the same access as `tailscale file get`.
+#[cfg(feature = "taildrop")]
+#[instrument(level = "debug", err(level = "debug"))]
+pub async fn waiting_file_count() -> Result<usize, AppError> {
+  use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
-  None
+  let mut stream = tokio::net::UnixStream::connect(daemon_socket())
+    .await
+    .map_err(|_| AppError::DaemonNotRunning)?;
+  // HTTP/1.0 so the body isn't chunked and the daemon closes the connection.
+  stream
+    .write_all(b"GET /localapi/v0/files/ HTTP/1.0\r\nHost: local-tailscaled.sock\r\n\r\n")
+    .await?;
+  let mut response = String::new();
+  tokio::time::timeout(
+    Duration::from_secs(LOCALAPI_TIMEOUT_SECS),
+    stream.read_to_string(&mut response),
+  )
+  .await
+  .map_err(|_| AppError::CliFailure("LocalAPI request timed out".to_string()))??;
+
+  let (head, body) = response.split_once("\r\n\r\n").unwrap_or((&response, ""));
+  let status = head.lines().next().unwrap_or_default();
+  if status.contains(" 403 ") {
+    return Err(AppError::PermissionDenied(body.trim().to_string()));
+  }
+  if !status.contains(" 200 ") {
+    return Err(AppError::CliFailure(format!("LocalAPI returned {status}")));
+  }
+  let files: Value = serde_json::from_str(body)?;
+  Ok(files.as_array().map_or(0, Vec::len))
 }
 
 /// Receive files through Tail Drop (with 30-second timeout).
-pub async fn tailscale_receive() -> String {
-  let Some(download_path) = dirs::download_dir() else {
-    return fl!("no-downloads-dir");
+#[cfg(feature = "taildrop")]
+#[instrument(level = "debug")]
+pub async fn tailscale_receive() -> ReceiveReport {
+  let Some(download_path) = receive_dir() else {
+    return ReceiveReport::NoDownloadsDir;
   };
 
   let download_str = download_path.to_string_lossy().to_string();
+  let before = dir_entries(&download_path).await;
 
-  let receive_fut = Command::new("tailscale")
+  let receive_fut = tailscale_command()
     .args(["file", "get", &download_str])
     .output();
 
-  match tokio::time::timeout(std::time::Duration::from_secs(30), receive_fut).await {
+  match tokio::time::timeout(Duration::from_secs(RECEIVE_TIMEOUT_SECS), receive_fut).await {
     Ok(Ok(output)) => {
       if output.status.success() && output.stderr.is_empty() {
-        fl!("received-files-success")
+        let mut files = Vec::new();
+        for name in dir_entries(&download_path).await.difference(&before) {
+          let size = tokio::fs::metadata(download_path.join(name))
+            .await
+            .map(|m| m.len())
+            .unwrap_or_default();
+          files.push(TransferFile {
+            name: name.to_string_lossy().to_string(),
+            size,
+            ok: true,
+          });
+        }
+        ReceiveReport::Received(files)
       } else {
-        String::from_utf8_lossy(&output.stderr).to_string()
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        if stderr.is_empty() {
+          ReceiveReport::Failed(format!("tailscale file get exited with {}", output.status))
+        } else {
+          ReceiveReport::Failed(stderr)
+        }
       }
     }
-    Ok(Err(e)) => format!("Failed to receive files: {e}"),
-    Err(_) => String::from("No files received (timed out after 30s)"),
+    Ok(Err(e)) => ReceiveReport::Failed(e.to_string()),
+    Err(_) => ReceiveReport::TimedOut(RECEIVE_TIMEOUT_SECS),
   }
 }
 
-pub async fn clear_status(wait_time: u64) -> Option<String> {
-  tokio::time::sleep(std::time::Duration::from_secs(wait_time)).await;
-  None
+/// How long to wait for `tailscale login` to print its URL.
+const LOGIN_URL_TIMEOUT_SECS: u64 = 30;
+/// How long to wait for the login to be completed in a browser.
+const LOGIN_AUTH_TIMEOUT_SECS: u64 = 600;
+
+/// Progress of an interactive login.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoginEvent {
+  /// Waiting for the login to be completed at this URL.
+  AuthUrl(String),
+  Success,
+  Failed(String),
+  /// No URL was printed, or the browser login wasn't completed, in time.
+  TimedOut,
+}
+
+/// What a line of `tailscale login` output means.
+#[derive(Debug, PartialEq, Eq)]
+enum LoginLine {
+  AuthUrl(String),
+  Success,
+  Error(String),
+  Other,
+}
+
+/// Run an interactive login, sending its progress to `events` until it ends.
+///
+/// `tailscale login` prints the same prompts as `tailscale up`: a URL to
+/// authenticate at, then "Success." once the login is completed in a browser.
+/// Only the wait for the URL holds the mutation queue, so other changes aren't
+/// blocked while the browser login is pending.
+#[instrument(level = "debug", skip_all)]
+pub async fn run_login(events: tokio::sync::mpsc::Sender<LoginEvent>) {
+  let event = match watch_login(&events).await {
+    Ok(event) => event,
+    Err(e) => LoginEvent::Failed(e.to_string()),
+  };
+  debug!(?event, "tailscale login finished");
+  let _ = events.send(event).await;
+}
+
+/// Follow `tailscale login` output, sending the URL once it appears and
+/// returning how the login ended.
+async fn watch_login(
+  events: &tokio::sync::mpsc::Sender<LoginEvent>,
+) -> Result<LoginEvent, AppError> {
+  let mut queued = Some(MUTATION_QUEUE.lock().await);
+  let mut child = tailscale_command()
+    .arg("login")
+    .stdout(Stdio::null())
+    .stderr(Stdio::piped())
+    .kill_on_drop(true)
+    .spawn()?;
+
+  let Some(stderr) = child.stderr.take() else {
+    return Err(AppError::CliFailure("tailscale login has no stderr".to_string()));
+  };
+  let mut lines = BufReader::new(stderr).lines();
+  let mut deadline = tokio::time::Instant::now() + Duration::from_secs(LOGIN_URL_TIMEOUT_SECS);
+  let mut error = None;
+
+  loop {
+    let Ok(line) = tokio::time::timeout_at(deadline, lines.next_line()).await else {
+      let _ = child.kill().await;
+      return Ok(LoginEvent::TimedOut);
+    };
+    let Some(line) = line? else {
+      break;
+    };
+
+    match parse_login_line(&line) {
+      LoginLine::AuthUrl(url) => {
+        queued = None;
+        deadline = tokio::time::Instant::now() + Duration::from_secs(LOGIN_AUTH_TIMEOUT_SECS);
+        if events.send(LoginEvent::AuthUrl(url)).await.is_err() {
+          // Nobody is waiting for the login any more.
+          return Ok(LoginEvent::Failed("login abandoned".to_string()));
+        }
+      }
+      LoginLine::Success => return Ok(LoginEvent::Success),
+      LoginLine::Error(message) => error = Some(message),
+      LoginLine::Other => {}
+    }
+  }
+  drop(queued);
+
+  let status = child.wait().await?;
+  Ok(match error {
+    Some(message) => LoginEvent::Failed(message),
+    None if status.success() => LoginEvent::Success,
+    None => LoginEvent::Failed(format!("tailscale login exited with {status}")),
+  })
+}
+
+/// Classify a line of `tailscale login` output.
+fn parse_login_line(line: &str) -> LoginLine {
+  let line = line.trim();
+  if let Some(url) = line
+    .split_whitespace()
+    .find(|word| word.starts_with("https://"))
+  {
+    return LoginLine::AuthUrl(url.to_string());
+  }
+  if line.eq_ignore_ascii_case("success.") {
+    return LoginLine::Success;
+  }
+  let lower = line.to_ascii_lowercase();
+  if lower.starts_with("error") || lower.starts_with("backend error") {
+    return LoginLine::Error(line.to_string());
+  }
+  LoginLine::Other
+}
+
+/// Open `url` in the default browser.
+#[instrument(level = "debug", skip_all, err(level = "debug"))]
+pub async fn open_url(url: &str) -> Result<(), AppError> {
+  let output = Command::new("xdg-open").arg(url).output().await?;
+  if !output.status.success() {
+    return Err(AppError::CliFailure(format!(
+      "xdg-open exited with {}: {}",
+      output.status,
+      String::from_utf8_lossy(&output.stderr).trim()
+    )));
+  }
+  Ok(())
+}
+
+/// Start `command` with `{host}` replaced by `host`, or `host` appended if it
+/// has no placeholder, e.g. `cosmic-term -- ssh laptop.tail1234.ts.net`. The
+/// program (a terminal, remote desktop client, ...) is left running on its own.
+#[instrument(level = "debug", skip_all, err(level = "debug"))]
+pub async fn launch_for_host(command: &[String], host: &str) -> Result<(), AppError> {
+  let Some((program, args)) = command.split_first() else {
+    return Err(AppError::CliFailure("command is empty".to_string()));
+  };
+  let mut args: Vec<String> = args.iter().map(|arg| arg.replace("{host}", host)).collect();
+  if !command.iter().any(|arg| arg.contains("{host}")) {
+    args.push(host.to_string());
+  }
+  Command::new(program).args(args).spawn()?;
+  Ok(())
+}
+
+/// Port Wake-on-LAN magic packets are sent to (the discard port).
+const WAKE_ON_LAN_PORT: u16 = 9;
+
+/// A MAC address such as `00:11:22:aa:bb:cc`, also accepting `-` separators.
+pub fn parse_mac(mac: &str) -> Option<[u8; 6]> {
+  let octets: Vec<u8> = mac
+    .trim()
+    .split([':', '-'])
+    .map(|octet| {
+      if octet.len() == 2 && octet.bytes().all(|b| b.is_ascii_hexdigit()) {
+        u8::from_str_radix(octet, 16).ok()
+      } else {
+        None
+      }
+    })
+    .collect::<Option<_>>()?;
+  octets.try_into().ok()
+}
+
+/// Broadcast address of an IPv4 route such as `192.168.1.0/24`.
+fn subnet_broadcast(route: &str) -> Option<std::net::Ipv4Addr> {
+  let (addr, len) = route.split_once('/')?;
+  let addr: std::net::Ipv4Addr = addr.parse().ok()?;
+  let len: u32 = len.parse().ok().filter(|len| *len <= 32)?;
+  let host_mask = u32::MAX.checked_shr(len).unwrap_or(0);
+  Some((u32::from(addr) | host_mask).into())
+}
+
+/// Send a Wake-on-LAN magic packet for `mac` to the local network, and as a
+/// directed broadcast into each of `routes` for machines behind a subnet
+/// router. Routers often drop directed broadcasts, so only the local network
+/// is sure to work.
+#[instrument(level = "debug", skip(mac), err(level = "debug"))]
+pub async fn send_wake_on_lan(mac: [u8; 6], routes: &[String]) -> Result<(), AppError> {
+  let mut packet = vec![0xff; 6];
+  for _ in 0..16 {
+    packet.extend_from_slice(&mac);
+  }
+
+  let socket = tokio::net::UdpSocket::bind((std::net::Ipv4Addr::UNSPECIFIED, 0)).await?;
+  socket.set_broadcast(true)?;
+  socket
+    .send_to(&packet, (std::net::Ipv4Addr::BROADCAST, WAKE_ON_LAN_PORT))
+    .await?;
+  for broadcast in routes.iter().filter_map(|route| subnet_broadcast(route)) {
+    if let Err(e) = socket.send_to(&packet, (broadcast, WAKE_ON_LAN_PORT)).await {
+      debug!("Failed to send magic packet to {broadcast}: {e}");
+    }
+  }
+  Ok(())
+}
+
+/// How long to wait for a peer's web port to accept a connection.
+const WEB_PROBE_TIMEOUT_SECS: u64 = 2;
+
+/// URL of the web page a peer serves, if it accepts connections on port 443
+/// (which Tailscale Serve and Funnel use) or else port 80. The URL names the
+/// peer by its machine name when it has one, so HTTPS certificates match.
+#[instrument(level = "debug", skip_all)]
+pub async fn probe_web_url(ip: IpAddr, dns_name: &str) -> Option<String> {
+  let accepts = |port: u16| async move {
+    tokio::time::timeout(
+      Duration::from_secs(WEB_PROBE_TIMEOUT_SECS),
+      tokio::net::TcpStream::connect((ip, port)),
+    )
+    .await
+    .is_ok_and(|stream| stream.is_ok())
+  };
+  let (https, http) = tokio::join!(accepts(443), accepts(80));
+  let host = if dns_name.is_empty() {
+    match ip {
+      IpAddr::V4(ip) => ip.to_string(),
+      IpAddr::V6(ip) => format!("[{ip}]"),
+    }
+  } else {
+    dns_name.to_string()
+  };
+  if https {
+    Some(format!("https://{host}"))
+  } else if http {
+    Some(format!("http://{host}"))
+  } else {
+    None
+  }
+}
+
+/// Play a sound from the XDG sound theme by its event ID (e.g. `complete`).
+pub async fn play_sound(event_id: &'static str) {
+  match Command::new("canberra-gtk-play")
+    .args(["--id", event_id])
+    .output()
+    .await
+  {
+    Ok(output) if !output.status.success() => {
+      debug!(
+        "canberra-gtk-play failed for {event_id}: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+      );
+    }
+    Ok(_) => {}
+    Err(e) => debug!("Failed to play sound {event_id}: {e}"),
+  }
+}
+
+#[cfg(feature = "taildrop")]
+pub async fn clear_status(wait_time: u64) {
+  tokio::time::sleep(Duration::from_secs(wait_time)).await;
 }
 
 /// Toggle a tailscale flag on/off
@@ -221,96 +1688,662 @@ async fn set_tailscale_flag(flag: &str, enabled: bool) -> Result<(), AppError> {
     format!("--{flag}=false")
   };
 
-  run_tailscale_cmd(&["set", &value]).await?;
+  run_mutation(&["set", &value]).await?;
   Ok(())
 }
 
+/// Toggle one of the advanced preferences.
+#[instrument(level = "debug", err(level = "debug"))]
+pub async fn set_advanced_pref(pref: AdvancedPref, enabled: bool) -> Result<(), AppError> {
+  set_tailscale_flag(pref.flag(), enabled).await
+}
+
 /// Toggle SSH on/off
+#[instrument(level = "debug", err(level = "debug"))]
 pub async fn set_ssh(ssh: bool) -> Result<(), AppError> {
   set_tailscale_flag("ssh", ssh).await
 }
 
 /// Toggle accept-routes on/off
+#[instrument(level = "debug", err(level = "debug"))]
 pub async fn set_routes(accept_routes: bool) -> Result<(), AppError> {
   set_tailscale_flag("accept-routes", accept_routes).await
 }
 
 /// Make current host an exit node
+#[instrument(level = "debug", err(level = "debug"))]
 pub async fn enable_exit_node(is_exit_node: bool) -> Result<(), AppError> {
   let flag = format!("--advertise-exit-node={is_exit_node}");
-  run_tailscale_cmd(&["set", &flag]).await?;
-  tailscale_int_up(true).await
+  run_mutation(&["set", &flag]).await?;
+  Ok(())
+}
+
+/// Advertise `routes` as subnet routes, replacing the current ones.
+///
+/// The exit node flag is passed along because it shares `AdvertiseRoutes`.
+#[instrument(level = "debug", skip_all, fields(routes = routes.len()), err(level = "debug"))]
+pub async fn set_advertised_routes(routes: &[String], is_exit_node: bool) -> Result<(), AppError> {
+  let routes_flag = format!("--advertise-routes={}", routes.join(","));
+  let exit_node_flag = format!("--advertise-exit-node={is_exit_node}");
+  run_mutation(&["set", &routes_flag, &exit_node_flag]).await?;
+  Ok(())
+}
+
+/// Normalized form of `cidr` if it is a valid subnet route, e.g. `192.168.1.0/24`.
+///
+/// Host bits must be zero, as `tailscale set` rejects routes like `192.168.1.1/24`.
+pub fn parse_cidr(cidr: &str) -> Option<String> {
+  let (addr, prefix) = cidr.trim().split_once('/')?;
+  let addr: IpAddr = addr.parse().ok()?;
+  let prefix: u8 = prefix.parse().ok()?;
+  let host_bits_zero = match addr {
+    IpAddr::V4(v4) => {
+      prefix <= 32 && u32::from(v4).checked_shl(u32::from(prefix)).unwrap_or(0) == 0
+    }
+    IpAddr::V6(v6) => {
+      prefix <= 128 && u128::from(v6).checked_shl(u32::from(prefix)).unwrap_or(0) == 0
+    }
+  };
+  // A /0 route is an exit node, which has its own toggle.
+  (host_bits_zero && prefix > 0).then(|| format!("{addr}/{prefix}"))
+}
+
+/// Private IPv4 ranges from RFC 1918, offered as route presets.
+pub const PRIVATE_IPV4_RANGES: [&str; 3] = ["10.0.0.0/8", "172.16.0.0/12", "192.168.0.0/16"];
+
+/// The IPv4 subnet of the interface holding the default route, e.g. the home
+/// LAN, or `None` if it can't be found.
+#[instrument(level = "debug")]
+pub async fn detect_lan_subnet() -> Option<String> {
+  let table = tokio::fs::read_to_string("/proc/net/route")
+    .await
+    .inspect_err(|e| warn!("Failed to read the routing table: {e}"))
+    .ok()?;
+  parse_lan_subnet(&table)
+}
+
+/// Find the directly connected route on the default route's interface in
+/// `/proc/net/route`, whose addresses are little-endian hex.
+fn parse_lan_subnet(table: &str) -> Option<String> {
+  // Interface, destination, gateway and mask of each route.
+  let routes: Vec<(&str, u32, u32, u32)> = table
+    .lines()
+    .skip(1)
+    .filter_map(|line| {
+      let fields: Vec<&str> = line.split_whitespace().collect();
+      let hex = |idx: usize| u32::from_str_radix(fields.get(idx)?, 16).ok();
+      Some((*fields.first()?, hex(1)?, hex(2)?, hex(7)?))
+    })
+    .filter(|(iface, ..)| !iface.starts_with("tailscale"))
+    .collect();
+
+  let (lan_iface, ..) = routes.iter().find(|(_, dest, _, mask)| *dest == 0 && *mask == 0)?;
+  routes
+    .iter()
+    .filter(|(iface, dest, gateway, mask)| {
+      iface == lan_iface && *dest != 0 && *gateway == 0 && *mask != 0
+    })
+    .find_map(|(_, dest, _, mask)| {
+      let addr = std::net::Ipv4Addr::from(dest.to_le_bytes());
+      parse_cidr(&format!("{addr}/{}", mask.count_ones()))
+    })
+}
+
+/// Server whose HTTPS `Date` header the system clock is compared with.
+const CLOCK_CHECK_URL: &str = "https://controlplane.tailscale.com/";
+const CLOCK_CHECK_TIMEOUT_SECS: u64 = 5;
+
+/// How far the system clock is ahead of the coordination server's, in seconds;
+/// negative when it is behind. Accurate to a second or two.
+///
+/// The request goes through `curl`, over HTTPS so the header can't be altered
+/// on the way.
+#[instrument(level = "debug", err(level = "debug"))]
+pub async fn measure_clock_skew() -> Result<i64, AppError> {
+  let sent = chrono::Utc::now();
+  let output = Command::new("curl")
+    .args(["--head", "--silent", "--show-error", "--max-time"])
+    .arg(CLOCK_CHECK_TIMEOUT_SECS.to_string())
+    .arg(CLOCK_CHECK_URL)
+    .kill_on_drop(true)
+    .output()
+    .await?;
+  // The server stamped the response somewhere between sending and receiving.
+  let local = sent + (chrono::Utc::now() - sent) / 2;
+  if !output.status.success() {
+    return Err(AppError::CliFailure(format!(
+      "curl exited with {}: {}",
+      output.status,
+      String::from_utf8_lossy(&output.stderr).trim()
+    )));
+  }
+
+  let response = String::from_utf8_lossy(&output.stdout);
+  let server = response
+    .lines()
+    .take_while(|line| !line.trim().is_empty())
+    .find_map(|line| {
+      let (name, value) = line.split_once(':')?;
+      name.eq_ignore_ascii_case("date").then(|| value.trim())
+    })
+    .and_then(|date| chrono::DateTime::parse_from_rfc2822(date).ok())
+    .ok_or_else(|| AppError::CliFailure(format!("{CLOCK_CHECK_URL} sent no Date header")))?;
+  Ok((local - server.with_timezone(&chrono::Utc)).num_seconds())
+}
+
+/// Whether this machine has a default route outside Tailscale, i.e. any network
+/// that could reach a coordination server. Assumes it does when the routing
+/// tables can't be read.
+pub async fn host_has_network() -> bool {
+  let ipv4 = tokio::fs::read_to_string("/proc/net/route").await;
+  let ipv6 = tokio::fs::read_to_string("/proc/net/ipv6_route").await;
+  if let (Err(e), Err(_)) = (&ipv4, &ipv6) {
+    warn!("Failed to read the routing table: {e}");
+    return true;
+  }
+  ipv4.is_ok_and(|table| has_ipv4_default_route(&table))
+    || ipv6.is_ok_and(|table| has_ipv6_default_route(&table))
+}
+
+/// A `0.0.0.0/0` route in `/proc/net/route` not via a Tailscale interface.
+fn has_ipv4_default_route(table: &str) -> bool {
+  table.lines().skip(1).any(|line| {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    matches!(
+      fields.as_slice(),
+      [iface, "00000000", _, _, _, _, _, "00000000", ..] if !iface.starts_with("tailscale")
+    )
+  })
+}
+
+/// A `::/0` route in `/proc/net/ipv6_route` not via loopback, where the kernel
+/// keeps its unreachable placeholder, or a Tailscale interface.
+fn has_ipv6_default_route(table: &str) -> bool {
+  table.lines().any(|line| {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let [dest, "00", .., iface] = fields.as_slice() else {
+      return false;
+    };
+    dest.bytes().all(|b| b == b'0') && *iface != "lo" && !iface.starts_with("tailscale")
+  })
+}
+
+/// `0.0.0.0/0` or `::/0`, which mean "exit node" rather than a subnet route.
+fn is_default_route(route: &str) -> bool {
+  route == "0.0.0.0/0" || route == "::/0"
 }
 
 /// Add/remove exit node's access to the host's local LAN
+#[instrument(level = "debug", err(level = "debug"))]
 pub async fn exit_node_allow_lan_access(is_allowed: bool) -> Result<(), AppError> {
   let flag = format!("--exit-node-allow-lan-access={is_allowed}");
-  run_tailscale_cmd(&["set", &flag]).await?;
+  run_mutation(&["set", &flag]).await?;
   Ok(())
 }
 
-/// Get available exit nodes
-pub async fn get_avail_exit_nodes() -> Result<Vec<String>, AppError> {
-  let exit_node_list_string = run_tailscale_cmd(&["exit-node", "list"]).await?;
+/// Preference changes applied together with one `tailscale set`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrefChanges {
+  pub ssh: Option<bool>,
+  pub accept_routes: Option<bool>,
+  /// Exit node to use, empty for none.
+  pub exit_node: Option<String>,
+  pub advertise_exit_node: Option<bool>,
+  pub exit_node_allow_lan_access: Option<bool>,
+}
 
-  if exit_node_list_string.is_empty() {
-    debug!("No exit nodes found");
-    return Ok(vec![fl!("no-exit-nodes")]);
+impl PrefChanges {
+  pub fn is_empty(&self) -> bool {
+    *self == Self::default()
   }
 
-  let mut exit_node_list: Vec<String> = vec!["None".to_string()];
+  /// Number of preferences that would change.
+  pub fn len(&self) -> usize {
+    self.flags().len()
+  }
 
-  let nodes: Vec<String> = exit_node_list_string
-    .lines()
-    .filter(|line| HOSTNAME_REGEX.is_match(line))
-    .filter_map(|hostname| {
-      hostname
-        .split_whitespace()
-        .nth(1)
-        .and_then(|fqdn| fqdn.split('.').next())
-        .map(std::string::ToString::to_string)
-    })
-    .collect();
+  fn flags(&self) -> Vec<String> {
+    let mut flags = Vec::new();
+    if let Some(ssh) = self.ssh {
+      flags.push(format!("--ssh={ssh}"));
+    }
+    if let Some(accept) = self.accept_routes {
+      flags.push(format!("--accept-routes={accept}"));
+    }
+    if let Some(exit_node) = &self.exit_node {
+      flags.push(format!("--exit-node={exit_node}"));
+    }
+    if let Some(advertise) = self.advertise_exit_node {
+      flags.push(format!("--advertise-exit-node={advertise}"));
+    }
+    if let Some(allowed) = self.exit_node_allow_lan_access {
+      flags.push(format!("--exit-node-allow-lan-access={allowed}"));
+    }
+    flags
+  }
+}
 
-  exit_node_list.extend(nodes);
-  Ok(exit_node_list)
+/// Apply several preference changes in a single `tailscale set`, so the
+/// daemon reconfigures once instead of passing through intermediate states.
+#[instrument(level = "debug", skip_all, fields(changes = changes.len()), err(level = "debug"))]
+pub async fn apply_pref_changes(changes: &PrefChanges) -> Result<(), AppError> {
+  let flags = changes.flags();
+  let mut args = vec!["set"];
+  args.extend(flags.iter().map(String::as_str));
+  run_mutation(&args).await?;
+  Ok(())
 }
 
-/// Set selected exit node as the exit node through Tailscale CLI
-pub async fn set_exit_node(exit_node: &str) -> Result<(), AppError> {
+/// Exit nodes from the status JSON: "None", the tailnet's own exit nodes by
+/// name, then location-based ones by country, city and preference. The latter
+/// are also returned with their locations, in the same order.
+fn parse_exit_nodes(status: &Value) -> (Vec<String>, Vec<LocatedExitNode>) {
+  let mut own = Vec::new();
+  let mut located = Vec::new();
+  let options = status
+    .get("Peer")
+    .and_then(Value::as_object)
+    .into_iter()
+    .flat_map(|peers| peers.values())
+    .filter(|peer| peer.get("ExitNodeOption").and_then(Value::as_bool).unwrap_or(false));
+  for peer in options {
+    let name = peer_name(peer).to_string();
+    let Some(location) = peer.get("Location").filter(|location| location.is_object()) else {
+      own.push(name);
+      continue;
+    };
+    let field = |key: &str| {
+      location.get(key).and_then(Value::as_str).unwrap_or_default().to_string()
+    };
+    located.push(LocatedExitNode {
+      name,
+      country: field("Country"),
+      city: field("City"),
+      priority: location.get("Priority").and_then(Value::as_i64).unwrap_or_default(),
+    });
+  }
+
+  if own.is_empty() && located.is_empty() {
+    debug!("No exit nodes found");
+    return (vec![fl!("no-exit-nodes")], Vec::new());
+  }
+  own.sort();
+  located.sort_by(|a, b| {
+    (&a.country, &a.city, std::cmp::Reverse(a.priority), &a.name)
+      .cmp(&(&b.country, &b.city, std::cmp::Reverse(b.priority), &b.name))
+  });
+  let names = std::iter::once("None".to_string())
+    .chain(own)
+    .chain(located.iter().map(|node| node.name.clone()))
+    .collect();
+  (names, located)
+}
+
+/// Set selected exit node as the exit node through Tailscale CLI, changing
+/// LAN access in the same `tailscale set` when `allow_lan` is given.
+#[instrument(level = "debug", skip_all, err(level = "debug"))]
+pub async fn set_exit_node(exit_node: &str, allow_lan: Option<bool>) -> Result<(), AppError> {
   let flag = format!("--exit-node={exit_node}");
-  run_tailscale_cmd(&["set", &flag]).await?;
+  match allow_lan {
+    Some(allow) => {
+      let lan_flag = format!("--exit-node-allow-lan-access={allow}");
+      run_mutation(&["set", &flag, &lan_flag]).await?;
+    }
+    None => {
+      run_mutation(&["set", &flag]).await?;
+    }
+  }
   Ok(())
 }
 
-pub async fn switch_accounts(acct_name: &str) -> Result<bool, AppError> {
-  let output = run_tailscale_cmd(&["switch", acct_name]).await?;
+#[cfg(feature = "accounts")]
+#[instrument(level = "debug", skip_all, err(level = "debug"))]
+pub async fn switch_accounts(id: &ProfileId) -> Result<bool, AppError> {
+  let output = run_mutation(&["switch", id.as_str()]).await?;
   Ok(output.to_lowercase().contains("success"))
 }
 
-pub async fn get_acct_list() -> Result<Vec<String>, AppError> {
+/// A login profile from `tailscale switch --list`.
+#[cfg(feature = "accounts")]
+#[derive(Debug, Clone)]
+pub struct AccountProfile {
+  pub id: ProfileId,
+  pub tailnet: String,
+  pub account: String,
+  pub current: bool,
+}
+
+/// List login profiles, current profile first, then sorted by tailnet.
+#[cfg(feature = "accounts")]
+#[instrument(level = "debug", err(level = "debug"))]
+pub async fn get_acct_list() -> Result<Vec<AccountProfile>, AppError> {
   let accts_str = run_tailscale_cmd(&["switch", "--list"]).await?;
 
-  let ret_accts: Vec<String> = accts_str
+  let mut profiles: Vec<AccountProfile> = accts_str
     .lines()
     .filter(|line| !line.to_lowercase().starts_with("id"))
-    .filter_map(|acct| acct.split_whitespace().nth(1).map(std::string::ToString::to_string))
+    .filter_map(|line| {
+      let current = line.trim_end().ends_with('*');
+      let mut fields = line.split_whitespace().map(|f| f.trim_end_matches('*'));
+      Some(AccountProfile {
+        id: fields.next()?.into(),
+        tailnet: fields.next()?.to_string(),
+        account: fields.next().unwrap_or_default().to_string(),
+        current,
+      })
+    })
     .collect();
 
-  Ok(ret_accts)
+  profiles.sort_by(|a, b| {
+    b.current
+      .cmp(&a.current)
+      .then_with(|| a.tailnet.to_lowercase().cmp(&b.tailnet.to_lowercase()))
+  });
+
+  Ok(profiles)
+}
+
+/// Fetch and parse `tailscale status --json`.
+async fn fetch_status_json() -> Result<Value, AppError> {
+  let output = run_tailscale_cmd(&["status", "--json"]).await?;
+  Ok(serde_json::from_str(&output)?)
 }
 
-/// Get the current account name from `tailscale status --json`.
-pub async fn get_current_acct() -> Result<String, AppError> {
+/// `tailscale status --json` pretty-printed for display, or verbatim if it
+/// isn't valid JSON so parsing problems can still be reported.
+#[instrument(level = "debug", err(level = "debug"))]
+pub async fn fetch_raw_status() -> Result<String, AppError> {
   let output = run_tailscale_cmd(&["status", "--json"]).await?;
-  let status: Value = serde_json::from_str(&output)?;
+  Ok(
+    serde_json::from_str::<Value>(&output)
+      .and_then(|status| serde_json::to_string_pretty(&status))
+      .unwrap_or(output),
+  )
+}
 
-  let acct = status
+/// Get the current account name from the status JSON: this node's
+/// `Self.DNSName`, e.g. `laptop.tail1234.ts.net`.
+fn parse_current_acct(status: &Value) -> String {
+  status
     .get("Self")
     .and_then(|s| s.get("DNSName"))
     .and_then(Value::as_str)
     .map(|dns| dns.trim_end_matches('.').to_string())
+    .unwrap_or_default()
+}
+
+fn parse_tailnet(status: &Value) -> String {
+  status
+    .get("CurrentTailnet")
+    .and_then(|tailnet| tailnet.get("Name"))
+    .and_then(Value::as_str)
+    .unwrap_or_default()
+    .to_string()
+}
+
+fn parse_daemon_version(status: &Value) -> String {
+  status
+    .get("Version")
+    .and_then(Value::as_str)
+    .unwrap_or_default()
+    .to_string()
+}
+
+/// URL to finish an interactive login at, while one is pending.
+fn parse_auth_url(status: &Value) -> Option<String> {
+  status
+    .get("AuthURL")
+    .and_then(Value::as_str)
+    .filter(|url| !url.is_empty())
+    .map(str::to_string)
+}
+
+fn parse_relay(status: &Value) -> String {
+  status
+    .get("Self")
+    .and_then(|me| me.get("Relay"))
+    .and_then(Value::as_str)
+    .unwrap_or_default()
+    .to_string()
+}
+
+/// Health warnings from the status JSON, e.g. DNS problems or key expiry.
+fn parse_health(status: &Value) -> Vec<String> {
+  status
+    .get("Health")
+    .and_then(Value::as_array)
+    .map(|warnings| {
+      warnings
+        .iter()
+        .filter_map(Value::as_str)
+        .map(str::trim)
+        .filter(|warning| !warning.is_empty())
+        .map(str::to_string)
+        .collect()
+    })
+    .unwrap_or_default()
+}
+
+/// Whether the status JSON's health warnings report a captive portal.
+fn parse_captive_portal(status: &Value) -> bool {
+  parse_health(status).iter().any(|warning| {
+    let warning = warning.to_lowercase();
+    warning.contains("captive portal") || warning.contains("log in using your web browser")
+  })
+}
+
+/// Short hostname for a peer, preferring the DNS label over `HostName`.
+fn peer_name(peer: &Value) -> PeerName {
+  peer
+    .get("DNSName")
+    .and_then(Value::as_str)
+    .and_then(|dns| dns.split('.').next())
+    .filter(|name| !name.is_empty())
+    .or_else(|| peer.get("HostName").and_then(Value::as_str))
+    .unwrap_or_default()
+    .into()
+}
+
+/// `ClientVersion.LatestVersion` when `RunningLatest` is false; control only
+/// sends `ClientVersion` once it knows about the node's version.
+fn parse_update_available(status: &Value) -> Option<String> {
+  let client = status.get("ClientVersion")?;
+  if client.get("RunningLatest").and_then(Value::as_bool).unwrap_or(true) {
+    return None;
+  }
+  client
+    .get("LatestVersion")
+    .and_then(Value::as_str)
+    .filter(|version| !version.is_empty())
+    .map(str::to_string)
+}
+
+fn parse_backend_state(status: &Value) -> BackendState {
+  status
+    .get("BackendState")
+    .and_then(Value::as_str)
+    .map(BackendState::parse)
+    .unwrap_or_default()
+}
+
+/// When this node's key expires, from the RFC 3339 `Self.KeyExpiry`.
+fn parse_key_expiry(status: &Value) -> Option<i64> {
+  let expiry = status.get("Self")?.get("KeyExpiry")?.as_str()?;
+  chrono::DateTime::parse_from_rfc3339(expiry)
+    .inspect_err(|e| warn!("Unparseable KeyExpiry {expiry}: {e}"))
+    .ok()
+    .map(|expiry| expiry.timestamp())
+}
+
+/// Capabilities of this node, from `Self.CapMap` keys and the older `Self.Capabilities`.
+fn parse_capabilities(status: &Value) -> Vec<String> {
+  let Some(me) = status.get("Self") else {
+    return Vec::new();
+  };
+
+  let mut caps: Vec<String> = me
+    .get("CapMap")
+    .and_then(Value::as_object)
+    .map(|caps| caps.keys().cloned().collect())
     .unwrap_or_default();
+  if let Some(list) = me.get("Capabilities").and_then(Value::as_array) {
+    caps.extend(list.iter().filter_map(Value::as_str).map(str::to_string));
+  }
+
+  caps.sort();
+  caps.dedup();
+  caps
+}
+
+/// Name of the peer this device uses as its exit node, empty for none.
+fn parse_exit_node(status: &Value) -> String {
+  status
+    .get("Peer")
+    .and_then(Value::as_object)
+    .and_then(|peers| {
+      peers
+        .values()
+        .find(|peer| peer.get("ExitNode").and_then(Value::as_bool) == Some(true))
+    })
+    .map(|peer| peer_name(peer).to_string())
+    .unwrap_or_default()
+}
+
+/// All peers with their online state and last handshake.
+fn parse_peers(status: &Value) -> Vec<Peer> {
+  let Some(peers) = status.get("Peer").and_then(Value::as_object) else {
+    return Vec::new();
+  };
+
+  let self_user = status
+    .get("Self")
+    .and_then(|me| me.get("UserID"))
+    .and_then(Value::as_u64);
+
+  // Users are listed separately, keyed by user ID.
+  let owner = |peer: &Value| {
+    let id = peer.get("UserID").and_then(Value::as_u64)?;
+    let user = status.get("User")?.get(id.to_string())?;
+    user.get("LoginName").and_then(Value::as_str).map(str::to_string)
+  };
+
+  // Unset times are the zero time, which is far before the epoch.
+  let timestamp = |peer: &Value, key: &str| {
+    peer
+      .get(key)
+      .and_then(Value::as_str)
+      .and_then(|time| chrono::DateTime::parse_from_rfc3339(time).ok())
+      .map(|time| time.timestamp())
+      .filter(|secs| *secs > 0)
+  };
+
+  let mut peers: Vec<Peer> = peers
+    .values()
+    .map(|peer| Peer {
+      id: peer.get("ID").and_then(Value::as_str).unwrap_or_default().into(),
+      name: peer_name(peer),
+      dns_name: peer
+        .get("DNSName")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .trim_end_matches('.')
+        .to_string(),
+      ips: peer
+        .get("TailscaleIPs")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|ip| ip.as_str()?.parse().ok())
+        .collect(),
+      os: peer.get("OS").and_then(Value::as_str).unwrap_or_default().to_string(),
+      owner: owner(peer).unwrap_or_default(),
+      tags: peer
+        .get("Tags")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .map(String::from)
+        .collect(),
+      ssh: peer
+        .get("sshHostKeys")
+        .and_then(Value::as_array)
+        .is_some_and(|keys| !keys.is_empty()),
+      exit_node_option: peer.get("ExitNodeOption").and_then(Value::as_bool).unwrap_or(false),
+      expired: peer.get("Expired").and_then(Value::as_bool).unwrap_or(false),
+      cur_addr: peer.get("CurAddr").and_then(Value::as_str).unwrap_or_default().to_string(),
+      relay: peer.get("Relay").and_then(Value::as_str).unwrap_or_default().to_string(),
+      online: peer.get("Online").and_then(Value::as_bool).unwrap_or(false),
+      active: peer.get("Active").and_then(Value::as_bool).unwrap_or(false),
+      last_handshake: timestamp(peer, "LastHandshake"),
+      last_seen: timestamp(peer, "LastSeen"),
+      mine: self_user.is_some() && peer.get("UserID").and_then(Value::as_u64) == self_user,
+      rx_bytes: peer.get("RxBytes").and_then(Value::as_u64).unwrap_or_default(),
+      tx_bytes: peer.get("TxBytes").and_then(Value::as_u64).unwrap_or_default(),
+    })
+    .collect();
+
+  peers.sort_by(|a, b| a.name.cmp(&b.name));
+  peers
+}
+
+/// Subnet routes advertised by peers (excluding exit node default routes).
+///
+/// A route is in use by this device when the peer is its primary router,
+/// which Tailscale reports through `PrimaryRoutes`.
+fn parse_subnet_routers(status: &Value) -> Vec<SubnetRouter> {
+  let Some(peers) = status.get("Peer").and_then(Value::as_object) else {
+    return Vec::new();
+  };
+
+  let is_subnet = |route: &&str| !is_default_route(route);
+
+  let mut routers: Vec<SubnetRouter> = peers
+    .values()
+    .filter_map(|peer| {
+      let primary: Vec<String> = peer
+        .get("PrimaryRoutes")
+        .and_then(Value::as_array)
+        .map(|routes| {
+          routes
+            .iter()
+            .filter_map(Value::as_str)
+            .filter(is_subnet)
+            .map(str::to_string)
+            .collect()
+        })
+        .unwrap_or_default();
+
+      // AllowedIPs includes the peer's own addresses, so only count
+      // prefixes wider than a single host.
+      let advertised: Vec<String> = peer
+        .get("AllowedIPs")
+        .and_then(Value::as_array)
+        .map(|routes| {
+          routes
+            .iter()
+            .filter_map(Value::as_str)
+            .filter(is_subnet)
+            .filter(|route| !route.ends_with("/32") && !route.ends_with("/128"))
+            .map(str::to_string)
+            .collect()
+        })
+        .unwrap_or_default();
+
+      let routes = if primary.is_empty() { advertised } else { primary.clone() };
+      if routes.is_empty() {
+        return None;
+      }
+
+      Some(SubnetRouter {
+        name: peer_name(peer),
+        routes,
+        online: peer.get("Online").and_then(Value::as_bool).unwrap_or(false),
+        primary: !primary.is_empty(),
+      })
+    })
+    .collect();
 
-  Ok(acct)
+  routers.sort_by(|a, b| a.name.cmp(&b.name));
+  routers
 }
@@ -1,12 +1,26 @@
+#[cfg(feature = "taildrop")]
+mod checksum;
 mod config;
+mod dbus;
+mod diagnostics;
 mod error;
+mod export;
+#[cfg(feature = "taildrop")]
+mod history;
 mod i18n;
+mod ids;
 mod logic;
+mod logging;
+mod shared_state;
 mod window;
 
+use crate::config::TailscaleConfig;
 use crate::window::Window;
 
 fn main() -> cosmic::iced::Result {
+  let config = TailscaleConfig::load_quiet();
+  logging::init(config.log_to_file, config.verbose_logging);
+
   let requested_languages = i18n_embed::DesktopLanguageRequester::requested_languages();
   i18n::init(&requested_languages);
 
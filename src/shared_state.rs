@@ -0,0 +1,149 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use tokio::fs;
+use tracing::{debug, warn};
+
+use crate::error::AppError;
+use crate::logic::{fetch_tailscale_state, TailscaleState};
+
+/// How long a cached state is reused instead of querying the CLI again.
+const CACHE_FRESH_FOR: Duration = Duration::from_secs(2);
+/// A lock older than this is assumed to belong to a crashed instance.
+const LOCK_STALE_AFTER: Duration = Duration::from_secs(30);
+const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(200);
+const LOCK_POLL_ATTEMPTS: u32 = 25;
+
+/// Removes the fetch lock when dropped.
+struct FetchLock(PathBuf);
+
+impl Drop for FetchLock {
+  fn drop(&mut self) {
+    if let Err(e) = std::fs::remove_file(&self.0) {
+      warn!("Failed to remove shared state lock: {e}");
+    }
+  }
+}
+
+/// Where instances share state: under `XDG_RUNTIME_DIR`, which only the user
+/// can access. There is no fallback, as other users could read or plant the
+/// cache in a shared directory such as `/tmp`.
+fn state_dir() -> Option<PathBuf> {
+  dirs::runtime_dir().map(|dir| dir.join("gui-scale-applet"))
+}
+
+fn cache_path(dir: &Path) -> PathBuf {
+  dir.join("state.json")
+}
+
+fn lock_path(dir: &Path) -> PathBuf {
+  dir.join("state.lock")
+}
+
+fn age(modified: SystemTime) -> Duration {
+  SystemTime::now()
+    .duration_since(modified)
+    .unwrap_or_default()
+}
+
+/// Read the cached state along with the time it was written.
+async fn read_cache(dir: &Path) -> Option<(SystemTime, TailscaleState)> {
+  let path = cache_path(dir);
+  let modified = fs::metadata(&path).await.ok()?.modified().ok()?;
+  let contents = fs::read_to_string(&path).await.ok()?;
+
+  match serde_json::from_str(&contents) {
+    Ok(state) => Some((modified, state)),
+    Err(e) => {
+      debug!("Ignoring unreadable shared state cache: {e}");
+      None
+    }
+  }
+}
+
+/// Atomically replace the cache so readers never see a partial write.
+async fn write_cache(dir: &Path, state: &TailscaleState) -> Result<SystemTime, AppError> {
+  let path = cache_path(dir);
+  let tmp = path.with_extension(format!("json.{}", std::process::id()));
+
+  fs::write(&tmp, serde_json::to_vec(state)?).await?;
+  fs::rename(&tmp, &path).await?;
+
+  Ok(fs::metadata(&path).await?.modified()?)
+}
+
+/// Try to become the instance that fetches; `None` if another one already is.
+async fn try_lock(dir: &Path) -> Option<FetchLock> {
+  let path = lock_path(dir);
+  if let Err(e) = fs::create_dir_all(dir).await {
+    warn!("Failed to create shared state directory: {e}");
+    return None;
+  }
+
+  if let Ok(meta) = fs::metadata(&path).await
+    && meta.modified().is_ok_and(|modified| age(modified) > LOCK_STALE_AFTER)
+  {
+    debug!("Removing stale shared state lock");
+    let _ = fs::remove_file(&path).await;
+  }
+
+  fs::OpenOptions::new()
+    .write(true)
+    .create_new(true)
+    .open(&path)
+    .await
+    .ok()
+    .map(|_| FetchLock(path))
+}
+
+/// Fetch Tailscale state, sharing the result with other applet instances.
+///
+/// Unless `force` is set, a recently cached state is reused. When another
+/// instance is already fetching, this waits for its result instead of
+/// spawning a second round of CLI calls. Without a runtime directory to share
+/// through, every instance fetches its own.
+pub async fn fetch_shared_state(force: bool) -> Result<(SystemTime, TailscaleState), AppError> {
+  let Some(dir) = state_dir() else {
+    let state = fetch_tailscale_state().await?;
+    return Ok((SystemTime::now(), state));
+  };
+
+  if !force
+    && let Some((modified, state)) = read_cache(&dir).await
+    && age(modified) < CACHE_FRESH_FOR
+  {
+    return Ok((modified, state));
+  }
+
+  if let Some(_lock) = try_lock(&dir).await {
+    let state = fetch_tailscale_state().await?;
+    let modified = write_cache(&dir, &state).await.unwrap_or_else(|e| {
+      warn!("Failed to write shared state cache: {e}");
+      SystemTime::now()
+    });
+    return Ok((modified, state));
+  }
+
+  let started = SystemTime::now();
+  for _ in 0..LOCK_POLL_ATTEMPTS {
+    tokio::time::sleep(LOCK_POLL_INTERVAL).await;
+    if let Some((modified, state)) = read_cache(&dir).await
+      && modified >= started
+    {
+      return Ok((modified, state));
+    }
+  }
+
+  debug!("No state from another instance; fetching directly");
+  let state = fetch_tailscale_state().await?;
+  Ok((SystemTime::now(), state))
+}
+
+/// Return the cached state if another instance wrote it after `since`.
+pub async fn read_newer_state(since: Option<SystemTime>) -> Option<(SystemTime, TailscaleState)> {
+  let (modified, state) = read_cache(&state_dir()?).await?;
+  match since {
+    Some(since) if modified <= since => None,
+    _ => Some((modified, state)),
+  }
+}
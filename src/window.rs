@@ -1,39 +1,292 @@
 use crate::config::TailscaleConfig;
+use crate::dbus::{self, DbusCommand};
+use crate::diagnostics::{generate_bundle, DiagnosticsInput};
 use crate::fl;
+use crate::ids::PeerName;
 use crate::logic::{
-  clear_status, enable_exit_node, exit_node_allow_lan_access, fetch_tailscale_state,
-  set_exit_node, set_routes, set_ssh, switch_accounts, tailscale_int_up,
-  tailscale_receive, tailscale_send, TailscaleState,
+  configure_cli, daemon_socket, enable_exit_node, exit_node_allow_lan_access, fetch_client_version,
+  fetch_derp_regions, fetch_exit_node_lists, fetch_funnel_hosts, fetch_ssh_access, grant_operator,
+  host_has_network, measure_clock_skew, open_url, ping_once, play_sound, run_login, run_update,
+  set_exit_node, set_routes, set_ssh, start_daemon, tailscale_int_up, BackendState, DerpRegion,
+  ExitNodeLists, LoginEvent, Peer, PingPath, PingReply, SubnetRouter, TailscaleState, UpdateEvent,
+  CAP_FUNNEL,
 };
+use crate::shared_state::{fetch_shared_state, read_newer_state};
 use cosmic::app::Core;
 use cosmic::cosmic_config::{Config, CosmicConfigEntry};
-use cosmic::dialog::file_chooser::{self, FileFilter};
+use cosmic::iced::futures::{SinkExt, StreamExt};
 use cosmic::iced::{
   alignment::Horizontal,
   platform_specific::shell::commands::popup::{destroy_popup, get_popup},
-  widget::{column, horizontal_space, row},
+  widget::{column, horizontal_space, qr_code, row, stack},
   window::Id,
-  Alignment, Length, Limits,
+  task, Alignment, Length, Limits, Subscription,
 };
 use cosmic::iced_runtime::core::window;
 use cosmic::iced_widget::Row;
 use cosmic::widget::{
-  button, dropdown, list_column,
+  button, container, icon, list_column, mouse_area,
   settings::{self},
   text, toggler,
 };
-use cosmic::{Action, Element, Task};
+use cosmic::{Action, Application, Element, Task};
+use std::any::TypeId;
+use std::collections::VecDeque;
+use std::net::IpAddr;
 use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
 use tracing::{error, warn};
-use url::Url;
 
-const ID: &str = "com.github.bhh32.GUIScaleApplet";
-const DEFAULT_EXIT_NODE: &str = "Select Exit Node";
+#[cfg(feature = "accounts")]
+mod accounts;
+mod advanced;
+mod advertise_routes;
+mod exit_locations;
+mod exit_node_picker;
+mod netcheck;
+mod notifications;
+mod panel_icons;
+mod peers;
+mod raw_status;
+mod staging;
+#[cfg(feature = "taildrop")]
+mod taildrop;
+mod whois;
+
+#[cfg(feature = "accounts")]
+use accounts::{AccountsMessage, AccountsState};
+use advanced::{AdvancedMessage, AdvancedState};
+use advertise_routes::{AdvertiseRoutesMessage, AdvertiseRoutesState};
+use exit_locations::{ExitLocationsMessage, ExitLocationsState};
+use exit_node_picker::{ExitNodePickerMessage, ExitNodePickerState};
+use netcheck::{NetcheckMessage, NetcheckState};
+use notifications::NotificationQueue;
+use panel_icons::{PanelIcon, PanelIcons};
+use peers::{PeerHighlights, PeerPings, PeersMessage};
+use raw_status::{RawStatusMessage, RawStatusState};
+use staging::{StagingMessage, StagingState};
+#[cfg(feature = "taildrop")]
+use taildrop::{inbox_subscription, TaildropMessage, TaildropState};
+use whois::{WhoisMessage, WhoisState};
+
+pub const ID: &str = "com.github.bhh32.GUIScaleApplet";
 const POPUP_MAX_WIDTH: f32 = 720.0;
-const POPUP_MIN_WIDTH: f32 = 640.0;
+/// Low enough for the compositor to shrink the popup on a small or highly scaled output.
+const POPUP_MIN_WIDTH: f32 = 360.0;
 const POPUP_MAX_HEIGHT: f32 = 1080.0;
 const POPUP_MIN_HEIGHT: f32 = 200.0;
-const STATUS_CLEAR_TIME: u64 = 5;
+/// `xdg_positioner` constraint adjustments: slide, flip and resize on both axes,
+/// so a popup that doesn't fit next to the panel is moved or shrunk onto the
+/// applet's output instead of opening partly off-screen.
+const POPUP_CONSTRAINT_ADJUSTMENT: u32 = 0x3f;
+const SHARED_STATE_POLL_SECS: u64 = 2;
+/// How often the connection is refreshed while the popup is open.
+const POPUP_REFRESH_SECS: u64 = 5;
+/// How often the peer list is refreshed while the popup is open.
+const PEERS_REFRESH_SECS: u64 = 5;
+/// How often state is refreshed while the exit node is suspended for a captive portal.
+const CAPTIVE_PORTAL_POLL_SECS: u64 = 10;
+/// How often the system clock is compared with the coordination server's.
+const CLOCK_CHECK_SECS: u64 = 3600;
+/// Clock skew beyond this is warned about; node keys carry validity times, so
+/// a clock this far off can make them look expired or not yet valid.
+const CLOCK_SKEW_WARN_SECS: u64 = 60;
+/// How often the exit node in use is pinged while the popup is open.
+const EXIT_NODE_PING_SECS: u64 = 15;
+/// How often state is refreshed while waiting for a browser login to complete.
+const LOGIN_POLL_SECS: u64 = 3;
+const LOGIN_QR_CELL_SIZE: u16 = 4;
+/// Size of the badge overlaid on the panel icon.
+const PANEL_BADGE_SIZE: u16 = 10;
+/// How long a copied address is labelled "Copied".
+const COPIED_FEEDBACK_SECS: u64 = 2;
+/// Key expiry is highlighted once it is closer than this many days.
+const KEY_EXPIRY_WARN_DAYS: i64 = 7;
+/// Number of recent state snapshots kept for the diagnostics bundle.
+const STATE_HISTORY_LEN: usize = 10;
+/// Quiet period after a toggle flip before its command runs, so rapid flips collapse into one.
+const SETTING_DEBOUNCE_MS: u64 = 300;
+/// A toggle's command running longer than this is offered for cancelling.
+const STUCK_COMMAND_SECS: u64 = 20;
+/// Dropdowns are sized to their longest label within these bounds.
+const DROPDOWN_MIN_WIDTH: f32 = 110.0;
+const DROPDOWN_MAX_WIDTH: f32 = 260.0;
+/// Average glyph width of the interface font, used to estimate label widths.
+const DROPDOWN_CHAR_WIDTH: f32 = 7.5;
+/// Horizontal padding plus the dropdown arrow.
+const DROPDOWN_CHROME_WIDTH: f32 = 40.0;
+const SOUND_CONNECTED: &str = "network-connectivity-established";
+const SOUND_DISCONNECTED: &str = "network-connectivity-lost";
+
+/// Most recent error per popup section, shown inside that section.
+#[derive(Debug, Default)]
+struct SectionErrors {
+  refresh: Option<String>,
+  #[cfg(feature = "taildrop")]
+  taildrop: Option<String>,
+  settings: Option<String>,
+}
+
+/// Config writes that failed, for the warning banner and diagnostics.
+#[derive(Default)]
+struct ConfigWriteFailures {
+  count: usize,
+  last_error: Option<String>,
+  /// The banner was dismissed; the next failure shows it again.
+  dismissed: bool,
+}
+
+impl ConfigWriteFailures {
+  fn record(&mut self, what: &str, e: &cosmic::cosmic_config::Error) {
+    error!("Failed to save {what}: {e}");
+    self.count += 1;
+    self.last_error = Some(format!("{what}: {e}"));
+    self.dismissed = false;
+  }
+
+  /// Latest failure, unless the banner was dismissed.
+  fn banner(&self) -> Option<&str> {
+    self.last_error.as_deref().filter(|_| !self.dismissed)
+  }
+}
+
+/// Toggles whose CLI command is debounced.
+#[derive(Clone, Copy, Debug)]
+pub enum Setting {
+  Ssh,
+  Routes,
+  Connect,
+}
+
+/// A toggle's command that hasn't finished yet.
+struct InFlight {
+  /// When the command started, or when the user last chose to keep waiting.
+  since: Instant,
+  handle: task::Handle,
+}
+
+/// Debounce bookkeeping for one toggle.
+#[derive(Default)]
+struct PendingSetting {
+  /// Bumped on every flip; a scheduled command only runs if it is still the latest.
+  latest: u64,
+  applied: u64,
+  /// Commands run one at a time, so they finish in the order they started.
+  in_flight: VecDeque<InFlight>,
+}
+
+impl PendingSetting {
+  /// Flipped but not yet confirmed by the CLI, so refreshed state is stale.
+  fn busy(&self) -> bool {
+    self.latest != self.applied || !self.in_flight.is_empty()
+  }
+
+  /// The oldest command has run for longer than it should.
+  fn stuck(&self) -> bool {
+    self
+      .in_flight
+      .front()
+      .is_some_and(|command| command.since.elapsed() >= Duration::from_secs(STUCK_COMMAND_SECS))
+  }
+
+  /// The oldest command finished.
+  fn finished(&mut self) {
+    self.in_flight.pop_front();
+  }
+}
+
+#[derive(Default)]
+struct PendingSettings {
+  ssh: PendingSetting,
+  routes: PendingSetting,
+  connect: PendingSetting,
+}
+
+impl PendingSettings {
+  fn get(&mut self, setting: Setting) -> &mut PendingSetting {
+    match setting {
+      Setting::Ssh => &mut self.ssh,
+      Setting::Routes => &mut self.routes,
+      Setting::Connect => &mut self.connect,
+    }
+  }
+
+  fn all(&self) -> [(Setting, &PendingSetting); 3] {
+    [
+      (Setting::Ssh, &self.ssh),
+      (Setting::Routes, &self.routes),
+      (Setting::Connect, &self.connect),
+    ]
+  }
+}
+
+/// Who last changed a preference, as far as the applet can tell.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum ChangeSource {
+  #[default]
+  Unknown,
+  Applet,
+  /// Another client, such as the CLI or another applet instance.
+  External,
+  /// The applet's change was refused by a system policy.
+  Policy,
+}
+
+/// Provenance of the preferences the popup lets users change.
+#[derive(Debug, Default)]
+struct PrefSources {
+  ssh: ChangeSource,
+  routes: ChangeSource,
+  exit_node: ChangeSource,
+  /// An exit node change is in flight, so refreshed state may be stale.
+  exit_node_pending: bool,
+}
+
+/// Flag a preference as changed externally when a refresh disagrees with what was shown.
+fn note_drift<T: PartialEq + ?Sized>(source: &mut ChangeSource, shown: &T, actual: &T) {
+  if shown != actual {
+    *source = ChangeSource::External;
+  }
+}
+
+/// Record who last changed a preference after the applet tried to set it.
+fn note_set_result(source: &mut ChangeSource, result: &Result<(), String>) {
+  *source = match result {
+    Ok(()) => ChangeSource::Applet,
+    Err(e) if is_policy_error(e) => ChangeSource::Policy,
+    Err(_) => return,
+  };
+}
+
+fn is_policy_error(error: &str) -> bool {
+  let error = error.to_lowercase();
+  error.contains("policy") || error.contains("managed by")
+}
+
+/// Whether a CLI error says the feature is switched off for the tailnet.
+fn is_not_enabled_error(error: &str) -> bool {
+  let error = error.to_lowercase();
+  error.contains("not enabled") || (error.contains("disabled") && error.contains("admin"))
+}
+
+/// Progress of a login started from the popup.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+enum LoginFlow {
+  #[default]
+  Idle,
+  Starting,
+  WaitingForBrowserAuth,
+  Success,
+  TimedOut,
+  Failed(String),
+}
+
+impl LoginFlow {
+  /// `tailscale login` is still running.
+  fn in_progress(&self) -> bool {
+    matches!(self, LoginFlow::Starting | LoginFlow::WaitingForBrowserAuth)
+  }
+}
 
 /// Holds the applet's state
 #[allow(clippy::struct_excessive_bools)]
@@ -42,63 +295,296 @@ pub struct Window {
   config: TailscaleConfig,
   config_handler: Option<Config>,
   popup: Option<Id>,
+  /// Right-click menu on the panel icon.
+  context_menu: Option<Id>,
   ssh: bool,
+  ssh_confirm: bool,
+  disconnect_confirm: bool,
+  /// `None` while the SSH policy is being read for the confirmation.
+  ssh_access: Option<Result<Vec<String>, String>>,
   routes: bool,
+  subnet_routers: Vec<SubnetRouter>,
+  peers: Vec<Peer>,
+  /// Panel icon for each connection state, from the config at startup.
+  panel_icons: PanelIcons,
+  /// Summary shown when hovering the panel icon, rebuilt on each refresh.
+  panel_tooltip: String,
+  peer_highlights: PeerHighlights,
+  peer_pings: PeerPings,
+  notifications: NotificationQueue,
+  /// Auto-connect hasn't been considered yet this session.
+  auto_connect_pending: bool,
+  /// The login session is locked; only tracked with `pause_when_locked`.
+  session_locked: bool,
+  /// Tailnet capabilities that Taildrop, exit node locations and Funnel are gated on.
+  capabilities: Vec<String>,
+  /// Funnel addresses, read each time the popup opens; `None` until then.
+  funnel: Option<Result<Vec<String>, String>>,
   connect: bool,
-  device_options: Vec<String>,
-  selected_device: String,
-  selected_device_idx: Option<usize>,
-  send_files: Vec<PathBuf>,
-  send_file_status: String,
-  files_sent: bool,
-  receive_file_status: String,
+  pending_settings: PendingSettings,
+  pref_sources: PrefSources,
+  staging: StagingState,
+  raw_status: RawStatusState,
+  advertise_routes: AdvertiseRoutesState,
+  exit_locations: ExitLocationsState,
+  exit_node_picker: ExitNodePickerState,
+  advanced: AdvancedState,
+  netcheck: NetcheckState,
+  whois: WhoisState,
+  #[cfg(feature = "taildrop")]
+  taildrop: TaildropState,
   avail_exit_nodes: Vec<String>,
-  sel_exit_node: String,
-  sel_exit_node_idx: Option<usize>,
-  acct_list: Vec<String>,
+  /// Devices and exit nodes are only fetched once something shows them.
+  details_wanted: bool,
+  /// D-Bus exit node request waiting for the exit node list to load.
+  deferred_exit_node: Option<String>,
+  /// Exit node in use, or `None` for none.
+  sel_exit_node: Option<PeerName>,
+  #[cfg(feature = "accounts")]
+  accounts: AccountsState,
   cur_acct: String,
+  tailnet: String,
   allow_lan: bool,
   is_exit_node: bool,
-  ip: String,
-  conn_status: bool,
+  /// `tailscale set` commands for the host exit node and LAN access toggles are running.
+  host_exit_node_pending: bool,
+  lan_access_pending: bool,
+  captive_portal: bool,
+  /// The machine has a network route outside Tailscale; without one,
+  /// connecting can't work.
+  host_online: bool,
+  limited: bool,
+  health: Vec<String>,
+  /// Seconds the system clock is ahead of the coordination server's, and when
+  /// that was last measured.
+  clock_skew: Option<i64>,
+  clock_checked: Option<Instant>,
+  needs_login: bool,
+  key_expired: bool,
+  key_expiry: Option<i64>,
+  key_expiry_disabled: bool,
+  login_url: Option<String>,
+  /// QR code of `login_url` for logging in from a phone.
+  login_qr: Option<qr_code::Data>,
+  login_flow: LoginFlow,
+  /// Distinguishes login subscriptions so a retry starts a fresh `tailscale login`.
+  login_attempt: u64,
+  /// Exit node turned off to log into a captive portal, restored once it clears.
+  suspended_exit_node: Option<String>,
+  /// `None` until the first state refresh.
+  ips: Option<Vec<IpAddr>>,
+  /// The IPv6 address row is expanded.
+  show_ipv6: bool,
+  /// The subnet routes section is expanded.
+  show_routes: bool,
+  /// Address just copied, labelled "Copied" for a moment.
+  copied_address: Option<IpAddr>,
+  dns_name: String,
+  /// DNS settings from the tailnet are applied.
+  accept_dns: bool,
+  /// Exit node traffic currently leaves through, per the status JSON.
+  egress_node: String,
+  /// Round trip to the exit node, with the node it was measured to, refreshed
+  /// while the popup is open.
+  exit_node_latency: Option<(String, Result<PingReply, String>)>,
+  exit_node_ping_pending: bool,
+  backend_state: BackendState,
+  shared_state_stamp: Option<SystemTime>,
+  state_history: VecDeque<TailscaleState>,
+  diagnostics_status: String,
+  peer_export_status: String,
+  dbus_conn: Option<zbus::Connection>,
+  dbus_last_state: Option<(bool, String, String)>,
+  last_errors: SectionErrors,
+  config_writes: ConfigWriteFailures,
+  daemon_running: bool,
+  daemon_start_status: String,
+  is_operator: bool,
+  operator_status: String,
+  /// Operator from the prefs, for the backend info rows.
+  operator_user: String,
+  daemon_version: String,
+  /// Home DERP region code, e.g. `fra`.
+  relay: String,
+  /// DERP regions for the relay row and netcheck, loaded when the popup first opens.
+  derp_regions: Option<Vec<DerpRegion>>,
+  /// CLI version for the popup footer, fetched once when the popup first opens.
+  client_version: Option<String>,
+  /// Newer stable version control reports, shown as a chip in the footer.
+  update_available: Option<String>,
+  /// A `tailscale update` is running; its subscription is keyed by the attempt.
+  updating: bool,
+  update_attempt: u64,
+  /// Latest update progress, or how the update ended.
+  update_status: String,
 }
 
 /// Messages to be sent to the Libcosmic Update function
 #[derive(Clone, Debug)]
 pub enum Message {
   TogglePopup,
+  /// Show or hide the panel icon's tooltip.
+  Surface(cosmic::surface::Action),
   PopupClosed(Id),
   EnableSSH(bool),
-  SshSet(bool, bool),
+  SshAccessLoaded(Result<Vec<String>, String>),
+  FunnelLoaded(Result<Vec<String>, String>),
+  ConfirmEnableSsh,
+  CancelEnableSsh,
+  SshSet(bool, Result<(), String>),
   AcceptRoutes(bool),
-  RoutesSet(bool, bool),
+  RoutesSet(bool, Result<(), String>),
   ConnectDisconnect(bool),
-  ConnectionSet(bool, bool),
-  SwitchAccount(usize),
-  DeviceSelected(usize),
-  ChooseFiles,
-  FilesSelected(Vec<Url>),
-  SendFiles,
-  FilesSent(Option<String>),
-  FileChoosingCancelled,
-  ReceiveFiles,
-  FilesReceived(String),
-  ExitNodeSelected(usize),
-  ExitNodeSet(String, usize, bool),
+  ConnectionToggled(bool),
+  ConfirmDisconnect,
+  CancelDisconnect,
+  ConnectionSet(bool, Result<(), String>),
+  ApplySetting(Setting, u64),
+  CheckStuckCommand(Setting),
+  KeepWaiting(Setting),
+  CancelCommand(Setting),
+  ToggleContextMenu,
+  QuickSwitchExitNode,
+  #[cfg(feature = "accounts")]
+  Accounts(AccountsMessage),
+  #[cfg(feature = "taildrop")]
+  Taildrop(TaildropMessage),
+  Staging(StagingMessage),
+  RawStatus(RawStatusMessage),
+  AdvertiseRoutes(AdvertiseRoutesMessage),
+  Advanced(AdvancedMessage),
+  Netcheck(NetcheckMessage),
+  Whois(WhoisMessage),
+  Peers(PeersMessage),
+  /// Exit node to use, or `None` to stop using one.
+  ExitNodeSelected(Option<PeerName>),
+  ExitLocations(ExitLocationsMessage),
+  ExitNodePicker(ExitNodePickerMessage),
+  PingExitNode,
+  ExitNodePinged(String, Result<PingReply, String>),
+  /// Exit node that was set (`None` to stop using one), and the LAN access applied with it.
+  ExitNodeSet(Option<PeerName>, Option<bool>, Result<(), String>),
   AllowExitNodeLanAccess(bool),
-  LanAccessSet(bool, bool),
+  LanAccessSet(bool, Result<(), String>),
   UpdateIsExitNode(bool),
-  ExitNodeEnabled(bool, bool),
-  ClearTailDropStatus,
+  ExitNodeEnabled(bool, Result<(), String>),
+  SuspendExitNode,
+  RestoreExitNode,
+  /// Refresh every section.
   RefreshState,
-  StateRefreshed(Box<TailscaleState>),
+  /// Refresh the connection, preferring state another instance just fetched.
+  LoadSharedState,
+  SyncSharedState,
+  SharedStateUnchanged,
+  ConnectionRefreshed(SystemTime, Box<TailscaleState>),
+  RefreshPeers,
+  PeersRefreshed(Vec<Peer>, Vec<SubnetRouter>),
+  ExitNodesRefreshed(Result<ExitNodeLists, String>),
   RefreshFailed(String),
+  HostNetworkChecked(bool),
+  DismissRefreshError,
+  DismissSettingsError,
+  DismissConfigWarning,
+  StartDaemon,
+  DaemonStarted(bool),
+  TogglePlaySounds(bool),
+  ToggleNotifications(bool),
+  ToggleAutoConnect(bool),
+  TogglePauseWhenLocked(bool),
+  ToggleCheckClock(bool),
+  ToggleConfirmDisconnect(bool),
+  SessionLocked(bool),
+  GrantOperator,
+  OperatorGranted(bool),
+  StartLogin,
+  Login(LoginEvent),
+  OpenLoginUrl,
+  CopyLoginUrl,
+  CopyDnsName,
+  CopyAddress(IpAddr),
+  ClearCopiedAddress(IpAddr),
+  ToggleIpv6,
+  ToggleRoutes,
+  PrunePeerHighlights,
+  FlushNotifications,
+  GenerateDiagnostics,
+  DiagnosticsGenerated(Result<PathBuf, String>),
+  ClientVersionLoaded(Result<String, String>),
+  ClockSkewMeasured(Result<i64, String>),
+  DerpRegionsLoaded(Result<Vec<DerpRegion>, String>),
+  UpdateClient,
+  Update(UpdateEvent),
+  DbusReady(zbus::Connection),
+  Dbus(DbusCommand),
+}
+
+impl Message {
+  /// Whether handling this message changes Tailscale state.
+  fn is_mutation(&self) -> bool {
+    match self {
+      #[cfg(feature = "accounts")]
+      Message::Accounts(msg) => return msg.is_mutation(),
+      #[cfg(feature = "taildrop")]
+      Message::Taildrop(msg) => return msg.is_mutation(),
+      Message::Staging(msg) => return msg.is_mutation(),
+      Message::ExitLocations(msg) => return msg.is_mutation(),
+      Message::ExitNodePicker(msg) => return msg.is_mutation(),
+      Message::Peers(msg) => return msg.is_mutation(),
+      Message::AdvertiseRoutes(msg) => return msg.is_mutation(),
+      Message::Advanced(msg) => return msg.is_mutation(),
+      _ => {}
+    }
+
+    matches!(
+      self,
+      Message::EnableSSH(_)
+        | Message::ConfirmEnableSsh
+        | Message::AcceptRoutes(_)
+        | Message::ConnectDisconnect(_)
+        | Message::ConnectionToggled(_)
+        | Message::ConfirmDisconnect
+        | Message::ApplySetting(..)
+        | Message::ExitNodeSelected(_)
+        | Message::QuickSwitchExitNode
+        | Message::AllowExitNodeLanAccess(_)
+        | Message::UpdateIsExitNode(_)
+        | Message::SuspendExitNode
+        | Message::RestoreExitNode
+        | Message::StartDaemon
+        | Message::GrantOperator
+        | Message::StartLogin
+        | Message::UpdateClient
+        | Message::Dbus(_)
+    )
+  }
 }
 
 impl Window {
+  /// Record the outcome of a settings change for the settings section.
+  fn settings_result(&mut self, result: Result<(), String>, what: &str) -> bool {
+    match result {
+      Ok(()) => {
+        self.last_errors.settings = None;
+        true
+      }
+      Err(e) => {
+        error!("Failed to set {what}: {e}");
+        let error = if is_not_enabled_error(&e) {
+          fl!("not-enabled-for-tailnet")
+        } else {
+          e
+        };
+        self.last_errors.settings = Some(fl!("setting-failed", setting = what, error = error));
+        false
+      }
+    }
+  }
+
   fn create_popup(&mut self) -> Task<Action<Message>> {
     let new_id = Id::unique();
     self.popup.replace(new_id);
+    #[cfg(feature = "taildrop")]
+    self.taildrop.mark_results_seen();
 
     let Some(main_id) = self.core.main_window_id() else {
       warn!("No main window ID available for popup");
@@ -111,17 +597,882 @@ impl Window {
         .applet
         .get_popup_settings(main_id, new_id, None, None, None);
 
+    // Without a grab the compositor won't dismiss the popup on focus loss.
+    popup_settings.grab = !self.keep_popup_open();
+    popup_settings.positioner.constraint_adjustment = POPUP_CONSTRAINT_ADJUSTMENT;
+
     popup_settings.positioner.size_limits = Limits::NONE
       .max_width(POPUP_MAX_WIDTH)
       .min_width(POPUP_MIN_WIDTH)
       .min_height(POPUP_MIN_HEIGHT)
       .max_height(POPUP_MAX_HEIGHT);
 
-    get_popup(popup_settings)
+    Task::batch([
+      get_popup(popup_settings),
+      refresh_peers(),
+      self.load_details(),
+      self.detect_lan_subnet(),
+      self.load_client_version(),
+      self.load_derp_regions(),
+      self.check_clock(),
+      self.load_funnel(),
+      cosmic::task::future(async { Message::PingExitNode }),
+    ])
+  }
+
+  /// Fetch DERP regions, which rarely change, for the relay row and netcheck.
+  fn load_derp_regions(&self) -> Task<Action<Message>> {
+    if self.derp_regions.is_some() {
+      return Task::none();
+    }
+    cosmic::task::future(async {
+      Message::DerpRegionsLoaded(fetch_derp_regions().await.map_err(|e| e.to_string()))
+    })
+  }
+
+  /// Compare the system clock with the coordination server's, if enabled, at
+  /// most once per `CLOCK_CHECK_SECS`.
+  fn check_clock(&mut self) -> Task<Action<Message>> {
+    if !self.config.check_clock
+      || self
+        .clock_checked
+        .is_some_and(|at| at.elapsed() < Duration::from_secs(CLOCK_CHECK_SECS))
+    {
+      return Task::none();
+    }
+    self.clock_checked = Some(Instant::now());
+    cosmic::task::future(async {
+      Message::ClockSkewMeasured(measure_clock_skew().await.map_err(|e| e.to_string()))
+    })
+  }
+
+  /// Read which addresses Funnel exposes, unless the tailnet doesn't allow it.
+  fn load_funnel(&self) -> Task<Action<Message>> {
+    if !self.tailnet_allows(CAP_FUNNEL) {
+      return Task::none();
+    }
+    cosmic::task::future(async {
+      Message::FunnelLoaded(fetch_funnel_hosts().await.map_err(|e| e.to_string()))
+    })
+  }
+
+  /// Fetch the CLI version, which only changes when the package is upgraded.
+  fn load_client_version(&self) -> Task<Action<Message>> {
+    if self.client_version.is_some() {
+      return Task::none();
+    }
+    cosmic::task::future(async {
+      Message::ClientVersionLoaded(fetch_client_version().await.map_err(|e| e.to_string()))
+    })
+  }
+
+  /// Start fetching exit nodes, which are skipped until first needed.
+  fn load_details(&mut self) -> Task<Action<Message>> {
+    if self.details_wanted {
+      return Task::none();
+    }
+    self.details_wanted = true;
+    refresh_exit_nodes()
+  }
+
+  /// IPv4 address with a copy button, and the IPv6 address in a row that expands below it.
+  fn address_view(&self) -> Element<'_, Message> {
+    let Some(ips) = &self.ips else {
+      return settings::item(fl!("tailscale-address"), text(fl!("loading"))).into();
+    };
+    let ipv6 = ips.iter().find(|ip| ip.is_ipv6());
+    // Without an IPv4 address, the IPv6 one is the address.
+    let Some(ip) = ips.iter().find(|ip| ip.is_ipv4()).or(ipv6) else {
+      return settings::item(fl!("tailscale-address"), text(fl!("not-available"))).into();
+    };
+
+    // The address itself is the copy button, since popup text can't be selected.
+    let copy_button = |ip: &IpAddr| {
+      let label = if self.copied_address == Some(*ip) {
+        fl!("copied")
+      } else {
+        ip.to_string()
+      };
+      button::text(label)
+        .trailing_icon(icon::from_name("edit-copy-symbolic"))
+        .on_press(Message::CopyAddress(*ip))
+        .tooltip(fl!("copy-address"))
+    };
+
+    let mut address = row!(copy_button(ip)).align_y(Alignment::Center).spacing(5);
+    let ipv6 = ipv6.filter(|ipv6| *ipv6 != ip);
+    if ipv6.is_some() {
+      address = address.push(
+        button::icon(icon::from_name(if self.show_ipv6 {
+          "go-up-symbolic"
+        } else {
+          "go-down-symbolic"
+        }))
+        .on_press(Message::ToggleIpv6)
+        .tooltip(fl!("show-ipv6-address")),
+      );
+    }
+
+    let mut col = column!(settings::item(fl!("tailscale-address"), address));
+    if self.show_ipv6
+      && let Some(ipv6) = ipv6
+    {
+      col = col.push(settings::item(
+        fl!("tailscale-ipv6-address"),
+        copy_button(ipv6),
+      ));
+    }
+    col.into()
+  }
+
+  /// Where DNS queries and outgoing traffic go right now, e.g. "DNS: Tailscale · Egress: Direct".
+  fn traffic_path_view(&self) -> Element<'_, Message> {
+    if !self.daemon_running || self.limited {
+      return Row::new().into();
+    }
+
+    let running = self.backend_state == BackendState::Running;
+    let dns = if running && self.accept_dns {
+      fl!("dns-tailscale")
+    } else {
+      fl!("dns-system")
+    };
+    let egress = if running && !self.egress_node.is_empty() {
+      fl!("egress-exit-node", node = self.egress_node.clone())
+    } else {
+      fl!("egress-direct")
+    };
+
+    settings::item(
+      fl!("traffic-path"),
+      text(fl!("traffic-path-summary", dns = dns, egress = egress)),
+    )
+    .into()
+  }
+
+  /// Home DERP relay, e.g. "fra (Frankfurt)", which carries traffic to peers
+  /// that can't be reached directly.
+  fn relay_view(&self) -> Element<'_, Message> {
+    if self.relay.is_empty() {
+      return Row::new().into();
+    }
+
+    let region = self.derp_region_by_code(&self.relay);
+    settings::item(fl!("relay"), text(derp_region_label(region, &self.relay))).into()
+  }
+
+  /// DERP region with the given ID, once the regions are loaded.
+  fn derp_region(&self, id: u64) -> Option<&DerpRegion> {
+    self.derp_regions.iter().flatten().find(|region| region.id == id)
+  }
+
+  /// DERP region with the given short code, such as `fra`.
+  fn derp_region_by_code(&self, code: &str) -> Option<&DerpRegion> {
+    self.derp_regions.iter().flatten().find(|region| region.code == code)
+  }
+
+  /// MagicDNS name with a copy button; most people share it rather than the IP.
+  fn dns_name_view(&self) -> Element<'_, Message> {
+    if self.dns_name.is_empty() {
+      return Row::new().into();
+    }
+
+    settings::item(
+      fl!("magicdns-name"),
+      row!(
+        text(self.dns_name.as_str()),
+        button::icon(icon::from_name("edit-copy-symbolic"))
+          .on_press(Message::CopyDnsName)
+          .tooltip(fl!("copy-dns-name")),
+      )
+      .align_y(Alignment::Center)
+      .spacing(5),
+    )
+    .into()
+  }
+
+  /// Show `url` as the pending login link, rendering its QR code.
+  fn set_login_url(&mut self, url: Option<String>) {
+    if self.login_url == url {
+      return;
+    }
+
+    self.login_qr = url.as_deref().and_then(|url| {
+      qr_code::Data::new(url)
+        .inspect_err(|e| warn!("Failed to render login QR code: {e}"))
+        .ok()
+    });
+    self.login_url = url;
+  }
+
+  /// "Key expires in … · date" row, highlighted when expiry is near, or a note
+  /// that expiry is disabled, which is only known while logged in and running.
+  fn key_expiry_view(&self) -> Element<'_, Message> {
+    if self.key_expiry_disabled {
+      if self.backend_state != BackendState::Running || self.needs_login {
+        return Row::new().into();
+      }
+      return settings::item(fl!("key-expiry"), text(fl!("key-expiry-disabled"))).into();
+    }
+    let Some(expiry) = self.key_expiry.filter(|_| !self.key_expired) else {
+      return Row::new().into();
+    };
+
+    let remaining = expiry - chrono::Utc::now().timestamp();
+    let days = remaining / 86_400;
+    let label = if remaining <= 0 {
+      fl!("key-expired")
+    } else if days > 0 {
+      fl!("key-expires-in-days", days = days)
+    } else {
+      fl!("key-expires-in-hours", hours = (remaining / 3_600).max(1))
+    };
+    let label = match chrono::DateTime::from_timestamp(expiry, 0) {
+      Some(date) => format!("{label} · {}", date.with_timezone(&chrono::Local).format("%Y-%m-%d")),
+      None => label,
+    };
+
+    let value: Element<'_, Message> = if days < KEY_EXPIRY_WARN_DAYS {
+      row!(
+        icon::from_name("dialog-warning-symbolic").size(16),
+        text(label).class(cosmic::theme::Text::Custom(warning_text)),
+      )
+      .align_y(Alignment::Center)
+      .spacing(8)
+      .into()
+    } else {
+      text(label).into()
+    };
+
+    settings::item(fl!("key-expiry"), value).into()
+  }
+
+  /// Read-only Funnel state; Funnel itself is configured with `tailscale funnel`.
+  fn funnel_view(&self) -> Element<'_, Message> {
+    if !self.tailnet_allows(CAP_FUNNEL) {
+      return settings::item(fl!("funnel"), text(fl!("not-enabled-for-tailnet"))).into();
+    }
+    let value = match &self.funnel {
+      Some(Ok(hosts)) if hosts.is_empty() => fl!("funnel-off"),
+      Some(Ok(hosts)) => fl!("funnel-on", hosts = hosts.join(", ")),
+      Some(Err(e)) if is_not_enabled_error(e) => fl!("not-enabled-for-tailnet"),
+      Some(Err(_)) | None => return Row::new().into(),
+    };
+    settings::item(fl!("funnel"), text(value)).into()
+  }
+
+  /// Logged-out or key-expired banner with the login link, browser button and QR code.
+  fn login_view(&self, mutable: bool) -> Element<'_, Message> {
+    let (title, action) = if self.key_expired {
+      (fl!("auth-expired"), fl!("reauthenticate"))
+    } else {
+      (fl!("logged-out"), fl!("log-in"))
+    };
+
+    let status = match &self.login_flow {
+      LoginFlow::Idle => String::new(),
+      LoginFlow::Starting => fl!("login-starting"),
+      LoginFlow::WaitingForBrowserAuth => fl!("login-waiting"),
+      LoginFlow::Success => fl!("login-success"),
+      LoginFlow::TimedOut => fl!("login-timed-out"),
+      LoginFlow::Failed(e) => fl!("login-failed", error = e.clone()),
+    };
+    let can_start = mutable && !self.login_flow.in_progress() && self.login_url.is_none();
+
+    let mut login = column!(
+      settings::item(
+        title,
+        button::suggested(action).on_press_maybe(can_start.then_some(Message::StartLogin)),
+      ),
+      text(status),
+    )
+    .spacing(5);
+
+    if let Some(url) = &self.login_url {
+      login = login.push(text(fl!("login-url-hint"))).push(text(url.clone()).size(12)).push(
+        row!(
+          button::suggested(fl!("open-browser")).on_press(Message::OpenLoginUrl),
+          button::standard(fl!("copy-link")).on_press(Message::CopyLoginUrl),
+        )
+        .spacing(5),
+      );
+    }
+
+    if let Some(data) = &self.login_qr {
+      login = login
+        .push(text(fl!("login-qr-hint")))
+        .push(qr_code(data).cell_size(LOGIN_QR_CELL_SIZE));
+    }
+
+    login.into()
+  }
+
+  fn create_context_menu(&mut self) -> Task<Action<Message>> {
+    let new_id = Id::unique();
+    self.context_menu.replace(new_id);
+
+    let Some(main_id) = self.core.main_window_id() else {
+      warn!("No main window ID available for context menu");
+      return Task::none();
+    };
+
+    let mut popup_settings =
+      self
+        .core
+        .applet
+        .get_popup_settings(main_id, new_id, None, None, None);
+    popup_settings.positioner.constraint_adjustment = POPUP_CONSTRAINT_ADJUSTMENT;
+
+    // The quick switch needs the exit node list to find the last-used node.
+    Task::batch([get_popup(popup_settings), self.load_details()])
+  }
+
+  /// Whether the popup should stay open because a pinned transfer is running.
+  #[cfg(feature = "taildrop")]
+  fn keep_popup_open(&self) -> bool {
+    self.config.pin_during_transfers && self.taildrop.transfer_in_progress()
+  }
+
+  #[cfg(not(feature = "taildrop"))]
+  fn keep_popup_open(&self) -> bool {
+    false
+  }
+
+  /// A connect, disconnect or account switch is running, so controls that
+  /// would queue conflicting commands are disabled until it finishes.
+  fn transitioning(&self) -> bool {
+    self.pending_settings.connect.busy() || self.account_switching()
+  }
+
+  /// What a profile switch would cut off: a Taildrop transfer or a settings
+  /// change still running; `None` when it can go ahead.
+  #[cfg(feature = "accounts")]
+  fn switch_blocker(&self) -> Option<String> {
+    #[cfg(feature = "taildrop")]
+    if self.taildrop.transfer_in_progress() {
+      return Some(fl!("switch-waits-for-transfer"));
+    }
+    let settings_busy = self.pending_settings.ssh.busy()
+      || self.pending_settings.routes.busy()
+      || self.pending_settings.connect.busy()
+      || self.pref_sources.exit_node_pending
+      || self.host_exit_node_pending
+      || self.lan_access_pending
+      || self.advertise_routes.applying()
+      || self.advanced.applying()
+      || self.staging.applying()
+      || self.updating;
+    settings_busy.then(|| fl!("switch-waits-for-settings"))
+  }
+
+  #[cfg(feature = "accounts")]
+  fn account_switching(&self) -> bool {
+    self.accounts.switching()
+  }
+
+  #[cfg(not(feature = "accounts"))]
+  fn account_switching(&self) -> bool {
+    false
+  }
+
+  /// "Connected • exit node: de-fra • 7/23 peers online", from the state of
+  /// the last refresh.
+  fn refresh_panel_tooltip(&mut self) {
+    let mut parts = vec![match self.backend_state {
+      BackendState::Running => fl!("connected-label"),
+      BackendState::Starting => fl!("connecting-label"),
+      _ => fl!("disconnected-label"),
+    }];
+    if !self.egress_node.is_empty() {
+      parts.push(fl!("tooltip-exit-node", node = self.egress_node.clone()));
+    }
+    if !self.peers.is_empty() {
+      let (count, online) = self
+        .tailnet_peers()
+        .fold((0, 0), |(count, online), peer| (count + 1, online + usize::from(peer.online)));
+      parts.push(fl!("tooltip-peers-online", online = online, count = count));
+    }
+    self.panel_tooltip = parts.join(" • ");
+  }
+
+  /// Icon overlaid on the panel icon, e.g. while a transfer runs.
+  #[cfg(feature = "taildrop")]
+  fn panel_badge(&self) -> Option<&'static str> {
+    self.taildrop.panel_badge()
+  }
+
+  #[cfg(not(feature = "taildrop"))]
+  fn panel_badge(&self) -> Option<&'static str> {
+    None
+  }
+
+  fn apply_ssh(&mut self, enabled: bool) -> Task<Action<Message>> {
+    if self.editing() {
+      self.stage_ssh(enabled);
+      return Task::none();
+    }
+    self.ssh = enabled;
+    self.debounce_setting(Setting::Ssh)
+  }
+
+  /// Schedule `setting`'s command after the debounce period, superseding any pending one.
+  fn debounce_setting(&mut self, setting: Setting) -> Task<Action<Message>> {
+    let pending = self.pending_settings.get(setting);
+    pending.latest += 1;
+    let generation = pending.latest;
+
+    cosmic::task::future(async move {
+      tokio::time::sleep(Duration::from_millis(SETTING_DEBOUNCE_MS)).await;
+      Message::ApplySetting(setting, generation)
+    })
+  }
+
+  /// "Taking longer than expected" rows for toggles whose command seems stuck,
+  /// offering to keep waiting or to cancel it; empty when none are.
+  fn stuck_commands_view(&self) -> Element<'_, Message> {
+    let mut rows = column!().spacing(5);
+    for (setting, pending) in self.pending_settings.all() {
+      if !pending.stuck() {
+        continue;
+      }
+      let what = match setting {
+        Setting::Ssh => fl!("enable-ssh"),
+        Setting::Routes => fl!("accept-routes"),
+        Setting::Connect => fl!("connected-label"),
+      };
+      rows = rows.push(
+        row!(
+          icon::from_name("dialog-warning-symbolic").size(16),
+          text(fl!("command-slow", setting = what)).width(Length::Fill),
+          button::standard(fl!("keep-waiting")).on_press(Message::KeepWaiting(setting)),
+          button::destructive(fl!("cancel")).on_press(Message::CancelCommand(setting)),
+        )
+        .align_y(Alignment::Center)
+        .spacing(8)
+        .padding(5),
+      );
+    }
+    rows.into()
+  }
+
+  /// Collapsible list of the subnet routes peers offer, each with its router and
+  /// whether `accept-routes` is pulling it in; empty when no peer offers any.
+  fn subnet_routes_view(&self) -> Element<'_, Message> {
+    let count: usize = self.subnet_routers.iter().map(|router| router.routes.len()).sum();
+    if count == 0 {
+      return Row::new().into();
+    }
+
+    let header = settings::item(
+      fl!("subnet-routes", count = count),
+      button::icon(icon::from_name(if self.show_routes {
+        "go-up-symbolic"
+      } else {
+        "go-down-symbolic"
+      }))
+      .on_press(Message::ToggleRoutes),
+    );
+    if !self.show_routes {
+      return header.into();
+    }
+
+    let mut list = column!(header).spacing(5);
+    for router in &self.subnet_routers {
+      let health = if !self.routes {
+        fl!("route-not-accepted")
+      } else if !router.online {
+        fl!("route-offline")
+      } else if router.primary {
+        fl!("route-in-use")
+      } else {
+        fl!("route-standby")
+      };
+
+      for route in &router.routes {
+        list = list.push(settings::item(
+          route.clone(),
+          text(format!("{} - {health}", router.name)),
+        ));
+      }
+    }
+    list.into()
+  }
+
+  /// Which daemon the CLI reaches and as whom, for setups with several daemons
+  /// or containers where commands may hit the wrong instance.
+  fn backend_info_view(&self) -> Element<'_, Message> {
+    let version = if self.daemon_version.is_empty() {
+      fl!("not-available")
+    } else {
+      self.daemon_version.clone()
+    };
+    let operator = if self.operator_user.is_empty() {
+      fl!("operator-unset")
+    } else if self.is_operator {
+      fl!("operator-you", user = self.operator_user.clone())
+    } else {
+      self.operator_user.clone()
+    };
+
+    column!(
+      settings::item(fl!("daemon-socket"), text(daemon_socket()).size(12)),
+      settings::item(fl!("daemon-version"), text(version)),
+      settings::item(fl!("operator"), text(operator)),
+    )
+    .spacing(5)
+    .into()
+  }
+
+  /// "Tailscale 1.76.1" with an "Update available" chip and an update button
+  /// when behind stable, and the progress of a running update.
+  fn version_footer(&self, mutable: bool) -> Element<'_, Message> {
+    let Some(version) = &self.client_version else {
+      return Row::new().into();
+    };
+
+    let mut footer = row!(text(fl!("client-version", version = version.clone())).size(12))
+      .align_y(Alignment::Center)
+      .spacing(8);
+    if let Some(latest) = &self.update_available {
+      footer = footer.push(
+        container(
+          row!(
+            icon::from_name("software-update-available-symbolic").size(12),
+            text(fl!("update-available", version = latest.clone()))
+              .size(12)
+              .class(cosmic::theme::Text::Accent),
+          )
+          .align_y(Alignment::Center)
+          .spacing(4),
+        )
+        .padding([2, 8])
+        .class(cosmic::theme::Container::Card),
+      );
+      footer = footer
+        .push(horizontal_space().width(Length::Fill))
+        .push(busy_marker(self.updating))
+        .push(
+          button::standard(fl!("update-tailscale"))
+            .on_press_maybe((mutable && !self.updating).then_some(Message::UpdateClient)),
+        );
+    }
+    if self.update_status.is_empty() {
+      return footer.into();
+    }
+    column!(footer, text(self.update_status.clone()).size(12))
+      .spacing(5)
+      .into()
+  }
+
+  /// Confirmation shown before SSH is enabled, listing who the policy lets in.
+  fn ssh_confirm_view(&self) -> Element<'_, Message> {
+    if !self.ssh_confirm {
+      return Row::new().into();
+    }
+
+    let mut access = column!(text(fl!("ssh-warning"))).spacing(5);
+    match &self.ssh_access {
+      None => access = access.push(text(fl!("loading"))),
+      Some(Ok(rules)) if !rules.is_empty() => {
+        access = access.push(text(fl!("ssh-access-rules")));
+        for rule in rules {
+          access = access.push(text(format!("  {rule}")));
+        }
+      }
+      Some(_) => access = access.push(text(fl!("ssh-access-unknown"))),
+    }
+
+    access
+      .push(
+        row!(
+          button::standard(fl!("cancel")).on_press(Message::CancelEnableSsh),
+          button::destructive(fl!("enable-ssh-confirm")).on_press(Message::ConfirmEnableSsh),
+        )
+        .spacing(10),
+      )
+      .padding(5)
+      .into()
+  }
+
+  /// Notice that the machine itself has no network, as opposed to Tailscale
+  /// being down, with a way to check again.
+  fn offline_view(&self) -> Element<'_, Message> {
+    if self.host_online {
+      return Row::new().into();
+    }
+
+    column!(
+      settings::item(
+        fl!("host-offline"),
+        button::standard(fl!("check-again")).on_press(Message::RefreshState),
+      ),
+      text(fl!("host-offline-hint")),
+    )
+    .spacing(5)
+    .into()
+  }
+
+  /// Confirmation shown before the connection toggle takes Tailscale down.
+  fn disconnect_confirm_view(&self) -> Element<'_, Message> {
+    if !self.disconnect_confirm {
+      return Row::new().into();
+    }
+
+    column!(
+      text(fl!("disconnect-warning")),
+      row!(
+        button::standard(fl!("cancel")).on_press(Message::CancelDisconnect),
+        button::destructive(fl!("disconnect-confirm")).on_press(Message::ConfirmDisconnect),
+      )
+      .spacing(10),
+    )
+    .spacing(5)
+    .padding(5)
+    .into()
+  }
+
+  /// Save the LAN access setting, remembering it for the exit node in use.
+  fn save_allow_lan(&mut self, allow_lan: bool) {
+    let Some(ref handler) = self.config_handler else {
+      return;
+    };
+    if let Err(e) = self.config.set_allow_lan(handler, allow_lan) {
+      self.config_writes.record("LAN access config", &e);
+    }
+
+    let exit_node = self.current_exit_node();
+    if exit_node.is_empty()
+      || self.config.exit_node_lan_access.get(exit_node.as_str()) == Some(&allow_lan)
+    {
+      return;
+    }
+    let mut remembered = self.config.exit_node_lan_access.clone();
+    remembered.insert(exit_node.into(), allow_lan);
+    if let Err(e) = self.config.set_exit_node_lan_access(handler, remembered) {
+      self.config_writes.record("exit node LAN access", &e);
+    }
+  }
+
+  /// Name of the exit node in use, or an empty string for none.
+  fn current_exit_node(&self) -> String {
+    self.sel_exit_node.as_ref().map(PeerName::to_string).unwrap_or_default()
+  }
+
+  /// Whether `name` is one of the available exit nodes; the "None" entry isn't.
+  fn exit_node_available(&self, name: &str) -> bool {
+    self.avail_exit_nodes.iter().skip(1).any(|node| node == name)
+  }
+
+  /// Whether the exit node in use is online, and its last measured round trip.
+  fn exit_node_status_view(&self) -> Element<'_, Message> {
+    if self.egress_node.is_empty() {
+      return Row::new().into();
+    }
+
+    let online = self
+      .peers
+      .iter()
+      .find(|peer| peer.name.as_str() == self.egress_node)
+      .is_some_and(|peer| peer.online);
+    let mut status = vec![if online {
+      fl!("exit-node-online")
+    } else {
+      fl!("exit-node-offline")
+    }];
+    match &self.exit_node_latency {
+      Some((node, Ok(reply))) if *node == self.egress_node => {
+        let ms = reply.latency.as_millis().to_string();
+        status.push(match &reply.path {
+          PingPath::Direct(_) => fl!("exit-node-rtt-direct", ms = ms),
+          PingPath::Relayed(region) => fl!(
+            "exit-node-rtt-relayed",
+            ms = ms,
+            region = derp_region_label(self.derp_region_by_code(region), region)
+          ),
+        });
+      }
+      Some((node, Err(_))) if *node == self.egress_node => status.push(fl!("exit-node-no-reply")),
+      _ => {}
+    }
+
+    text(fl!(
+      "exit-node-status",
+      node = self.egress_node.clone(),
+      status = status.join(" · ")
+    ))
+    .size(12)
+    .into()
+  }
+
+  /// Select the exit node Tailscale reports, keeping the dropdown in step with the CLI.
+  fn sync_exit_node(&mut self, exit_node: &str) {
+    self.sel_exit_node = (!exit_node.is_empty()).then(|| PeerName::from(exit_node));
+  }
+
+  /// Whether the tailnet grants `cap`; unknown capabilities never gate a feature.
+  fn tailnet_allows(&self, cap: &str) -> bool {
+    self.capabilities.is_empty() || self.capabilities.iter().any(|granted| granted == cap)
+  }
+
+  /// The last-used exit node, if it is still available.
+  fn last_exit_node(&self) -> Option<PeerName> {
+    let node = &self.config.last_exit_node;
+    self.exit_node_available(node.as_str()).then(|| node.clone())
+  }
+
+  /// Title and button label for the exit node on/off switch, or `None` when
+  /// there is nothing to switch between.
+  fn quick_switch_labels(&self) -> Option<(String, String)> {
+    if self.is_exit_node || self.limited {
+      return None;
+    }
+
+    let current = self.current_exit_node();
+    if !current.is_empty() {
+      return Some((fl!("exit-node-via", node = current), fl!("exit-node-turn-off")));
+    }
+
+    self.last_exit_node().map(|node| {
+      (fl!("no-exit-node"), fl!("exit-node-use-last", node = node.to_string()))
+    })
+  }
+
+  /// Exit node on/off switch shown at the top of the popup.
+  fn quick_switch_view(&self, mutable: bool) -> Option<Element<'_, Message>> {
+    let (title, label) = self.quick_switch_labels()?;
+    Some(
+      settings::item(
+        title,
+        button::standard(label).on_press_maybe(
+          (mutable && !self.transitioning()).then_some(Message::QuickSwitchExitNode),
+        ),
+      )
+      .into(),
+    )
+  }
+
+  /// Right-click menu on the panel icon.
+  fn context_menu_view(&self) -> Element<'_, Message> {
+    let mutable = !self.config.monitor_only && !self.transitioning();
+    let item: Element<'_, Message> = match self.quick_switch_labels() {
+      Some((title, label)) => column!(
+        text(title),
+        button::text(label)
+          .on_press_maybe(mutable.then_some(Message::QuickSwitchExitNode))
+          .width(Length::Fill),
+      )
+      .spacing(5)
+      .into(),
+      None => text(fl!("no-recent-exit-node")).into(),
+    };
+
+    self
+      .core
+      .applet
+      .popup_container(column!(item).padding(10))
+      .into()
+  }
+
+  /// Emit the D-Bus `StateChanged` signal if the published state changed.
+  fn dbus_state_changed(&mut self) -> Task<Action<Message>> {
+    let Some(conn) = self.dbus_conn.clone() else {
+      return Task::none();
+    };
+
+    let ip = self
+      .ips
+      .as_ref()
+      .and_then(|ips| ips.first())
+      .map(ToString::to_string)
+      .unwrap_or_default();
+    let connected = self.backend_state == BackendState::Running;
+    let state = (connected, ip, self.current_exit_node());
+    if self.dbus_last_state.as_ref() == Some(&state) {
+      return Task::none();
+    }
+    self.dbus_last_state = Some(state.clone());
+
+    Task::future(async move {
+      let (connected, ip, exit_node) = state;
+      if let Err(e) = dbus::emit_state_changed(&conn, connected, &ip, &exit_node).await {
+        warn!("Failed to emit StateChanged: {e}");
+      }
+    })
+    .discard()
+  }
+
+  /// Act on a request received over D-Bus.
+  fn handle_dbus_command(&mut self, cmd: DbusCommand) -> Task<Action<Message>> {
+    match cmd {
+      DbusCommand::Connect => self.update(Message::ConnectDisconnect(true)),
+      DbusCommand::Disconnect => self.update(Message::ConnectDisconnect(false)),
+      DbusCommand::SetExitNode(name) => {
+        let node = if name.is_empty() || name.eq_ignore_ascii_case("none") {
+          Some(None)
+        } else {
+          self
+            .avail_exit_nodes
+            .iter()
+            .skip(1)
+            .find(|node| node.eq_ignore_ascii_case(&name))
+            .map(|node| Some(PeerName::from(node.as_str())))
+        };
+
+        match node {
+          Some(node) => self.update(Message::ExitNodeSelected(node)),
+          None if !self.details_wanted => {
+            self.deferred_exit_node = Some(name);
+            self.load_details()
+          }
+          None => {
+            warn!("D-Bus SetExitNode: unknown exit node {name}");
+            Task::none()
+          }
+        }
+      }
+      #[cfg(feature = "taildrop")]
+      DbusCommand::SendFiles(paths, target) => {
+        let files = paths.into_iter().map(PathBuf::from).collect();
+        self.send_files_to(files, target)
+      }
+      #[cfg(not(feature = "taildrop"))]
+      DbusCommand::SendFiles(..) => {
+        warn!("D-Bus SendFiles: built without Taildrop support");
+        Task::none()
+      }
+    }
+  }
+
+  /// Current account name; switching needs the `accounts` feature.
+  #[cfg(not(feature = "accounts"))]
+  fn account_view(&self) -> Element<'_, Message> {
+    row!(settings::item(fl!("account"), text(self.cur_acct.clone()))).into()
+  }
+
+  /// Tailnet this device is on, so users of several tailnets can tell them apart.
+  fn tailnet_view(&self) -> Element<'_, Message> {
+    if self.tailnet.is_empty() {
+      return Row::new().into();
+    }
+    settings::item(fl!("tailnet"), text(self.tailnet.clone())).into()
+  }
+
+  /// Background work is paused because the session is locked.
+  fn paused(&self) -> bool {
+    self.config.pause_when_locked && self.session_locked
+  }
+
+  /// Play an XDG theme sound if the user has opted into status sounds.
+  fn alert_sound(&self, event_id: &'static str) -> Task<Action<Message>> {
+    if !self.config.play_sounds || self.paused() {
+      return Task::none();
+    }
+
+    Task::future(play_sound(event_id)).discard()
   }
 }
 
-impl cosmic::Application for Window {
+impl Application for Window {
   type Executor = cosmic::executor::multi::Executor;
   type Flags = ();
   type Message = Message;
@@ -136,6 +1487,7 @@ impl cosmic::Application for Window {
   }
 
   fn init(core: Core, _flags: Self::Flags) -> (Window, Task<Action<Self::Message>>) {
+    let mut config_writes = ConfigWriteFailures::default();
     let (config_handler, config) =
       match Config::new(ID, TailscaleConfig::VERSION) {
         Ok(handler) => match TailscaleConfig::get_entry(&handler) {
@@ -149,326 +1501,994 @@ impl cosmic::Application for Window {
           }
         },
         Err(e) => {
-          error!("Failed to create config handler: {e}");
+          // Without a handler nothing can be saved, so warn right away.
+          config_writes.record("config handler", &e);
           (None, TailscaleConfig::default())
         }
       };
 
-    let sel_exit_node_idx = if config.exit_node_idx > 0 {
-      Some(config.exit_node_idx)
-    } else {
-      None
-    };
+    configure_cli(&config.cli_path, &config.cli_extra_args);
+    let panel_icons = PanelIcons::from_config(&config);
+
+    let sel_exit_node = (!config.exit_node.is_empty()).then(|| config.exit_node.clone());
 
     let window = Window {
       core,
       config: config.clone(),
       config_handler,
       ssh: false,
+      ssh_confirm: false,
+      disconnect_confirm: false,
+      ssh_access: None,
       routes: false,
+      subnet_routers: Vec::new(),
+      peers: Vec::new(),
+      panel_icons,
+      panel_tooltip: String::new(),
+      peer_highlights: PeerHighlights::default(),
+      peer_pings: PeerPings::default(),
+      notifications: NotificationQueue::default(),
+      auto_connect_pending: true,
+      session_locked: false,
+      capabilities: Vec::new(),
+      funnel: None,
       connect: false,
-      device_options: vec!["Select".to_string()],
+      pending_settings: PendingSettings::default(),
+      pref_sources: PrefSources::default(),
+      staging: StagingState::default(),
+      raw_status: RawStatusState::default(),
+      advertise_routes: AdvertiseRoutesState::default(),
+      exit_locations: ExitLocationsState::default(),
+      exit_node_picker: ExitNodePickerState::default(),
+      advanced: AdvancedState::default(),
+      netcheck: NetcheckState::default(),
+      whois: WhoisState::default(),
+      #[cfg(feature = "taildrop")]
+      taildrop: TaildropState::default(),
       popup: None,
-      selected_device: DEFAULT_EXIT_NODE.to_string(),
-      selected_device_idx: Some(0),
-      send_files: Vec::new(),
-      send_file_status: String::new(),
-      files_sent: false,
-      receive_file_status: String::new(),
+      context_menu: None,
       avail_exit_nodes: vec!["None".to_string()],
-      sel_exit_node: DEFAULT_EXIT_NODE.to_string(),
-      sel_exit_node_idx,
-      acct_list: Vec::new(),
+      details_wanted: false,
+      deferred_exit_node: None,
+      sel_exit_node,
+      #[cfg(feature = "accounts")]
+      accounts: AccountsState::default(),
       cur_acct: String::new(),
+      tailnet: String::new(),
       allow_lan: config.allow_lan,
       is_exit_node: false,
-      ip: fl!("loading"),
-      conn_status: false,
+      host_exit_node_pending: false,
+      lan_access_pending: false,
+      captive_portal: false,
+      host_online: true,
+      limited: false,
+      health: Vec::new(),
+      clock_skew: None,
+      clock_checked: None,
+      needs_login: false,
+      key_expired: false,
+      key_expiry: None,
+      key_expiry_disabled: false,
+      login_url: None,
+      login_qr: None,
+      login_flow: LoginFlow::Idle,
+      login_attempt: 0,
+      suspended_exit_node: None,
+      ips: None,
+      show_ipv6: false,
+      show_routes: false,
+      copied_address: None,
+      dns_name: String::new(),
+      accept_dns: false,
+      egress_node: String::new(),
+      exit_node_latency: None,
+      exit_node_ping_pending: false,
+      backend_state: BackendState::NoState,
+      shared_state_stamp: None,
+      state_history: VecDeque::with_capacity(STATE_HISTORY_LEN),
+      diagnostics_status: String::new(),
+      peer_export_status: String::new(),
+      dbus_conn: None,
+      dbus_last_state: None,
+      last_errors: SectionErrors::default(),
+      config_writes,
+      daemon_running: true,
+      daemon_start_status: String::new(),
+      is_operator: true,
+      operator_status: String::new(),
+      operator_user: String::new(),
+      daemon_version: String::new(),
+      relay: String::new(),
+      derp_regions: None,
+      client_version: None,
+      update_available: None,
+      updating: false,
+      update_attempt: 0,
+      update_status: String::new(),
     };
 
-    let task = cosmic::task::future(async { Message::RefreshState });
+    let task = Task::batch([
+      cosmic::task::future(async { Message::LoadSharedState }),
+      refresh_peers(),
+    ]);
     (window, task)
   }
 
+  fn subscription(&self) -> Subscription<Self::Message> {
+    let mut subscriptions = vec![dbus_subscription()];
+
+    if self.login_flow.in_progress() {
+      subscriptions.push(login_subscription(self.login_attempt));
+    }
+
+    if self.updating {
+      subscriptions.push(update_subscription(self.update_attempt));
+    }
+    #[cfg(feature = "accounts")]
+    subscriptions.extend(self.queued_switch_subscription());
+    subscriptions.extend(self.ping_subscriptions());
+
+    if self.config.pause_when_locked {
+      subscriptions.push(session_lock_subscription());
+    }
+    if self.paused() {
+      return Subscription::batch(subscriptions);
+    }
+
+    // Pick up state fetched by applet instances on other panels.
+    subscriptions.push(
+      cosmic::iced::time::every(Duration::from_secs(SHARED_STATE_POLL_SECS))
+        .map(|_| Message::SyncSharedState),
+    );
+    // Keep the open popup current; exit nodes only refresh after changes.
+    if self.popup.is_some() {
+      subscriptions.push(
+        cosmic::iced::time::every(Duration::from_secs(POPUP_REFRESH_SECS))
+          .map(|_| Message::LoadSharedState),
+      );
+      subscriptions.push(
+        cosmic::iced::time::every(Duration::from_secs(PEERS_REFRESH_SECS))
+          .map(|_| Message::RefreshPeers),
+      );
+    }
+
+    #[cfg(feature = "taildrop")]
+    subscriptions.push(inbox_subscription());
+
+    // Notice when the browser login completes; only the connection tells.
+    if self.needs_login && self.login_url.is_some() {
+      subscriptions.push(
+        cosmic::iced::time::every(Duration::from_secs(LOGIN_POLL_SECS))
+          .map(|_| Message::LoadSharedState),
+      );
+    }
+
+    // Keep the exit node's round trip current while it is on screen.
+    if self.popup.is_some() && !self.egress_node.is_empty() {
+      subscriptions.push(
+        cosmic::iced::time::every(Duration::from_secs(EXIT_NODE_PING_SECS))
+          .map(|_| Message::PingExitNode),
+      );
+    }
+
+    // Watch for the captive portal clearing so the exit node can be restored.
+    if self.suspended_exit_node.is_some() {
+      subscriptions.push(
+        cosmic::iced::time::every(Duration::from_secs(CAPTIVE_PORTAL_POLL_SECS))
+          .map(|_| Message::LoadSharedState),
+      );
+    }
+
+    Subscription::batch(subscriptions)
+  }
+
   fn on_close_requested(&self, id: window::Id) -> Option<Message> {
     Some(Message::PopupClosed(id))
   }
 
   fn update(&mut self, message: Self::Message) -> Task<Action<Self::Message>> {
+    if self.config.monitor_only && message.is_mutation() {
+      warn!("Monitor-only mode: ignoring {message:?}");
+      return Task::none();
+    }
+
     match message {
+      // Sections are fetched apart, so one failing doesn't hold up the others.
       Message::RefreshState => {
-        return cosmic::task::future(async {
-          match fetch_tailscale_state().await {
-            Ok(state) => Message::StateRefreshed(Box::new(state)),
-            Err(e) => Message::RefreshFailed(e.to_string()),
+        let mut tasks = vec![refresh_connection(true), refresh_peers()];
+        if self.details_wanted {
+          tasks.push(refresh_exit_nodes());
+        }
+        #[cfg(feature = "accounts")]
+        tasks.push(self.refresh_accounts());
+        return Task::batch(tasks);
+      }
+      Message::LoadSharedState => {
+        return refresh_connection(false);
+      }
+      Message::SyncSharedState => {
+        let since = self.shared_state_stamp;
+        return cosmic::task::future(async move {
+          match read_newer_state(since).await {
+            Some((stamp, state)) => Message::ConnectionRefreshed(stamp, Box::new(state)),
+            None => Message::SharedStateUnchanged,
           }
         });
       }
-      Message::StateRefreshed(state) => {
-        self.ip = state.ip;
-        self.conn_status = state.connected;
-        self.connect = state.connected;
-        self.ssh = state.ssh_enabled;
-        self.routes = state.routes_enabled;
+      Message::SharedStateUnchanged => {}
+      Message::ConnectionRefreshed(stamp, state) => {
+        if self.shared_state_stamp.is_some_and(|current| stamp < current) {
+          return Task::none();
+        }
+        self.shared_state_stamp = Some(stamp);
+        self.last_errors.refresh = None;
+        if self.state_history.len() == STATE_HISTORY_LEN {
+          self.state_history.pop_front();
+        }
+        self.state_history.push_back((*state).clone());
+        // Drift is only meaningful once something has been shown.
+        let check_drift = self.ips.is_some() && !state.limited;
+        self.ips = Some(state.ips);
+        self.dns_name = state.dns_name;
+        self.accept_dns = state.accept_dns;
+        let was_running = self.backend_state == BackendState::Running;
+        // Toggles with a pending change keep the user's value until the CLI answers.
+        if !self.pending_settings.connect.busy() {
+          self.backend_state = state.backend_state;
+          self.connect = state.connected;
+        }
+        if !self.pending_settings.ssh.busy() {
+          if check_drift {
+            note_drift(&mut self.pref_sources.ssh, &self.ssh, &state.ssh_enabled);
+          }
+          self.ssh = state.ssh_enabled;
+        }
+        if !self.pending_settings.routes.busy() {
+          if check_drift {
+            note_drift(&mut self.pref_sources.routes, &self.routes, &state.routes_enabled);
+          }
+          self.routes = state.routes_enabled;
+        }
+        self.capabilities = state.capabilities;
         self.is_exit_node = state.is_exit_node;
-        self.device_options = state.devices;
-        self.avail_exit_nodes = state.exit_nodes;
-        self.acct_list = state.acct_list;
+        self.sync_advertised_routes(state.advertised_routes);
+        self.sync_advanced_prefs(state.advanced);
+        let shown_exit_node = self.current_exit_node();
+        if !self.pref_sources.exit_node_pending && state.daemon_running && !state.limited {
+          if check_drift {
+            note_drift(
+              &mut self.pref_sources.exit_node,
+              shown_exit_node.as_str(),
+              state.exit_node.as_str(),
+            );
+          }
+          self.sync_exit_node(&state.exit_node);
+        }
+        let egress_changed = self.egress_node != state.exit_node;
+        self.egress_node = state.exit_node;
+        self.refresh_panel_tooltip();
         self.cur_acct = state.current_acct;
+        self.tailnet = state.tailnet;
+        self.relay = state.relay;
+        self.daemon_running = state.daemon_running;
+        if self.daemon_running {
+          self.daemon_start_status = String::new();
+        }
+        self.is_operator = state.is_operator;
+        self.operator_user = state.operator_user;
+        self.daemon_version = state.daemon_version;
+        self.update_available = state.update_available;
+        if self.is_operator {
+          self.operator_status = String::new();
+        }
+        self.captive_portal = state.captive_portal;
+        self.limited = state.limited;
+        self.health = state.health;
+        self.needs_login = state.needs_login;
+        self.key_expired = state.key_expired;
+        self.key_expiry = state.key_expiry;
+        self.key_expiry_disabled = state.key_expiry_disabled;
+        if !self.needs_login {
+          self.set_login_url(None);
+          self.login_flow = LoginFlow::Idle;
+        } else if state.auth_url.is_some() {
+          self.set_login_url(state.auth_url);
+        }
+
+        let mut tasks = vec![
+          self.dbus_state_changed(),
+          cosmic::task::future(async { Message::HostNetworkChecked(host_has_network().await) }),
+        ];
+        // Only changes made elsewhere, e.g. by the CLI or after resume.
+        let running = self.backend_state == BackendState::Running;
+        if check_drift && running != was_running {
+          tasks.push(self.notify(if running {
+            fl!("notify-connected")
+          } else {
+            fl!("notify-disconnected")
+          }));
+        }
+        // Decided once, at the first state where the daemon is up.
+        if self.auto_connect_pending && self.daemon_running {
+          self.auto_connect_pending = false;
+          if self.config.auto_connect
+            && !self.needs_login
+            && self.backend_state == BackendState::Stopped
+          {
+            tasks.push(self.update(Message::ConnectDisconnect(true)));
+          }
+        }
+        if egress_changed && self.popup.is_some() {
+          tasks.push(cosmic::task::future(async { Message::PingExitNode }));
+        }
+        if self.suspended_exit_node.is_some() && !self.captive_portal {
+          tasks.push(cosmic::task::future(async { Message::RestoreExitNode }));
+        }
+        return Task::batch(tasks);
+      }
+      Message::RefreshPeers => {
+        return refresh_peers();
+      }
+      Message::PeersRefreshed(peers, subnet_routers) => {
+        self.subnet_routers = subnet_routers;
+        let peer_changes = self.track_peer_changes(&peers);
+        self.peers = peers;
+        self.refresh_panel_tooltip();
+        #[cfg(feature = "taildrop")]
+        self.sync_taildrop_devices();
+        return peer_changes;
+      }
+      Message::ExitNodesRefreshed(Err(e)) => {
+        // Keep offering the exit nodes from the last successful refresh.
+        warn!("Failed to list exit nodes: {e}");
+      }
+      Message::ExitNodesRefreshed(Ok(lists)) => {
+        self.avail_exit_nodes = lists.names;
+        self.sync_located_exit_nodes(lists.located);
+        // Located exit nodes aren't devices to send files to.
+        #[cfg(feature = "taildrop")]
+        self.sync_taildrop_devices();
+        if !self.pref_sources.exit_node_pending {
+          let egress_node = self.egress_node.clone();
+          self.sync_exit_node(&egress_node);
+        }
+        if let Some(name) = self.deferred_exit_node.take() {
+          return self.handle_dbus_command(DbusCommand::SetExitNode(name));
+        }
+      }
+      Message::HostNetworkChecked(online) => {
+        self.host_online = online;
       }
       Message::RefreshFailed(err) => {
         error!("Failed to refresh Tailscale state: {err}");
+        self.last_errors.refresh = Some(err);
+      }
+      Message::DismissRefreshError => {
+        self.last_errors.refresh = None;
+      }
+      Message::DismissSettingsError => {
+        self.last_errors.settings = None;
+      }
+      Message::DismissConfigWarning => {
+        self.config_writes.dismissed = true;
+      }
+      Message::StartDaemon => {
+        self.daemon_start_status = fl!("daemon-starting");
+        return cosmic::task::future(async {
+          let success = match start_daemon().await {
+            Ok(()) => true,
+            Err(e) => {
+              error!("Failed to start tailscaled: {e}");
+              false
+            }
+          };
+          Message::DaemonStarted(success)
+        });
+      }
+      Message::DaemonStarted(success) => {
+        if success {
+          self.daemon_start_status = fl!("daemon-started");
+          return cosmic::task::future(async { Message::RefreshState });
+        }
+        self.daemon_start_status = fl!("daemon-start-failed");
+      }
+      Message::GrantOperator => {
+        self.operator_status = fl!("operator-granting");
+        return cosmic::task::future(async {
+          let success = match grant_operator().await {
+            Ok(()) => true,
+            Err(e) => {
+              error!("Failed to grant operator access: {e}");
+              false
+            }
+          };
+          Message::OperatorGranted(success)
+        });
+      }
+      Message::OperatorGranted(success) => {
+        if success {
+          self.operator_status = fl!("operator-granted");
+          return cosmic::task::future(async { Message::RefreshState });
+        }
+        self.operator_status = fl!("operator-grant-failed");
+      }
+      Message::GenerateDiagnostics => {
+        self.diagnostics_status = fl!("diagnostics-generating");
+        let input = DiagnosticsInput {
+          config: serde_json::to_value(&self.config).unwrap_or_default(),
+          config_path: TailscaleConfig::path(),
+          config_write_failures: self.config_writes.count,
+          config_write_error: self.config_writes.last_error.clone(),
+          snapshots: self.state_history.iter().cloned().collect(),
+        };
+        return cosmic::task::future(async move {
+          Message::DiagnosticsGenerated(generate_bundle(input).await.map_err(|e| e.to_string()))
+        });
+      }
+      Message::DiagnosticsGenerated(result) => {
+        self.diagnostics_status = match result {
+          Ok(path) => fl!("diagnostics-saved", path = path.display().to_string()),
+          Err(e) => {
+            error!("Failed to generate diagnostics: {e}");
+            fl!("diagnostics-failed")
+          }
+        };
+      }
+      Message::ClientVersionLoaded(result) => match result {
+        Ok(version) => self.client_version = Some(version),
+        Err(e) => warn!("Failed to get client version: {e}"),
+      },
+      Message::ClockSkewMeasured(result) => match result {
+        Ok(skew) => self.clock_skew = Some(skew),
+        Err(e) => warn!("Failed to check the system clock: {e}"),
+      },
+      Message::DerpRegionsLoaded(result) => match result {
+        Ok(regions) => self.derp_regions = Some(regions),
+        Err(e) => warn!("Failed to get DERP regions: {e}"),
+      },
+      Message::UpdateClient => {
+        if !self.updating {
+          self.updating = true;
+          self.update_attempt += 1;
+          self.update_status = fl!("update-starting");
+        }
+      }
+      Message::Update(event) => match event {
+        UpdateEvent::Progress(line) => self.update_status = line,
+        UpdateEvent::Finished(result) => {
+          self.updating = false;
+          match result {
+            Ok(()) => {
+              self.update_status = fl!("update-finished");
+              self.client_version = None;
+              return Task::batch([
+                self.load_client_version(),
+                cosmic::task::future(async { Message::RefreshState }),
+              ]);
+            }
+            Err(e) => {
+              error!("Failed to update Tailscale: {e}");
+              self.update_status = fl!("update-failed", error = e);
+            }
+          }
+        }
+      },
+      Message::DbusReady(conn) => {
+        self.dbus_conn = Some(conn);
+        return self.dbus_state_changed();
+      }
+      Message::Dbus(cmd) => {
+        return self.handle_dbus_command(cmd);
+      }
+      #[cfg(feature = "accounts")]
+      Message::Accounts(msg) => {
+        return self.update_accounts(msg);
+      }
+      #[cfg(feature = "taildrop")]
+      Message::Taildrop(msg) => {
+        return self.update_taildrop(msg);
+      }
+      Message::Staging(msg) => {
+        return self.update_staging(msg);
+      }
+      Message::RawStatus(msg) => {
+        return self.update_raw_status(msg);
+      }
+      Message::AdvertiseRoutes(msg) => {
+        return self.update_advertise_routes(msg);
+      }
+      Message::Advanced(msg) => {
+        return self.update_advanced(msg);
+      }
+      Message::Netcheck(msg) => {
+        return self.update_netcheck(msg);
+      }
+      Message::Whois(msg) => {
+        return self.update_whois(msg);
+      }
+      Message::Peers(msg) => {
+        return self.update_peers(msg);
+      }
+      Message::TogglePlaySounds(enabled) => {
+        if let Some(ref handler) = self.config_handler
+          && let Err(e) = self.config.set_play_sounds(handler, enabled)
+        {
+          self.config_writes.record("sound config", &e);
+        }
+      }
+      Message::ToggleNotifications(enabled) => {
+        if let Some(ref handler) = self.config_handler
+          && let Err(e) = self.config.set_notifications(handler, enabled)
+        {
+          self.config_writes.record("notification config", &e);
+        }
+      }
+      Message::ToggleAutoConnect(enabled) => {
+        if let Some(ref handler) = self.config_handler
+          && let Err(e) = self.config.set_auto_connect(handler, enabled)
+        {
+          self.config_writes.record("auto-connect config", &e);
+        }
+      }
+      Message::TogglePauseWhenLocked(enabled) => {
+        if let Some(ref handler) = self.config_handler
+          && let Err(e) = self.config.set_pause_when_locked(handler, enabled)
+        {
+          self.config_writes.record("pause-when-locked config", &e);
+        }
+      }
+      Message::ToggleCheckClock(enabled) => {
+        if let Some(ref handler) = self.config_handler
+          && let Err(e) = self.config.set_check_clock(handler, enabled)
+        {
+          self.config_writes.record("clock check config", &e);
+        }
+        self.clock_checked = None;
+        self.clock_skew = None;
+        return self.check_clock();
+      }
+      Message::ToggleConfirmDisconnect(enabled) => {
+        if let Some(ref handler) = self.config_handler
+          && let Err(e) = self.config.set_confirm_disconnect(handler, enabled)
+        {
+          self.config_writes.record("disconnect confirmation config", &e);
+        }
+      }
+      Message::SessionLocked(locked) => {
+        let unlocked = self.session_locked && !locked;
+        self.session_locked = locked;
+        // Catch up on whatever changed while polling was paused.
+        if unlocked {
+          return cosmic::task::future(async { Message::RefreshState });
+        }
+      }
+      Message::Surface(action) => {
+        return cosmic::task::message(cosmic::Action::Cosmic(cosmic::app::Action::Surface(action)));
       }
       Message::TogglePopup => {
+        if let Some(menu) = self.context_menu.take() {
+          return destroy_popup(menu);
+        }
         return if let Some(p) = self.popup.take() {
-          self.receive_file_status = String::new();
+          #[cfg(feature = "taildrop")]
+          self.taildrop.clear_receive_status();
           destroy_popup(p)
         } else {
           self.create_popup()
         }
       }
+      Message::ToggleContextMenu => {
+        return if let Some(menu) = self.context_menu.take() {
+          destroy_popup(menu)
+        } else if self.popup.is_some() {
+          Task::none()
+        } else {
+          self.create_context_menu()
+        };
+      }
+      Message::QuickSwitchExitNode => {
+        let close_menu = self.context_menu.take().map_or_else(Task::none, destroy_popup);
+
+        let target = if self.sel_exit_node.is_none() {
+          self.last_exit_node().map(Some)
+        } else {
+          Some(None)
+        };
+
+        let Some(node) = target else {
+          return close_menu;
+        };
+        return Task::batch([close_menu, self.update(Message::ExitNodeSelected(node))]);
+      }
+      // Running `login_subscription` is what drives the login.
+      Message::StartLogin => {
+        if !self.login_flow.in_progress() {
+          self.login_flow = LoginFlow::Starting;
+          self.login_attempt += 1;
+        }
+      }
+      Message::Login(event) => match event {
+        LoginEvent::AuthUrl(url) => {
+          self.login_flow = LoginFlow::WaitingForBrowserAuth;
+          self.set_login_url(Some(url));
+        }
+        LoginEvent::Success => {
+          self.login_flow = LoginFlow::Success;
+          self.set_login_url(None);
+          return cosmic::task::future(async { Message::RefreshState });
+        }
+        LoginEvent::TimedOut => {
+          warn!("Login timed out");
+          self.login_flow = LoginFlow::TimedOut;
+          self.set_login_url(None);
+        }
+        LoginEvent::Failed(e) => {
+          error!("Login failed: {e}");
+          self.login_flow = LoginFlow::Failed(e);
+          self.set_login_url(None);
+        }
+      },
+      Message::OpenLoginUrl => {
+        if let Some(url) = self.login_url.clone() {
+          return Task::future(async move {
+            if let Err(e) = open_url(&url).await {
+              warn!("Failed to open login URL: {e}");
+            }
+          })
+          .discard();
+        }
+      }
+      Message::CopyAddress(ip) => {
+        self.copied_address = Some(ip);
+        return Task::batch([
+          cosmic::iced::clipboard::write(ip.to_string()),
+          cosmic::task::future(async move {
+            tokio::time::sleep(Duration::from_secs(COPIED_FEEDBACK_SECS)).await;
+            Message::ClearCopiedAddress(ip)
+          }),
+        ]);
+      }
+      Message::ClearCopiedAddress(ip) => {
+        // Another address may have been copied since.
+        if self.copied_address == Some(ip) {
+          self.copied_address = None;
+        }
+      }
+      Message::ToggleIpv6 => {
+        self.show_ipv6 = !self.show_ipv6;
+      }
+      Message::ToggleRoutes => {
+        self.show_routes = !self.show_routes;
+      }
+      Message::PrunePeerHighlights => {
+        self.prune_peer_highlights();
+      }
+      Message::FlushNotifications => {
+        return self.flush_notifications();
+      }
+      Message::CopyDnsName => {
+        return cosmic::iced::clipboard::write(self.dns_name.clone());
+      }
+      Message::CopyLoginUrl => {
+        if let Some(url) = self.login_url.clone() {
+          return cosmic::iced::clipboard::write(url);
+        }
+      }
       Message::PopupClosed(id) => {
+        if self.context_menu.as_ref() == Some(&id) {
+          self.context_menu = None;
+        }
         if self.popup.as_ref() == Some(&id) {
           self.popup = None;
+
+          if self.keep_popup_open() {
+            return self.create_popup();
+          }
         }
       }
       Message::EnableSSH(enabled) => {
-        self.ssh = enabled;
-        let ssh = self.ssh;
-        return cosmic::task::future(async move {
-          let success = set_ssh(ssh).await.is_ok();
-          Message::SshSet(ssh, success)
-        });
+        // Turning SSH on exposes the device, so confirm it first.
+        if enabled {
+          self.ssh_confirm = true;
+          self.ssh_access = None;
+          return cosmic::task::future(async {
+            Message::SshAccessLoaded(fetch_ssh_access().await.map_err(|e| e.to_string()))
+          });
+        }
+        return self.apply_ssh(false);
+      }
+      Message::SshAccessLoaded(result) => {
+        if let Err(e) = &result {
+          warn!("Failed to read SSH policy: {e}");
+        }
+        self.ssh_access = Some(result);
+      }
+      Message::FunnelLoaded(result) => {
+        if let Err(e) = &result
+          && !is_not_enabled_error(e)
+        {
+          warn!("Failed to read Funnel status: {e}");
+        }
+        self.funnel = Some(result);
+      }
+      Message::ConfirmEnableSsh => {
+        self.ssh_confirm = false;
+        return self.apply_ssh(true);
+      }
+      Message::CancelEnableSsh => {
+        self.ssh_confirm = false;
       }
-      Message::SshSet(value, success) => {
-        if !success {
+      // Failures only revert the toggle if it hasn't been flipped again since.
+      Message::SshSet(value, result) => {
+        self.pending_settings.ssh.finished();
+        note_set_result(&mut self.pref_sources.ssh, &result);
+        if !self.settings_result(result, "SSH") && self.ssh == value {
           self.ssh = !value;
-          error!("Failed to set SSH to {value}");
         }
       }
       Message::AcceptRoutes(accepted) => {
+        if self.editing() {
+          self.stage_routes(accepted);
+          return Task::none();
+        }
         self.routes = accepted;
-        let routes = self.routes;
-        return cosmic::task::future(async move {
-          let success = set_routes(routes).await.is_ok();
-          Message::RoutesSet(routes, success)
-        });
+        return self.debounce_setting(Setting::Routes);
       }
-      Message::RoutesSet(value, success) => {
-        if !success {
+      Message::RoutesSet(value, result) => {
+        self.pending_settings.routes.finished();
+        note_set_result(&mut self.pref_sources.routes, &result);
+        if !self.settings_result(result, "accept-routes") && self.routes == value {
           self.routes = !value;
-          error!("Failed to set routes to {value}");
         }
       }
+      // Only the toggle asks; D-Bus callers and auto-connect go straight through.
+      Message::ConnectionToggled(false) if self.config.confirm_disconnect => {
+        self.disconnect_confirm = true;
+      }
+      Message::ConnectionToggled(connection) => {
+        self.disconnect_confirm = false;
+        return self.update(Message::ConnectDisconnect(connection));
+      }
+      Message::ConfirmDisconnect => {
+        self.disconnect_confirm = false;
+        return self.update(Message::ConnectDisconnect(false));
+      }
+      Message::CancelDisconnect => {
+        self.disconnect_confirm = false;
+      }
       Message::ConnectDisconnect(connection) => {
         self.connect = connection;
-        self.conn_status = connection;
-        let connect = self.connect;
-        return cosmic::task::future(async move {
-          let success = tailscale_int_up(connect).await.is_ok();
-          Message::ConnectionSet(connect, success)
-        });
+        self.backend_state = if connection {
+          BackendState::Starting
+        } else {
+          BackendState::Stopped
+        };
+        return self.debounce_setting(Setting::Connect);
       }
-      Message::ConnectionSet(value, success) => {
-        if !success {
-          self.connect = !value;
-          self.conn_status = !value;
-          error!("Failed to set connection to {value}");
+      Message::ConnectionSet(value, result) => {
+        self.pending_settings.connect.finished();
+        if !self.settings_result(result, "connection") {
+          if self.connect == value {
+            self.connect = !value;
+            // The attempt may have got partway, so ask the daemon where it ended up.
+            return cosmic::task::future(async { Message::RefreshState });
+          }
+          return Task::none();
         }
-      }
-      Message::SwitchAccount(new_acct) => {
-        if let Some(acct) = self.acct_list.get(new_acct).cloned() {
-          self.cur_acct.clone_from(&acct);
-          return cosmic::task::future(async move {
-            if let Err(e) = switch_accounts(&acct).await {
-              error!("Failed to switch accounts: {e}");
-            }
-            Message::RefreshState
-          });
+        // `tailscale up` returns once the connection is running.
+        if self.connect == value {
+          self.backend_state = if value {
+            BackendState::Running
+          } else {
+            BackendState::Stopped
+          };
         }
+
+        return Task::batch([
+          self.alert_sound(if value {
+            SOUND_CONNECTED
+          } else {
+            SOUND_DISCONNECTED
+          }),
+          self.dbus_state_changed(),
+        ]);
       }
-      Message::DeviceSelected(device) => {
-        if let Some(dev) = self.device_options.get(device) {
-          self.selected_device = dev.clone();
-          self.selected_device_idx = Some(device);
+      Message::ApplySetting(setting, generation) => {
+        let pending = self.pending_settings.get(setting);
+        if pending.latest != generation {
+          return Task::none();
+        }
+        pending.applied = generation;
 
-          if self.files_sent {
-            self.files_sent = false;
+        let command = match setting {
+          Setting::Ssh => {
+            let ssh = self.ssh;
+            cosmic::task::future(async move {
+              let result = set_ssh(ssh).await.map_err(|e| e.to_string());
+              Message::SshSet(ssh, result)
+            })
           }
-        }
-      }
-      Message::ChooseFiles => {
-        return cosmic::task::future(async move {
-          let file_filter = FileFilter::new("Any").glob("*.*");
-          let dialog = file_chooser::open::Dialog::new()
-            .title(fl!("choose-files-title"))
-            .filter(file_filter);
-
-          match dialog.open_files().await {
-            Ok(file_responses) => {
-              Message::FilesSelected(file_responses.urls().to_vec())
-            }
-            Err(file_chooser::Error::Cancelled) => Message::FileChoosingCancelled,
-            Err(e) => {
-              error!("Choosing a file or files went wrong: {e}");
-              Message::FileChoosingCancelled
-            }
+          Setting::Routes => {
+            let routes = self.routes;
+            cosmic::task::future(async move {
+              let result = set_routes(routes).await.map_err(|e| e.to_string());
+              Message::RoutesSet(routes, result)
+            })
+          }
+          Setting::Connect => {
+            let connect = self.connect;
+            cosmic::task::future(async move {
+              let result = tailscale_int_up(connect).await.map_err(|e| e.to_string());
+              Message::ConnectionSet(connect, result)
+            })
           }
+        };
+        // Aborting drops the command's future, which kills the CLI process.
+        let (command, handle) = command.abortable();
+        self.pending_settings.get(setting).in_flight.push_back(InFlight {
+          since: Instant::now(),
+          handle,
         });
+        return Task::batch([command, stuck_command_watchdog(setting)]);
       }
-      Message::FilesSelected(urls) => {
-        for url in &urls {
-          if let Ok(path) = url.to_file_path() {
-            if path.exists() {
-              self.send_files.push(path);
-            }
-          } else {
-            warn!("Invalid file URL: {url}");
-          }
+      Message::CheckStuckCommand(setting) => {
+        if self.pending_settings.get(setting).stuck() {
+          warn!("{setting:?} command is still running after {STUCK_COMMAND_SECS}s");
         }
-
-        self.files_sent = false;
-        return self.create_popup();
       }
-      Message::SendFiles => {
-        let files = self.send_files.clone();
-        let dev = self.selected_device.clone();
-
-        if dev != "Select" {
-          self.files_sent = true;
-          return cosmic::task::future(async move {
-            let tx_status = tailscale_send(&files, &dev).await;
-            Message::FilesSent(tx_status)
-          });
+      Message::KeepWaiting(setting) => {
+        for command in &mut self.pending_settings.get(setting).in_flight {
+          command.since = Instant::now();
         }
+        return stuck_command_watchdog(setting);
       }
-      Message::FilesSent(tx_status) => {
-        self.send_file_status = match tx_status {
-          Some(err_val) => err_val,
-          None => fl!("files-sent-success"),
-        };
-
-        if !self.send_file_status.is_empty() {
-          if !self.send_files.is_empty() {
-            self.send_files.clear();
-          }
-
-          return cosmic::task::future(async move { Message::ClearTailDropStatus });
+      Message::CancelCommand(setting) => {
+        let pending = self.pending_settings.get(setting);
+        for command in pending.in_flight.drain(..) {
+          command.handle.abort();
         }
+        warn!("Cancelled stuck {setting:?} command");
+        self.last_errors.settings = Some(fl!("command-cancelled"));
+        // The command may have got partway, so ask the daemon where it ended up.
+        return cosmic::task::future(async { Message::RefreshState });
+      }
+      Message::ExitLocations(msg) => {
+        return self.update_exit_locations(msg);
       }
-      Message::FileChoosingCancelled => {
-        return self.create_popup();
+      Message::ExitNodePicker(msg) => {
+        return self.update_exit_node_picker(msg);
       }
-      Message::ReceiveFiles => {
+      Message::PingExitNode => {
+        if self.exit_node_ping_pending
+          || self.egress_node.is_empty()
+          || self.backend_state != BackendState::Running
+        {
+          return Task::none();
+        }
+        self.exit_node_ping_pending = true;
+        let node = self.egress_node.clone();
         return cosmic::task::future(async move {
-          let rx_status = tailscale_receive().await;
-          Message::FilesReceived(rx_status)
+          let result = ping_once(&PeerName::from(node.as_str()))
+            .await
+            .map_err(|e| e.to_string());
+          Message::ExitNodePinged(node, result)
         });
       }
-      Message::FilesReceived(rx_status) => {
-        self.receive_file_status = rx_status;
-
-        if !self.receive_file_status.is_empty() {
-          return cosmic::task::future(async move { Message::ClearTailDropStatus });
-        }
+      Message::ExitNodePinged(node, result) => {
+        self.exit_node_ping_pending = false;
+        self.exit_node_latency = Some((node, result));
       }
-      Message::ExitNodeSelected(exit_node) => {
-        if !self.is_exit_node
-          && let Some(node) = self.avail_exit_nodes.get(exit_node).cloned()
+      Message::ExitNodeSelected(node) => {
+        if let Some(name) = &node
+          && !self.exit_node_available(name.as_str())
         {
+          warn!("{name} is not in the exit node list");
+          return Task::none();
+        }
+        if !self.is_exit_node {
           self.sel_exit_node.clone_from(&node);
-          self.sel_exit_node_idx = Some(exit_node);
+          self.pref_sources.exit_node_pending = true;
 
-          let exit_node_name = if exit_node == 0 {
-            String::new()
-          } else {
-            node.clone()
-          };
+          let allow_lan = self
+            .config
+            .exit_node_lan_access
+            .get(node.as_ref().map_or("", PeerName::as_str))
+            .copied()
+            .filter(|allow| *allow != self.allow_lan);
 
           return cosmic::task::future(async move {
-            let success = set_exit_node(&exit_node_name).await.is_ok();
-            Message::ExitNodeSet(node, exit_node, success)
+            let result = set_exit_node(node.as_ref().map_or("", PeerName::as_str), allow_lan)
+              .await
+              .map_err(|e| e.to_string());
+            Message::ExitNodeSet(node, allow_lan, result)
           });
         }
       }
-      Message::ExitNodeSet(_node, idx, success) => {
-        if success {
-          if let Some(ref handler) = self.config_handler
-            && let Err(e) = self.config.set_exit_node_idx(handler, idx)
+      Message::ExitNodeSet(node, allow_lan, result) => {
+        self.pref_sources.exit_node_pending = false;
+        note_set_result(&mut self.pref_sources.exit_node, &result);
+        if result.is_ok()
+          && let Some(allow) = allow_lan
+        {
+          self.allow_lan = allow;
+          self.save_allow_lan(allow);
+        }
+        if self.settings_result(result, "exit node")
+          && let Some(ref handler) = self.config_handler
+        {
+          if let Err(e) = self.config.set_exit_node(handler, node.clone().unwrap_or_default()) {
+            self.config_writes.record("exit node config", &e);
+          }
+          if let Some(node) = node
+            && node != self.config.last_exit_node
+            && let Err(e) = self.config.set_last_exit_node(handler, node)
           {
-            error!("Failed to save exit node config: {e}");
+            self.config_writes.record("last exit node", &e);
           }
-        } else {
-          error!("Failed to set exit node");
         }
       }
       Message::AllowExitNodeLanAccess(allow_lan_access) => {
+        if self.editing() {
+          self.stage_allow_lan(allow_lan_access);
+          return Task::none();
+        }
         self.allow_lan = allow_lan_access;
 
-        if self.is_exit_node {
+        if self.is_exit_node || !self.current_exit_node().is_empty() {
           let allow = self.allow_lan;
+          self.lan_access_pending = true;
           return cosmic::task::future(async move {
-            let success = exit_node_allow_lan_access(allow).await.is_ok();
-            Message::LanAccessSet(allow, success)
+            let result = exit_node_allow_lan_access(allow).await.map_err(|e| e.to_string());
+            Message::LanAccessSet(allow, result)
           });
         }
       }
-      Message::LanAccessSet(value, success) => {
-        if success {
-          if let Some(ref handler) = self.config_handler
-            && let Err(e) = self.config.set_allow_lan(handler, value)
-          {
-            error!("Failed to save LAN access config: {e}");
-          }
+      Message::LanAccessSet(value, result) => {
+        self.lan_access_pending = false;
+        if self.settings_result(result, "LAN access") {
+          self.save_allow_lan(value);
         } else {
           self.allow_lan = !value;
-          error!("Failed to set LAN access");
         }
       }
       Message::UpdateIsExitNode(is_exit_node) => {
-        if self.sel_exit_node_idx == Some(0) || self.sel_exit_node_idx.is_none() {
+        if self.editing() {
+          if self.shown_exit_node().is_none() {
+            self.stage_is_exit_node(is_exit_node);
+          }
+          return Task::none();
+        }
+        if self.sel_exit_node.is_none() {
           self.is_exit_node = is_exit_node;
+          self.host_exit_node_pending = true;
           let exit_node = self.is_exit_node;
 
           return cosmic::task::future(async move {
-            let success = enable_exit_node(exit_node).await.is_ok();
-            Message::ExitNodeEnabled(exit_node, success)
+            let result = enable_exit_node(exit_node).await.map_err(|e| e.to_string());
+            Message::ExitNodeEnabled(exit_node, result)
           });
         }
       }
-      Message::ExitNodeEnabled(value, success) => {
-        if success {
+      Message::ExitNodeEnabled(value, result) => {
+        self.host_exit_node_pending = false;
+        if self.settings_result(result, "host exit node") {
           return cosmic::task::future(async { Message::RefreshState });
         }
         self.is_exit_node = !value;
-        error!("Failed to enable/disable exit node");
       }
-      Message::ClearTailDropStatus => {
-        if !self.receive_file_status.is_empty() {
-          return cosmic::task::future(async move {
-            Message::FilesReceived(
-              match clear_status(STATUS_CLEAR_TIME).await {
-                Some(bad_value) => format!(
-                  "Something went wrong and clear status returned a value: {bad_value}"
-                ),
-                None => String::new(),
-              },
-            )
-          });
-        } else if !self.send_file_status.is_empty() || self.files_sent {
-          self.selected_device_idx = Some(0);
-          if let Some(dev) = self.device_options.first() {
-            self.selected_device = dev.clone();
+      Message::SuspendExitNode => {
+        if let Some(node) = &self.sel_exit_node {
+          self.suspended_exit_node = Some(node.to_string());
+          return self.update(Message::ExitNodeSelected(None));
+        }
+      }
+      Message::RestoreExitNode => {
+        if let Some(node) = self.suspended_exit_node.take() {
+          if self.exit_node_available(&node) {
+            return self.update(Message::ExitNodeSelected(Some(node.into())));
           }
-
-          return cosmic::task::future(async move {
-            Message::FilesSent(match clear_status(STATUS_CLEAR_TIME).await {
-              Some(bad_value) => Some(format!(
-                "Something went wrong and clear status returned a value: {bad_value}"
-              )),
-              None => Some(String::new()),
-            })
-          });
+          warn!("Suspended exit node {node} is no longer available");
+          self.last_errors.settings = Some(fl!("exit-node-restore-failed", node = node));
         }
       }
     }
@@ -476,46 +2496,73 @@ impl cosmic::Application for Window {
   }
 
   fn view(&self) -> Element<'_, Self::Message> {
-    self
-      .core
-      .applet
-      .icon_button("tailscale-icon")
-      .on_press(Message::TogglePopup)
+    // Show that a connection is being brought up, e.g. by auto-connect.
+    let icon = if self.backend_state == BackendState::Starting
+      || (self.connect && self.pending_settings.connect.busy())
+    {
+      &self.panel_icons.connecting
+    } else if self.backend_state != BackendState::Running {
+      &self.panel_icons.disconnected
+    } else if self.egress_node.is_empty() {
+      &self.panel_icons.connected
+    } else {
+      &self.panel_icons.exit_node
+    };
+
+    let button = match icon {
+      PanelIcon::Named(name) => self.core.applet.icon_button(name),
+      PanelIcon::File(path) => self
+        .core
+        .applet
+        .icon_button_from_handle(icon::from_path(path.clone())),
+    }
+    .on_press(Message::TogglePopup);
+    let content: Element<'_, Message> = match self.panel_badge() {
+      Some(badge) => stack![
+        button,
+        container(icon::from_name(badge).size(PANEL_BADGE_SIZE))
+          .align_right(Length::Fill)
+          .align_bottom(Length::Fill),
+      ]
+      .into(),
+      None => button.into(),
+    };
+
+    let content = self.core.applet.applet_tooltip::<Message>(
+      content,
+      self.panel_tooltip.clone(),
+      self.popup.is_some(),
+      Message::Surface,
+      None,
+    );
+    mouse_area(content)
+      .on_right_press(Message::ToggleContextMenu)
       .into()
   }
 
-  fn view_window(&self, _id: Id) -> Element<'_, Self::Message> {
-    let cur_acct = &self.cur_acct;
-    let acct_list = &self.acct_list;
-    let ip = &self.ip;
-
-    let mut sel_acct_idx = None;
-    for (idx, acct) in acct_list.iter().enumerate() {
-      if acct == cur_acct {
-        sel_acct_idx = Some(idx);
-        break;
-      }
+  fn view_window(&self, id: Id) -> Element<'_, Self::Message> {
+    if self.context_menu == Some(id) {
+      return self.context_menu_view();
     }
 
-    let conn_status = self.conn_status;
+    let mutable = !self.config.monitor_only;
+    // Settings that would conflict with a connect or account switch in flight.
+    let settable = mutable && !self.transitioning();
 
     let status_elements: Vec<Element<'_, Message>> = vec![Element::from(column!(
-      row!(settings::item(
-        fl!("account"),
-        dropdown(acct_list, sel_acct_idx, Message::SwitchAccount)
-      )),
-      row!(settings::item(
-        fl!("tailscale-address"),
-        text(ip.clone()),
-      )),
+      self.account_view(),
+      self.tailnet_view(),
+      self.tailnet_summary_view(),
+      self.address_view(),
+      self.dns_name_view(),
       row!(settings::item(
         fl!("connection-status"),
-        text(if conn_status {
-          fl!("connected")
-        } else {
-          fl!("disconnected")
-        })
+        text(backend_state_label(self.backend_state))
       )),
+      self.traffic_path_view(),
+      self.relay_view(),
+      self.key_expiry_view(),
+      self.funnel_view(),
     ))];
 
     let status_row = Row::with_children(status_elements)
@@ -524,122 +2571,102 @@ impl cosmic::Application for Window {
 
     let enable_elements: Vec<Element<'_, Message>> = vec![Element::from(
       column!(
+        self.staging_view(mutable),
         row!(settings::item(
           fl!("enable-ssh"),
-          toggler(self.ssh).on_toggle(Message::EnableSSH)
+          row!(
+            busy_marker(self.pending_settings.ssh.busy()),
+            source_marker(self.pref_sources.ssh),
+            toggler(self.shown_ssh()).on_toggle_maybe(settable.then_some(Message::EnableSSH))
+          )
+          .align_y(Alignment::Center)
+          .spacing(8)
         )),
+        self.ssh_confirm_view(),
         row!(settings::item(
           fl!("accept-routes"),
-          toggler(self.routes).on_toggle(Message::AcceptRoutes)
+          row!(
+            busy_marker(self.pending_settings.routes.busy()),
+            source_marker(self.pref_sources.routes),
+            toggler(self.shown_routes()).on_toggle_maybe(settable.then_some(Message::AcceptRoutes))
+          )
+          .align_y(Alignment::Center)
+          .spacing(8)
+        )),
+        self.subnet_routes_view(),
+        row!(settings::item(
+          fl!("play-sounds"),
+          toggler(self.config.play_sounds).on_toggle(Message::TogglePlaySounds)
+        )),
+        row!(settings::item(
+          fl!("notifications"),
+          toggler(self.config.notifications).on_toggle(Message::ToggleNotifications)
+        )),
+        row!(settings::item(
+          fl!("auto-connect"),
+          toggler(self.config.auto_connect).on_toggle(Message::ToggleAutoConnect)
+        )),
+        row!(settings::item(
+          fl!("pause-when-locked"),
+          toggler(self.config.pause_when_locked).on_toggle(Message::TogglePauseWhenLocked)
+        )),
+        row!(settings::item(
+          fl!("confirm-disconnect"),
+          toggler(self.config.confirm_disconnect).on_toggle(Message::ToggleConfirmDisconnect)
+        )),
+        row!(settings::item(
+          fl!("check-clock"),
+          toggler(self.config.check_clock).on_toggle(Message::ToggleCheckClock)
         )),
+        error_banner(
+          self.last_errors.settings.as_deref(),
+          None,
+          Message::DismissSettingsError
+        ),
       )
       .spacing(5),
     )];
 
     let enable_row = Row::with_children(enable_elements);
 
-    let taildrop_elements: Vec<Element<'_, Message>> = vec![Element::from(
-      column!(
-        row!(text(fl!("tail-drop"))).align_y(Alignment::Center),
-        row!(
-          column!(
-            dropdown(
-              &self.device_options,
-              self.selected_device_idx,
-              Message::DeviceSelected
-            )
-            .width(110),
-          )
-          .align_x(Horizontal::Left)
-          .padding(5),
-          horizontal_space().width(Length::Fill),
-          column!(
-            button::standard(fl!("select-files"))
-              .on_press(Message::ChooseFiles)
-              .width(220)
-              .tooltip(fl!("select-files-tooltip"))
-          )
-          .align_x(Horizontal::Right)
-          .padding(5)
-        )
-        .align_y(Alignment::Center)
-        .spacing(25),
-        row!(
-          column!(if !self.send_files.is_empty() {
-            button::standard(fl!("send-files"))
-              .on_press(Message::SendFiles)
-              .width(110)
-              .tooltip(fl!("send-files-tooltip"))
-          } else {
-            button::standard(fl!("send-files"))
-              .width(110)
-              .tooltip(fl!("send-files-tooltip"))
-          })
-          .align_x(Horizontal::Left)
-          .padding(5),
-          horizontal_space().width(Length::Fill),
-          column!(
-            button::standard(fl!("receive-files"))
-              .on_press(Message::ReceiveFiles)
-              .width(220)
-              .tooltip(fl!("receive-files-tooltip"))
-          )
-          .align_x(Horizontal::Right)
-          .padding(5)
-        )
-        .align_y(Alignment::Center)
-        .spacing(25)
-      )
-      .align_x(Alignment::Center),
-    )];
-
-    let taildrop_row = Row::with_children(taildrop_elements);
-
-    let taildrop_status_elements: Vec<Element<'_, Message>> = vec![Element::from(column!(
-      row!(text(fl!("send-receive-status"))
-        .width(Length::Fill)
-        .align_x(Horizontal::Center))
-      .height(30)
-      .align_y(Alignment::Center),
-      row!(if !self.send_file_status.is_empty() {
-        text(self.send_file_status.clone())
-      } else if self.files_sent && self.selected_device != *"Select" {
-        text(fl!("files-sent-success"))
-      } else if self.selected_device == *"Select" && !self.files_sent {
-        text(fl!("choose-device-first"))
-      } else {
-        text("")
-      }),
-      row!(text(self.receive_file_status.clone()))
-    ))];
-
-    let tx_rx_status_row = Row::with_children(taildrop_status_elements);
-
     let mut exit_node_elements: Vec<Element<'_, Message>> = Vec::new();
 
+    let is_exit_node = self.shown_is_exit_node();
+    let exit_node = self.shown_exit_node();
+    let exit_node_picker =
+      self.exit_node_picker_view(exit_node, dropdown_width(&self.avail_exit_nodes), settable);
     let host_exit_node_col = column!(
-      Element::from(
-        if self.sel_exit_node_idx == Some(0) || self.sel_exit_node_idx.is_none() {
-          if self.is_exit_node {
-            toggler(self.is_exit_node)
+      row!(
+        if exit_node.is_none() {
+          if is_exit_node {
+            toggler(is_exit_node)
               .label(fl!("disable-host-exit-node"))
-              .on_toggle(Message::UpdateIsExitNode)
+              .on_toggle_maybe(settable.then_some(Message::UpdateIsExitNode))
           } else {
-            toggler(self.is_exit_node)
+            toggler(is_exit_node)
               .label(fl!("enable-host-exit-node"))
-              .on_toggle(Message::UpdateIsExitNode)
+              .on_toggle_maybe(settable.then_some(Message::UpdateIsExitNode))
           }
         } else {
-          toggler(self.is_exit_node).label(fl!("enable-host-exit-node"))
+          toggler(is_exit_node).label(fl!("enable-host-exit-node"))
         },
-      ),
-      Element::from(if self.is_exit_node {
-        toggler(self.allow_lan)
-          .label(fl!("allow-lan-access"))
-          .on_toggle(Message::AllowExitNodeLanAccess)
-      } else {
-        toggler(self.allow_lan).label(fl!("allow-lan-access"))
-      })
+        busy_marker(self.host_exit_node_pending),
+      )
+      .align_y(Alignment::Center)
+      .spacing(8),
+      // LAN access applies both to serving as and to using an exit node.
+      row!(
+        if is_exit_node || exit_node.is_some() {
+          toggler(self.shown_allow_lan())
+            .label(fl!("allow-lan-access"))
+            .on_toggle_maybe(settable.then_some(Message::AllowExitNodeLanAccess))
+        } else {
+          toggler(self.shown_allow_lan()).label(fl!("allow-lan-access"))
+        },
+        busy_marker(self.lan_access_pending),
+      )
+      .align_y(Alignment::Center)
+      .spacing(8)
     )
     .spacing(5)
     .align_x(Alignment::Start);
@@ -654,12 +2681,15 @@ impl cosmic::Application for Window {
             text(fl!("selected-node"))
               .align_x(Alignment::Start)
               .align_y(Alignment::Center),
-            dropdown(
-              &self.avail_exit_nodes,
-              self.sel_exit_node_idx,
-              Message::ExitNodeSelected
+            row!(
+              exit_node_picker,
+              busy_marker(self.pref_sources.exit_node_pending),
             )
-            .width(125)
+            .align_y(Alignment::Center)
+            .spacing(8),
+            source_marker(self.pref_sources.exit_node),
+            self.exit_locations_view(settable),
+            self.exit_node_status_view(),
           )
           .align_x(Alignment::Center))
           .padding(15)
@@ -673,19 +2703,428 @@ impl cosmic::Application for Window {
 
     let exit_node_row = Row::with_children(exit_node_elements);
 
-    let content_list = list_column()
-      .padding(5)
-      .spacing(0)
-      .add(Element::from(status_row))
-      .add(Element::from(enable_row))
-      .add(settings::item(
-        fl!("connected-label"),
-        toggler(self.connect).on_toggle(Message::ConnectDisconnect),
-      ))
-      .add(Element::from(taildrop_row))
-      .add(Element::from(tx_rx_status_row))
-      .add(Element::from(exit_node_row));
+    let mut content_list = list_column().padding(5).spacing(0);
+
+    if !mutable {
+      content_list = content_list.add(text(fl!("monitor-only-hint")));
+    }
+
+    let refresh_error = self
+      .last_errors
+      .refresh
+      .as_deref()
+      .map(|e| fl!("refresh-failed", error = e.to_string()));
+    content_list = content_list.add(error_banner(
+      refresh_error.as_deref(),
+      Some(Message::RefreshState),
+      Message::DismissRefreshError,
+    ));
+    let config_warning = self
+      .config_writes
+      .banner()
+      .map(|e| fl!("config-not-saved", error = e.to_string()));
+    content_list = content_list.add(error_banner(
+      config_warning.as_deref(),
+      None,
+      Message::DismissConfigWarning,
+    ));
+    content_list = content_list.add(self.stuck_commands_view());
+
+    if let Some(quick_switch) = self.quick_switch_view(mutable) {
+      content_list = content_list.add(quick_switch);
+    }
+    if !self.limited {
+      content_list = content_list.add(self.favorites_view());
+    }
+
+    let clock_warning = self
+      .clock_skew
+      .filter(|skew| skew.unsigned_abs() > CLOCK_SKEW_WARN_SECS)
+      .map(|skew| {
+        let count = skew.unsigned_abs() / 60;
+        if skew > 0 {
+          fl!("clock-ahead", count = count)
+        } else {
+          fl!("clock-behind", count = count)
+        }
+      });
+    if !self.health.is_empty() || clock_warning.is_some() {
+      let mut warnings = column!(text(fl!("health-warnings")).size(14)).spacing(5);
+      for warning in self.health.iter().chain(&clock_warning) {
+        warnings = warnings.push(
+          row!(
+            icon::from_name("dialog-warning-symbolic").size(16),
+            text(warning.clone())
+          )
+          .align_y(Alignment::Center)
+          .spacing(8),
+        );
+      }
+      content_list = content_list.add(Element::from(warnings));
+    }
+
+    if self.limited {
+      content_list = content_list.add(text(fl!("limited-info")));
+    }
+
+    if !self.daemon_running {
+      content_list = content_list.add(Element::from(
+        column!(
+          settings::item(
+            fl!("daemon-not-running"),
+            button::suggested(fl!("start-daemon"))
+              .on_press_maybe(mutable.then_some(Message::StartDaemon)),
+          ),
+          text(self.daemon_start_status.clone()),
+        )
+        .spacing(5),
+      ));
+    } else if self.needs_login {
+      content_list = content_list.add(self.login_view(mutable));
+    } else if self.backend_state == BackendState::NeedsMachineAuth {
+      content_list = content_list.add(text(fl!("machine-auth-hint")));
+    } else if let Some(node) = &self.suspended_exit_node {
+      content_list = content_list.add(Element::from(
+        column!(
+          settings::item(
+            fl!("exit-node-suspended", node = node.clone()),
+            button::standard(fl!("restore-exit-node"))
+              .on_press_maybe(mutable.then_some(Message::RestoreExitNode)),
+          ),
+          text(fl!("exit-node-suspended-hint")),
+        )
+        .spacing(5),
+      ));
+    } else if self.captive_portal && !self.current_exit_node().is_empty() {
+      content_list = content_list.add(Element::from(
+        column!(
+          settings::item(
+            fl!("captive-portal-detected"),
+            button::suggested(fl!("suspend-exit-node"))
+              .on_press_maybe(mutable.then_some(Message::SuspendExitNode)),
+          ),
+          text(fl!("captive-portal-hint")),
+        )
+        .spacing(5),
+      ));
+    } else if !self.is_operator {
+      content_list = content_list.add(Element::from(
+        column!(
+          settings::item(
+            fl!("read-only-mode"),
+            button::suggested(fl!("grant-operator"))
+              .on_press_maybe(mutable.then_some(Message::GrantOperator)),
+          ),
+          text(fl!("read-only-mode-hint")),
+          text(self.operator_status.clone()),
+        )
+        .spacing(5),
+      ));
+    }
+
+    content_list = content_list.add(Element::from(status_row));
+
+    // Until the node is logged in and approved, only the prompts above apply.
+    let logged_in = self.daemon_running && !self.needs_login && self.backend_state.logged_in();
+    if logged_in {
+      // Connecting without any network only fails, so offer it once one is back.
+      let can_toggle = mutable && (self.connect || self.host_online);
+      content_list = content_list
+        .add(Element::from(enable_row))
+        .add(self.offline_view())
+        .add(settings::item(
+          fl!("connected-label"),
+          row!(
+            busy_marker(self.pending_settings.connect.busy()),
+            toggler(self.connect).on_toggle_maybe(can_toggle.then_some(Message::ConnectionToggled)),
+          )
+          .align_y(Alignment::Center)
+          .spacing(8),
+        ))
+        .add(self.disconnect_confirm_view());
+    }
+
+    // Peer lists are unknown in limited mode, so skip the sections built on them.
+    if logged_in && !self.limited {
+      content_list = content_list.add(self.peers_view());
+      #[cfg(feature = "taildrop")]
+      {
+        content_list = content_list.add(self.taildrop_view(mutable));
+      }
+      content_list = content_list
+        .add(Element::from(exit_node_row))
+        .add(self.advertise_routes_view(mutable))
+        .add(self.advanced_view(mutable));
+    }
+
+    let content_list = content_list
+      .add(Element::from(
+        column!(
+          settings::item(
+            fl!("diagnostics"),
+            button::standard(fl!("generate-diagnostics"))
+              .on_press(Message::GenerateDiagnostics)
+              .tooltip(fl!("generate-diagnostics-tooltip")),
+          ),
+          text(self.diagnostics_status.clone()),
+          self.peer_export_view(),
+          self.netcheck_view(),
+          self.whois_view(),
+          self.backend_info_view(),
+          self.raw_status_view(),
+          self.version_footer(mutable),
+        )
+        .spacing(5),
+      ));
 
     self.core.applet.popup_container(content_list).into()
   }
 }
+
+fn warning_text(theme: &cosmic::Theme) -> cosmic::iced_widget::text::Style {
+  cosmic::iced_widget::text::Style {
+    color: Some(theme.cosmic().warning_text_color().into()),
+  }
+}
+
+/// "fra (Frankfurt)", or just `fallback` when the region isn't known.
+fn derp_region_label(region: Option<&DerpRegion>, fallback: &str) -> String {
+  match region {
+    Some(region) => fl!("relay-region", code = region.code.clone(), name = region.name.clone()),
+    None => fallback.to_string(),
+  }
+}
+
+/// Connection status line for each backend state.
+fn backend_state_label(state: BackendState) -> String {
+  match state {
+    BackendState::NoState => fl!("backend-no-state"),
+    BackendState::NeedsLogin => fl!("logged-out"),
+    BackendState::NeedsMachineAuth => fl!("backend-needs-machine-auth"),
+    BackendState::Stopped => fl!("disconnected"),
+    BackendState::Starting => fl!("backend-starting"),
+    BackendState::Running => fl!("connected"),
+  }
+}
+
+/// Working indicator next to a control whose command hasn't finished yet.
+fn busy_marker(busy: bool) -> Element<'static, Message> {
+  if busy {
+    icon::from_name("process-working-symbolic").size(16).into()
+  } else {
+    Row::new().into()
+  }
+}
+
+/// Small marker next to a setting that was changed outside this applet.
+fn source_marker(source: ChangeSource) -> Element<'static, Message> {
+  match source {
+    ChangeSource::External => text(fl!("changed-externally")).size(12).into(),
+    ChangeSource::Policy => text(fl!("set-by-policy")).size(12).into(),
+    ChangeSource::Unknown | ChangeSource::Applet => Row::new().into(),
+  }
+}
+
+/// Fetch the connection section, reusing another instance's fresh state unless
+/// `force` is set.
+fn refresh_connection(force: bool) -> Task<Action<Message>> {
+  cosmic::task::future(async move {
+    match fetch_shared_state(force).await {
+      Ok((stamp, state)) => Message::ConnectionRefreshed(stamp, Box::new(state)),
+      Err(e) => Message::RefreshFailed(e.to_string()),
+    }
+  })
+}
+
+/// Fetch the peer list, from the same shared state as the connection so both
+/// refreshes can share one status query.
+fn refresh_peers() -> Task<Action<Message>> {
+  cosmic::task::future(async {
+    match fetch_shared_state(false).await {
+      // Limited status has no peer list, so the last one is kept.
+      Ok((_, state)) if !state.limited => {
+        Message::PeersRefreshed(state.peers, state.subnet_routers)
+      }
+      Ok(_) => Message::SharedStateUnchanged,
+      Err(e) => {
+        warn!("Failed to refresh peers: {e}");
+        Message::SharedStateUnchanged
+      }
+    }
+  })
+}
+
+/// Fetch the exit node lists, which only the popup needs.
+fn refresh_exit_nodes() -> Task<Action<Message>> {
+  cosmic::task::future(async {
+    Message::ExitNodesRefreshed(fetch_exit_node_lists().await.map_err(|e| e.to_string()))
+  })
+}
+
+/// Check on `setting`'s command once it should have finished, so the popup
+/// offers to cancel it if it hasn't.
+fn stuck_command_watchdog(setting: Setting) -> Task<Action<Message>> {
+  cosmic::task::future(async move {
+    tokio::time::sleep(Duration::from_secs(STUCK_COMMAND_SECS)).await;
+    Message::CheckStuckCommand(setting)
+  })
+}
+
+/// Inline "last error" line for a popup section, empty when there is none.
+fn section_error(error: Option<&str>) -> Element<'static, Message> {
+  match error {
+    Some(err) => text(fl!("last-error", error = err.to_string())).into(),
+    None => Row::new().into(),
+  }
+}
+
+/// Dismissible error banner, with a retry button when the failed action can be
+/// repeated; empty when there is no error.
+fn error_banner(
+  error: Option<&str>,
+  retry: Option<Message>,
+  dismiss: Message,
+) -> Element<'static, Message> {
+  let Some(error) = error else {
+    return Row::new().into();
+  };
+
+  let mut banner = row!(
+    icon::from_name("dialog-error-symbolic").size(16),
+    text(error.to_string()).width(Length::Fill),
+  )
+  .align_y(Alignment::Center)
+  .spacing(8)
+  .padding(5);
+  if let Some(retry) = retry {
+    banner = banner.push(button::standard(fl!("retry")).on_press(retry));
+  }
+  banner
+    .push(
+      button::icon(icon::from_name("window-close-symbolic"))
+        .on_press(dismiss)
+        .tooltip(fl!("dismiss")),
+    )
+    .into()
+}
+
+/// Width that fits the longest of `labels`, so long hostnames aren't clipped and
+/// short lists don't waste space. Only labels past the maximum are clipped.
+#[allow(clippy::cast_precision_loss)]
+fn dropdown_width(labels: &[String]) -> f32 {
+  let longest = labels
+    .iter()
+    .map(|label| label.chars().count())
+    .max()
+    .unwrap_or_default();
+  (longest as f32 * DROPDOWN_CHAR_WIDTH + DROPDOWN_CHROME_WIDTH)
+    .clamp(DROPDOWN_MIN_WIDTH, DROPDOWN_MAX_WIDTH)
+}
+
+/// How long ago something happened, e.g. "3 hours ago".
+fn format_age(secs: u64) -> String {
+  match secs {
+    0..60 => fl!("age-just-now"),
+    60..3600 => fl!("age-minutes", count = secs / 60),
+    3600..86400 => fl!("age-hours", count = secs / 3600),
+    _ => fl!("age-days", count = secs / 86400),
+  }
+}
+
+/// Serve the D-Bus interface and forward incoming requests as messages.
+fn dbus_subscription() -> Subscription<Message> {
+  Subscription::run_with_id(
+    TypeId::of::<DbusCommand>(),
+    cosmic::iced::stream::channel(16, |mut output| async move {
+      let (tx, mut rx) = tokio::sync::mpsc::channel(16);
+
+      let conn = match dbus::serve(tx).await {
+        Ok(conn) => conn,
+        Err(e) => {
+          warn!("D-Bus interface unavailable: {e}");
+          return;
+        }
+      };
+
+      if output.send(Message::DbusReady(conn)).await.is_err() {
+        return;
+      }
+
+      while let Some(cmd) = rx.recv().await {
+        if output.send(Message::Dbus(cmd)).await.is_err() {
+          break;
+        }
+      }
+    }),
+  )
+}
+
+/// Report the login session locking and unlocking, starting with its current state.
+fn session_lock_subscription() -> Subscription<Message> {
+  struct SessionLock;
+
+  Subscription::run_with_id(
+    TypeId::of::<SessionLock>(),
+    cosmic::iced::stream::channel(4, |mut output| async move {
+      let (locked, mut changes) = match dbus::watch_session_lock().await {
+        Ok(watch) => watch,
+        Err(e) => {
+          warn!("Session lock state unavailable: {e}");
+          return;
+        }
+      };
+
+      if output.send(Message::SessionLocked(locked)).await.is_err() {
+        return;
+      }
+
+      while let Some(change) = changes.next().await {
+        match change.get().await {
+          Ok(locked) => {
+            if output.send(Message::SessionLocked(locked)).await.is_err() {
+              break;
+            }
+          }
+          Err(e) => warn!("Failed to read session lock state: {e}"),
+        }
+      }
+    }),
+  )
+}
+
+/// Run `tailscale login` for one login attempt, reporting its progress.
+fn login_subscription(attempt: u64) -> Subscription<Message> {
+  Subscription::run_with_id(
+    (TypeId::of::<LoginEvent>(), attempt),
+    cosmic::iced::stream::channel(4, |mut output| async move {
+      let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+      // Dropping the subscription drops the login too, which kills the CLI.
+      let forward = async move {
+        while let Some(event) = rx.recv().await {
+          if output.send(Message::Login(event)).await.is_err() {
+            break;
+          }
+        }
+      };
+      cosmic::iced::futures::future::join(run_login(tx), forward).await;
+    }),
+  )
+}
+
+/// Runs `pkexec tailscale update` for one attempt, forwarding its progress.
+fn update_subscription(attempt: u64) -> Subscription<Message> {
+  Subscription::run_with_id(
+    (TypeId::of::<UpdateEvent>(), attempt),
+    cosmic::iced::stream::channel(4, |mut output| async move {
+      let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+      let forward = async move {
+        while let Some(event) = rx.recv().await {
+          if output.send(Message::Update(event)).await.is_err() {
+            break;
+          }
+        }
+      };
+      cosmic::iced::futures::future::join(run_update(tx), forward).await;
+    }),
+  )
+}
@@ -0,0 +1,243 @@
+//! Login profile switching, compiled with the `accounts` feature.
+
+use super::{Message, Window};
+use crate::fl;
+use crate::ids::ProfileId;
+use crate::logic::{get_acct_list, switch_accounts, AccountProfile};
+use cosmic::iced::{
+  widget::{column, row},
+  Alignment, Length, Subscription,
+};
+use cosmic::iced_widget::Row;
+use cosmic::widget::{button, icon, scrollable, settings, text, text_input};
+use cosmic::{Action, Element, Task};
+use tracing::error;
+
+const ACCOUNT_LIST_HEIGHT: f32 = 200.0;
+/// How often a queued switch checks whether what it waits for has finished.
+const QUEUED_SWITCH_POLL_SECS: u64 = 1;
+
+/// Account switcher state; profiles are only fetched while the list is open.
+#[derive(Default)]
+pub struct AccountsState {
+  accounts: Vec<AccountProfile>,
+  open: bool,
+  loading: bool,
+  search: String,
+  /// A `tailscale switch` is running.
+  switching: bool,
+  /// Profile to switch to once transfers and settings changes finish, and
+  /// what it is waiting for.
+  queued: Option<(ProfileId, String)>,
+}
+
+impl AccountsState {
+  /// Whether an account switch is still running.
+  pub fn switching(&self) -> bool {
+    self.switching
+  }
+}
+
+/// Messages handled by the account switcher.
+#[derive(Clone, Debug)]
+pub enum AccountsMessage {
+  ToggleList,
+  Refreshed(Result<Vec<AccountProfile>, String>),
+  SearchChanged(String),
+  Switch(ProfileId),
+  /// See whether a queued switch can run yet.
+  RetryQueued,
+  CancelQueued,
+  Switched(Result<(), String>),
+}
+
+impl AccountsMessage {
+  /// Whether handling this message changes Tailscale state.
+  pub fn is_mutation(&self) -> bool {
+    matches!(self, AccountsMessage::Switch(_))
+  }
+}
+
+impl From<AccountsMessage> for Message {
+  fn from(msg: AccountsMessage) -> Self {
+    Message::Accounts(msg)
+  }
+}
+
+impl Window {
+  pub(super) fn update_accounts(&mut self, message: AccountsMessage) -> Task<Action<Message>> {
+    let state = &mut self.accounts;
+
+    match message {
+      AccountsMessage::ToggleList => {
+        state.open = !state.open;
+        state.search = String::new();
+
+        return self.refresh_accounts();
+      }
+      AccountsMessage::Refreshed(result) => {
+        state.loading = false;
+        match result {
+          Ok(accounts) => state.accounts = accounts,
+          Err(e) => {
+            error!("Failed to list accounts: {e}");
+            self.last_errors.settings = Some(e);
+          }
+        }
+      }
+      AccountsMessage::SearchChanged(search) => {
+        state.search = search;
+      }
+      AccountsMessage::Switch(id) => {
+        // Switching profiles mid-transfer or mid-change aborts those with
+        // confusing errors, so wait for them.
+        if let Some(reason) = self.switch_blocker() {
+          let state = &mut self.accounts;
+          state.open = false;
+          state.queued = Some((id, reason));
+          return Task::none();
+        }
+        return self.start_switch(id);
+      }
+      AccountsMessage::RetryQueued => {
+        if let Some(reason) = self.switch_blocker() {
+          if let Some((_, waiting_for)) = &mut self.accounts.queued {
+            *waiting_for = reason;
+          }
+        } else if let Some((id, _)) = self.accounts.queued.take() {
+          return self.start_switch(id);
+        }
+      }
+      AccountsMessage::CancelQueued => {
+        state.queued = None;
+      }
+      AccountsMessage::Switched(result) => {
+        state.switching = false;
+        state.accounts.clear();
+        self.settings_result(result, "account");
+        return cosmic::task::future(async { Message::RefreshState });
+      }
+    }
+    Task::none()
+  }
+
+  /// Reload the profiles if the list is open; many tailnets make listing them
+  /// slow, so they are only fetched on demand.
+  pub(super) fn refresh_accounts(&mut self) -> Task<Action<Message>> {
+    let state = &mut self.accounts;
+    if !state.open || state.loading {
+      return Task::none();
+    }
+    state.loading = true;
+    cosmic::task::future(async {
+      let result = get_acct_list().await.map_err(|e| e.to_string());
+      Message::Accounts(AccountsMessage::Refreshed(result))
+    })
+  }
+
+  fn start_switch(&mut self, id: ProfileId) -> Task<Action<Message>> {
+    let state = &mut self.accounts;
+    let Some(acct) = state.accounts.iter().find(|acct| acct.id == id) else {
+      return Task::none();
+    };
+    self.cur_acct.clone_from(&acct.tailnet);
+    state.open = false;
+    state.switching = true;
+    cosmic::task::future(async move {
+      let result = switch_accounts(&id).await.map(|_| ()).map_err(|e| e.to_string());
+      Message::Accounts(AccountsMessage::Switched(result))
+    })
+  }
+
+  /// Checks on a queued switch until it can run.
+  pub(super) fn queued_switch_subscription(&self) -> Option<Subscription<Message>> {
+    self.accounts.queued.as_ref().map(|_| {
+      cosmic::iced::time::every(std::time::Duration::from_secs(QUEUED_SWITCH_POLL_SECS))
+        .map(|_| Message::Accounts(AccountsMessage::RetryQueued))
+    })
+  }
+
+  /// Account profiles matching the search text, current profile first.
+  fn filtered_accounts(&self) -> impl Iterator<Item = &AccountProfile> {
+    let search = self.accounts.search.to_lowercase();
+    self.accounts.accounts.iter().filter(move |acct| {
+      search.is_empty()
+        || acct.tailnet.to_lowercase().contains(&search)
+        || acct.account.to_lowercase().contains(&search)
+    })
+  }
+
+  /// Scrollable, searchable list of login profiles shown under the account row.
+  fn account_list_view(&self) -> Element<'_, Message> {
+    if self.accounts.loading {
+      return text(fl!("loading")).into();
+    }
+
+    let mut list = column!().spacing(2);
+    for acct in self.filtered_accounts() {
+      let label = if acct.current {
+        fl!("account-entry-current", tailnet = acct.tailnet.clone(), account = acct.account.clone())
+      } else {
+        fl!("account-entry", tailnet = acct.tailnet.clone(), account = acct.account.clone())
+      };
+
+      list = list.push(
+        button::text(label)
+          .on_press_maybe((!acct.current).then(|| AccountsMessage::Switch(acct.id.clone()).into()))
+          .width(Length::Fill),
+      );
+    }
+
+    column!(
+      text_input(fl!("search-accounts"), &self.accounts.search)
+        .on_input(|search| AccountsMessage::SearchChanged(search).into())
+        .width(Length::Fill),
+      scrollable(list).height(Length::Fixed(ACCOUNT_LIST_HEIGHT)),
+    )
+    .spacing(5)
+    .padding(5)
+    .into()
+  }
+
+  /// Current account button, with the profile list below it when open.
+  pub(super) fn account_view(&self) -> Element<'_, Message> {
+    let cur_acct = self
+      .accounts
+      .accounts
+      .iter()
+      .find(|acct| acct.current)
+      .map_or(self.cur_acct.as_str(), |acct| acct.tailnet.as_str());
+
+    let account_list: Element<'_, Message> = if self.accounts.open {
+      self.account_list_view()
+    } else {
+      Row::new().into()
+    };
+    let queued: Element<'_, Message> = match &self.accounts.queued {
+      Some((_, waiting_for)) => row!(
+        text(waiting_for.clone()).width(Length::Fill),
+        button::standard(fl!("cancel")).on_press(AccountsMessage::CancelQueued.into()),
+      )
+      .align_y(Alignment::Center)
+      .spacing(8)
+      .into(),
+      None => Row::new().into(),
+    };
+
+    column!(
+      row!(settings::item(
+        fl!("account"),
+        button::standard(cur_acct.to_string())
+          .trailing_icon(if self.accounts.open {
+            icon::from_name("go-up-symbolic")
+          } else {
+            icon::from_name("go-down-symbolic")
+          })
+          .on_press(AccountsMessage::ToggleList.into())
+      )),
+      queued,
+      account_list,
+    )
+    .into()
+  }
+}
@@ -0,0 +1,128 @@
+//! Collapsible section for less common `tailscale set` preferences.
+
+use super::{Message, Window};
+use crate::fl;
+use crate::logic::{set_advanced_pref, AdvancedPref, AdvancedPrefs};
+use cosmic::iced::widget::column;
+use cosmic::widget::{button, icon, settings, text, toggler};
+use cosmic::{Action, Element, Task};
+
+/// Advanced preferences as shown, and which are still being applied.
+#[derive(Default)]
+pub struct AdvancedState {
+  open: bool,
+  prefs: AdvancedPrefs,
+  /// Preferences with a `tailscale set` in flight, which refreshes leave alone.
+  applying: Vec<AdvancedPref>,
+}
+
+impl AdvancedState {
+  /// Whether a preference change is still being applied.
+  pub fn applying(&self) -> bool {
+    !self.applying.is_empty()
+  }
+}
+
+/// Messages handled by the Advanced section.
+#[derive(Clone, Debug)]
+pub enum AdvancedMessage {
+  Toggle,
+  Set(AdvancedPref, bool),
+  Applied(AdvancedPref, bool, Result<(), String>),
+}
+
+impl AdvancedMessage {
+  /// Whether handling this message changes Tailscale state.
+  pub fn is_mutation(&self) -> bool {
+    matches!(self, AdvancedMessage::Set(..))
+  }
+}
+
+impl From<AdvancedMessage> for Message {
+  fn from(msg: AdvancedMessage) -> Self {
+    Message::Advanced(msg)
+  }
+}
+
+impl Window {
+  pub(super) fn update_advanced(&mut self, message: AdvancedMessage) -> Task<Action<Message>> {
+    let state = &mut self.advanced;
+
+    match message {
+      AdvancedMessage::Toggle => {
+        state.open = !state.open;
+      }
+      AdvancedMessage::Set(pref, enabled) => {
+        if state.applying.contains(&pref) {
+          return Task::none();
+        }
+        state.prefs.set(pref, enabled);
+        state.applying.push(pref);
+        return cosmic::task::future(async move {
+          let result = set_advanced_pref(pref, enabled).await.map_err(|e| e.to_string());
+          Message::Advanced(AdvancedMessage::Applied(pref, enabled, result))
+        });
+      }
+      AdvancedMessage::Applied(pref, enabled, result) => {
+        state.applying.retain(|applying| *applying != pref);
+        if !self.settings_result(result, pref.flag()) {
+          self.advanced.prefs.set(pref, !enabled);
+        }
+      }
+    }
+    Task::none()
+  }
+
+  /// Preferences from a state refresh, keeping values that are still being applied.
+  pub(super) fn sync_advanced_prefs(&mut self, prefs: AdvancedPrefs) {
+    let state = &mut self.advanced;
+    let mut prefs = prefs;
+    for pref in &state.applying {
+      prefs.set(*pref, state.prefs.get(*pref));
+    }
+    state.prefs = prefs;
+  }
+
+  /// "Advanced" header, with the preference toggles below it when expanded.
+  pub(super) fn advanced_view(&self, mutable: bool) -> Element<'_, Message> {
+    let state = &self.advanced;
+
+    let header = settings::item(
+      fl!("advanced"),
+      button::icon(icon::from_name(if state.open {
+        "go-up-symbolic"
+      } else {
+        "go-down-symbolic"
+      }))
+      .on_press(AdvancedMessage::Toggle.into()),
+    );
+    if !state.open {
+      return header.into();
+    }
+
+    let pref_toggle = |pref: AdvancedPref, label: String| {
+      let editable = mutable && !state.applying.contains(&pref);
+      let on_toggle = move |enabled| Message::from(AdvancedMessage::Set(pref, enabled));
+      settings::item(
+        label,
+        toggler(state.prefs.get(pref)).on_toggle_maybe(editable.then_some(on_toggle)),
+      )
+    };
+
+    let netfilter_mode = if state.prefs.netfilter_mode.is_empty() {
+      fl!("not-available")
+    } else {
+      state.prefs.netfilter_mode.clone()
+    };
+
+    column!(
+      header,
+      pref_toggle(AdvancedPref::WebClient, fl!("web-client")),
+      pref_toggle(AdvancedPref::AutoUpdate, fl!("auto-update")),
+      pref_toggle(AdvancedPref::PostureChecking, fl!("posture-checking")),
+      settings::item(fl!("netfilter-mode"), text(netfilter_mode)),
+    )
+    .spacing(5)
+    .into()
+  }
+}
@@ -0,0 +1,205 @@
+//! Editor for the subnet routes this node advertises.
+
+use super::{Message, Window};
+use crate::fl;
+use crate::logic::{detect_lan_subnet, parse_cidr, set_advertised_routes, PRIVATE_IPV4_RANGES};
+use cosmic::iced::{
+  widget::{column, row},
+  Alignment, Length,
+};
+use cosmic::widget::{button, flex_row, icon, settings, text, text_input};
+use cosmic::{Action, Element, Task};
+
+/// Advertised routes as shown, and the route being typed.
+#[derive(Default)]
+pub struct AdvertiseRoutesState {
+  routes: Vec<String>,
+  draft: String,
+  /// The draft isn't a valid CIDR; shown until it is edited.
+  invalid: bool,
+  /// A `tailscale set` is running, so refreshes don't overwrite the list.
+  applying: bool,
+  /// Subnet of the LAN behind the default route, offered as a preset.
+  lan_subnet: Option<String>,
+}
+
+impl AdvertiseRoutesState {
+  /// Whether a route change is being applied.
+  pub fn applying(&self) -> bool {
+    self.applying
+  }
+}
+
+/// Messages handled by the route editor.
+#[derive(Clone, Debug)]
+pub enum AdvertiseRoutesMessage {
+  DraftChanged(String),
+  /// Fill the draft with a preset, so it can be checked before adding.
+  Preset(String),
+  LanSubnetDetected(Option<String>),
+  Add,
+  /// Stop advertising this CIDR.
+  Remove(String),
+  /// Routes that were applied, and the previous list to restore on failure.
+  Applied(Vec<String>, Result<(), String>),
+}
+
+impl AdvertiseRoutesMessage {
+  /// Whether handling this message changes Tailscale state.
+  pub fn is_mutation(&self) -> bool {
+    matches!(
+      self,
+      AdvertiseRoutesMessage::Add | AdvertiseRoutesMessage::Remove(_)
+    )
+  }
+}
+
+impl From<AdvertiseRoutesMessage> for Message {
+  fn from(msg: AdvertiseRoutesMessage) -> Self {
+    Message::AdvertiseRoutes(msg)
+  }
+}
+
+impl Window {
+  pub(super) fn update_advertise_routes(
+    &mut self,
+    message: AdvertiseRoutesMessage,
+  ) -> Task<Action<Message>> {
+    let state = &mut self.advertise_routes;
+
+    match message {
+      AdvertiseRoutesMessage::DraftChanged(draft) => {
+        state.draft = draft;
+        state.invalid = false;
+      }
+      AdvertiseRoutesMessage::Preset(route) => {
+        state.draft = route;
+        state.invalid = false;
+      }
+      AdvertiseRoutesMessage::LanSubnetDetected(subnet) => {
+        state.lan_subnet = subnet;
+      }
+      // Enter submits even while the buttons are disabled.
+      AdvertiseRoutesMessage::Add if state.applying => {}
+      AdvertiseRoutesMessage::Add => {
+        let Some(route) = parse_cidr(&state.draft) else {
+          state.invalid = true;
+          return Task::none();
+        };
+        state.draft.clear();
+        if state.routes.contains(&route) {
+          return Task::none();
+        }
+
+        let mut routes = state.routes.clone();
+        routes.push(route);
+        return self.apply_advertised_routes(routes);
+      }
+      AdvertiseRoutesMessage::Remove(route) => {
+        if !state.applying && state.routes.contains(&route) {
+          let routes = state.routes.iter().filter(|r| **r != route).cloned().collect();
+          return self.apply_advertised_routes(routes);
+        }
+      }
+      AdvertiseRoutesMessage::Applied(previous, result) => {
+        state.applying = false;
+        if !self.settings_result(result, "advertised routes") {
+          self.advertise_routes.routes = previous;
+          return Task::none();
+        }
+        return cosmic::task::future(async { Message::RefreshState });
+      }
+    }
+    Task::none()
+  }
+
+  /// Show `routes` right away and advertise them.
+  fn apply_advertised_routes(&mut self, routes: Vec<String>) -> Task<Action<Message>> {
+    let state = &mut self.advertise_routes;
+    let previous = std::mem::replace(&mut state.routes, routes.clone());
+    state.applying = true;
+    let is_exit_node = self.is_exit_node;
+
+    cosmic::task::future(async move {
+      let result = set_advertised_routes(&routes, is_exit_node)
+        .await
+        .map_err(|e| e.to_string());
+      Message::AdvertiseRoutes(AdvertiseRoutesMessage::Applied(previous, result))
+    })
+  }
+
+  /// Look up the current LAN subnet, which changes as the device roams.
+  pub(super) fn detect_lan_subnet(&self) -> Task<Action<Message>> {
+    cosmic::task::future(async {
+      Message::AdvertiseRoutes(AdvertiseRoutesMessage::LanSubnetDetected(
+        detect_lan_subnet().await,
+      ))
+    })
+  }
+
+  /// Routes from a state refresh, unless a change is still being applied.
+  pub(super) fn sync_advertised_routes(&mut self, routes: Vec<String>) {
+    if !self.advertise_routes.applying {
+      self.advertise_routes.routes = routes;
+    }
+  }
+
+  /// Advertised routes with remove buttons, and an input to add one.
+  pub(super) fn advertise_routes_view(&self, mutable: bool) -> Element<'_, Message> {
+    let state = &self.advertise_routes;
+    let editable = mutable && !state.applying;
+
+    let mut list = column!(text(fl!("advertised-routes")).size(14)).spacing(5);
+    if state.routes.is_empty() {
+      list = list.push(text(fl!("no-advertised-routes")));
+    }
+    for route in &state.routes {
+      list = list.push(settings::item(
+        route.clone(),
+        button::icon(icon::from_name("edit-delete-symbolic"))
+          .on_press_maybe(editable.then(|| AdvertiseRoutesMessage::Remove(route.clone()).into()))
+          .tooltip(fl!("remove-route")),
+      ));
+    }
+
+    // Presets not advertised yet; the detected LAN shows its CIDR in the label.
+    let presets: Vec<Element<'_, Message>> = state
+      .lan_subnet
+      .iter()
+      .map(|subnet| (fl!("route-preset-lan", subnet = subnet.clone()), subnet.as_str()))
+      .chain(PRIVATE_IPV4_RANGES.iter().map(|range| ((*range).to_string(), *range)))
+      .filter(|(_, route)| !state.routes.iter().any(|r| r.as_str() == *route))
+      .map(|(label, route)| {
+        button::text(label)
+          .on_press_maybe(
+            editable.then(|| AdvertiseRoutesMessage::Preset(route.to_string()).into()),
+          )
+          .into()
+      })
+      .collect();
+    if !presets.is_empty() {
+      list = list
+        .push(text(fl!("route-presets")).size(12))
+        .push(flex_row(presets).column_spacing(5).row_spacing(5));
+    }
+
+    list = list.push(
+      row!(
+        text_input(fl!("route-placeholder"), &state.draft)
+          .on_input(|draft| AdvertiseRoutesMessage::DraftChanged(draft).into())
+          .on_submit(|_| AdvertiseRoutesMessage::Add.into())
+          .width(Length::Fill),
+        button::standard(fl!("add-route")).on_press_maybe(
+          (editable && !state.draft.trim().is_empty()).then_some(AdvertiseRoutesMessage::Add.into())
+        ),
+      )
+      .align_y(Alignment::Center)
+      .spacing(10),
+    );
+    if state.invalid {
+      list = list.push(text(fl!("invalid-route")));
+    }
+
+    list.into()
+  }
+}
@@ -0,0 +1,176 @@
+//! Country and city pickers for location-based exit nodes, such as Mullvad's,
+//! which would swamp the exit node dropdown.
+
+use super::{dropdown_width, Message, StagingMessage, Window};
+use crate::fl;
+use crate::ids::PeerName;
+use crate::logic::{LocatedExitNode, CAP_MULLVAD};
+use cosmic::iced::{
+  widget::{column, row},
+  Alignment,
+};
+use cosmic::iced_widget::Row;
+use cosmic::widget::{dropdown, text};
+use cosmic::{Action, Element, Task};
+
+/// Located exit nodes, and the country and city lists for the pickers.
+#[derive(Default)]
+pub struct ExitLocationsState {
+  /// Sorted by country, city and preference, as at the end of the exit node list.
+  nodes: Vec<LocatedExitNode>,
+  countries: Vec<String>,
+  /// Country picked, whose cities are listed.
+  country: Option<String>,
+  cities: Vec<String>,
+}
+
+/// Messages handled by the location pickers.
+#[derive(Clone, Debug)]
+pub enum ExitLocationsMessage {
+  CountrySelected(String),
+  /// City in the picked country.
+  CitySelected(String),
+}
+
+impl ExitLocationsMessage {
+  /// Whether handling this message changes Tailscale state.
+  pub fn is_mutation(&self) -> bool {
+    matches!(self, ExitLocationsMessage::CitySelected(_))
+  }
+}
+
+impl From<ExitLocationsMessage> for Message {
+  fn from(msg: ExitLocationsMessage) -> Self {
+    Message::ExitLocations(msg)
+  }
+}
+
+impl ExitLocationsState {
+  fn select_country(&mut self, country: Option<String>) {
+    self.country = country.filter(|country| self.countries.contains(country));
+    self.cities = match &self.country {
+      Some(country) => {
+        let mut cities: Vec<String> = self
+          .nodes
+          .iter()
+          .filter(|node| node.country == *country)
+          .map(|node| node.city.clone())
+          .collect();
+        cities.dedup();
+        cities
+      }
+      None => Vec::new(),
+    };
+  }
+}
+
+impl Window {
+  pub(super) fn update_exit_locations(
+    &mut self,
+    message: ExitLocationsMessage,
+  ) -> Task<Action<Message>> {
+    match message {
+      ExitLocationsMessage::CountrySelected(country) => {
+        self.exit_locations.select_country(Some(country));
+      }
+      ExitLocationsMessage::CitySelected(city) => {
+        if !self.tailnet_allows(CAP_MULLVAD) {
+          return Task::none();
+        }
+        let state = &self.exit_locations;
+        let Some(country) = &state.country else {
+          return Task::none();
+        };
+        // Nodes are sorted by preference, so the first in the city is the best.
+        let Some(node) = state
+          .nodes
+          .iter()
+          .find(|node| node.country == *country && node.city == city)
+        else {
+          return Task::none();
+        };
+        let node = Some(PeerName::from(node.name.as_str()));
+        return if self.editing() {
+          self.update(StagingMessage::ExitNodeSelected(node).into())
+        } else {
+          self.update(Message::ExitNodeSelected(node))
+        };
+      }
+    }
+    Task::none()
+  }
+
+  /// Located exit nodes from an exit node refresh, keeping the picked country if
+  /// it still has nodes.
+  pub(super) fn sync_located_exit_nodes(&mut self, nodes: Vec<LocatedExitNode>) {
+    let state = &mut self.exit_locations;
+    state.countries = nodes.iter().map(|node| node.country.clone()).collect();
+    state.countries.dedup();
+    state.nodes = nodes;
+    let country = state.country.take();
+    state.select_country(country);
+  }
+
+  /// The located exit node called `name`, if it is one.
+  pub(super) fn located_exit_node(&self, name: &str) -> Option<&LocatedExitNode> {
+    self.exit_locations.nodes.iter().find(|node| node.name == name)
+  }
+
+  /// The located node in use or staged, if any.
+  fn shown_located_exit_node(&self) -> Option<&LocatedExitNode> {
+    self.located_exit_node(self.shown_exit_node()?)
+  }
+
+  /// Country and city pickers; empty when the tailnet has no located exit nodes,
+  /// and only a note when it doesn't have the Mullvad add-on.
+  pub(super) fn exit_locations_view(&self, settable: bool) -> Element<'_, Message> {
+    let state = &self.exit_locations;
+    if !self.tailnet_allows(CAP_MULLVAD) {
+      return column!(
+        text(fl!("exit-node-locations")),
+        text(fl!("not-enabled-for-tailnet")).size(12),
+      )
+      .spacing(5)
+      .into();
+    }
+    if state.nodes.is_empty() {
+      return Row::new().into();
+    }
+
+    let shown = self.shown_located_exit_node();
+    // Follow the node in use until another country is picked.
+    let country = state.country.as_ref().or(shown.map(|shown| &shown.country));
+    let city = shown
+      .filter(|shown| country == Some(&shown.country))
+      .and_then(|shown| state.cities.iter().position(|city| *city == shown.city));
+
+    // The pickers send names, resolved now, so a refresh reordering the lists
+    // before the message is handled can't change what was picked.
+    let countries = state.countries.clone();
+    let mut pickers = row!(
+      dropdown(
+        &state.countries,
+        country.and_then(|country| state.countries.iter().position(|c| c == country)),
+        move |idx| ExitLocationsMessage::CountrySelected(countries[idx].clone()).into(),
+      )
+      .width(dropdown_width(&state.countries))
+    )
+    .align_y(Alignment::Center)
+    .spacing(8);
+    if state.country.is_some() && settable {
+      let cities = state.cities.clone();
+      pickers = pickers.push(
+        dropdown(&state.cities, city, move |idx| {
+          ExitLocationsMessage::CitySelected(cities[idx].clone()).into()
+        })
+        .width(dropdown_width(&state.cities)),
+      );
+    } else if let Some(shown) = shown {
+      pickers = pickers.push(text(shown.city.clone()));
+    }
+
+    column!(text(fl!("exit-node-locations")), pickers)
+      .spacing(5)
+      .into()
+  }
+}
@@ -0,0 +1,163 @@
+//! Searchable list for choosing an exit node, which stays usable on tailnets
+//! with dozens of them.
+
+use super::{Message, StagingMessage, Window};
+use crate::fl;
+use crate::ids::PeerName;
+use cosmic::iced::{widget::column, Length};
+use cosmic::widget::{button, icon, scrollable, text_input};
+use cosmic::{Action, Element, Task};
+
+const EXIT_NODE_LIST_HEIGHT: f32 = 200.0;
+
+/// Whether the list is open, and the text it is filtered by.
+#[derive(Default)]
+pub struct ExitNodePickerState {
+  open: bool,
+  search: String,
+}
+
+/// Messages handled by the exit node picker.
+#[derive(Clone, Debug)]
+pub enum ExitNodePickerMessage {
+  Toggle,
+  SearchChanged(String),
+  /// Exit node to use, or `None` to stop using one.
+  Pick(Option<PeerName>),
+}
+
+impl ExitNodePickerMessage {
+  /// Whether handling this message changes Tailscale state.
+  pub fn is_mutation(&self) -> bool {
+    matches!(self, ExitNodePickerMessage::Pick(_))
+  }
+}
+
+impl From<ExitNodePickerMessage> for Message {
+  fn from(msg: ExitNodePickerMessage) -> Self {
+    Message::ExitNodePicker(msg)
+  }
+}
+
+impl Window {
+  pub(super) fn update_exit_node_picker(
+    &mut self,
+    message: ExitNodePickerMessage,
+  ) -> Task<Action<Message>> {
+    let state = &mut self.exit_node_picker;
+
+    match message {
+      ExitNodePickerMessage::Toggle => {
+        state.open = !state.open;
+        state.search.clear();
+      }
+      ExitNodePickerMessage::SearchChanged(search) => {
+        state.search = search;
+      }
+      ExitNodePickerMessage::Pick(node) => {
+        state.open = false;
+        state.search.clear();
+        // Edit mode stages the choice instead of applying it.
+        return if self.editing() {
+          self.update(StagingMessage::ExitNodeSelected(node).into())
+        } else {
+          self.update(Message::ExitNodeSelected(node))
+        };
+      }
+    }
+    Task::none()
+  }
+
+  /// Owner and location of an exit node, for its list entry and the search.
+  fn exit_node_details(&self, name: &str) -> Vec<String> {
+    let owner = self
+      .peers
+      .iter()
+      .find(|peer| peer.name.as_str() == name)
+      .map(|peer| peer.owner.clone());
+    let location = self
+      .located_exit_node(name)
+      .map(|node| format!("{}, {}", node.city, node.country));
+    owner
+      .into_iter()
+      .chain(location)
+      .filter(|detail| !detail.is_empty())
+      .collect()
+  }
+
+  /// Exit nodes whose name, owner or location contains the search text, with
+  /// the node each entry picks; "None" is always listed and picks `None`.
+  fn filtered_exit_nodes(&self) -> Vec<(Option<PeerName>, &String, Vec<String>)> {
+    let search = self.exit_node_picker.search.trim().to_lowercase();
+    self
+      .avail_exit_nodes
+      .iter()
+      .enumerate()
+      .map(|(idx, name)| {
+        if idx == 0 {
+          (None, name, Vec::new())
+        } else {
+          (Some(PeerName::from(name.as_str())), name, self.exit_node_details(name))
+        }
+      })
+      .filter(|(node, name, details)| {
+        node.is_none()
+          || search.is_empty()
+          || name.to_lowercase().contains(&search)
+          || details.iter().any(|detail| detail.to_lowercase().contains(&search))
+      })
+      .collect()
+  }
+
+  /// Button showing the selected exit node, with the searchable list below it
+  /// when open. Without `settable` the button is disabled.
+  pub(super) fn exit_node_picker_view(
+    &self,
+    selected: Option<&str>,
+    width: f32,
+    settable: bool,
+  ) -> Element<'_, Message> {
+    let state = &self.exit_node_picker;
+    // The first entry stands for no exit node.
+    let label = selected
+      .or_else(|| self.avail_exit_nodes.first().map(String::as_str))
+      .unwrap_or_default()
+      .to_string();
+    let toggle = button::standard(label)
+      .trailing_icon(icon::from_name(if state.open {
+        "go-up-symbolic"
+      } else {
+        "go-down-symbolic"
+      }))
+      .width(width)
+      .on_press_maybe(settable.then_some(ExitNodePickerMessage::Toggle.into()));
+    if !(state.open && settable) {
+      return toggle.into();
+    }
+
+    let mut list = column!().spacing(2);
+    for (node, name, details) in self.filtered_exit_nodes() {
+      let label = if details.is_empty() {
+        name.clone()
+      } else {
+        fl!("exit-node-entry", node = name.clone(), details = details.join(" · "))
+      };
+      let current = node.as_ref().map(PeerName::as_str) == selected;
+      list = list.push(
+        button::text(label)
+          .on_press_maybe((!current).then(|| ExitNodePickerMessage::Pick(node).into()))
+          .width(Length::Fill),
+      );
+    }
+
+    column!(
+      toggle,
+      text_input(fl!("search-exit-nodes"), &state.search)
+        .on_input(|search| ExitNodePickerMessage::SearchChanged(search).into())
+        .width(Length::Fill),
+      scrollable(list).height(Length::Fixed(EXIT_NODE_LIST_HEIGHT)),
+    )
+    .spacing(5)
+    .into()
+  }
+}
@@ -0,0 +1,147 @@
+//! Collapsible connectivity check built on `tailscale netcheck`.
+
+use super::{busy_marker, derp_region_label, Message, Window};
+use crate::fl;
+use crate::logic::{run_netcheck_report, NetcheckReport};
+use cosmic::iced::{
+  widget::{column, row},
+  Alignment,
+};
+use cosmic::widget::{button, icon, settings, text};
+use cosmic::{Action, Element, Task};
+
+/// DERP regions listed with their latency, fastest first.
+const REGIONS_SHOWN: usize = 8;
+
+/// Whether the section is expanded, and the last report.
+#[derive(Default)]
+pub struct NetcheckState {
+  open: bool,
+  running: bool,
+  report: Option<Result<NetcheckReport, String>>,
+}
+
+/// Messages handled by the connectivity check.
+#[derive(Clone, Debug)]
+pub enum NetcheckMessage {
+  Toggle,
+  Run,
+  Finished(Result<NetcheckReport, String>),
+}
+
+impl From<NetcheckMessage> for Message {
+  fn from(msg: NetcheckMessage) -> Self {
+    Message::Netcheck(msg)
+  }
+}
+
+impl Window {
+  pub(super) fn update_netcheck(&mut self, message: NetcheckMessage) -> Task<Action<Message>> {
+    let state = &mut self.netcheck;
+
+    match message {
+      NetcheckMessage::Toggle => {
+        state.open = !state.open;
+      }
+      NetcheckMessage::Run => {
+        if !state.running {
+          state.running = true;
+          return cosmic::task::future(async {
+            let result = run_netcheck_report().await.map_err(|e| e.to_string());
+            Message::Netcheck(NetcheckMessage::Finished(result))
+          });
+        }
+      }
+      NetcheckMessage::Finished(result) => {
+        state.running = false;
+        state.report = Some(result);
+      }
+    }
+    Task::none()
+  }
+
+  /// "Connectivity" header, with the check and its last report when expanded.
+  pub(super) fn netcheck_view(&self) -> Element<'_, Message> {
+    let state = &self.netcheck;
+
+    let header = settings::item(
+      fl!("netcheck"),
+      button::icon(icon::from_name(if state.open {
+        "go-up-symbolic"
+      } else {
+        "go-down-symbolic"
+      }))
+      .on_press(NetcheckMessage::Toggle.into()),
+    );
+    if !state.open {
+      return header.into();
+    }
+
+    let mut content = column!(
+      header,
+      row!(
+        button::standard(fl!("netcheck-run"))
+          .on_press_maybe((!state.running).then_some(NetcheckMessage::Run.into())),
+        busy_marker(state.running),
+      )
+      .align_y(Alignment::Center)
+      .spacing(8),
+    )
+    .spacing(5);
+
+    match &state.report {
+      None => {}
+      Some(Err(e)) => content = content.push(text(fl!("netcheck-failed", error = e.clone()))),
+      Some(Ok(report)) => content = content.push(self.netcheck_report_view(report)),
+    }
+    content.into()
+  }
+
+  fn netcheck_report_view<'a>(&'a self, report: &'a NetcheckReport) -> Element<'a, Message> {
+    let yes_no = |value: bool| if value { fl!("yes") } else { fl!("no") };
+    let nat = match report.mapping_varies_by_dest_ip {
+      Some(true) => fl!("nat-hard"),
+      Some(false) => fl!("nat-easy"),
+      None => fl!("not-available"),
+    };
+    let port_mapping: Vec<&str> = [
+      (report.upnp, "UPnP"),
+      (report.pmp, "NAT-PMP"),
+      (report.pcp, "PCP"),
+    ]
+    .into_iter()
+    .filter_map(|(supported, name)| supported.then_some(name))
+    .collect();
+    let port_mapping = if port_mapping.is_empty() {
+      fl!("netcheck-none")
+    } else {
+      port_mapping.join(", ")
+    };
+    let nearest = if report.preferred_derp == 0 {
+      fl!("not-available")
+    } else {
+      derp_region_label(
+        self.derp_region(report.preferred_derp),
+        &report.preferred_derp.to_string(),
+      )
+    };
+
+    let mut list = column!(
+      settings::item(fl!("netcheck-udp"), text(yes_no(report.udp))),
+      settings::item(fl!("netcheck-ipv4"), text(yes_no(report.ipv4))),
+      settings::item(fl!("netcheck-ipv6"), text(yes_no(report.ipv6))),
+      settings::item(fl!("netcheck-nat"), text(nat)),
+      settings::item(fl!("netcheck-port-mapping"), text(port_mapping)),
+      settings::item(fl!("netcheck-nearest-relay"), text(nearest)),
+      text(fl!("netcheck-latency")).size(14),
+    )
+    .spacing(5);
+    for (id, latency) in report.region_latency.iter().take(REGIONS_SHOWN) {
+      list = list.push(settings::item(
+        derp_region_label(self.derp_region(*id), &id.to_string()),
+        text(fl!("latency-ms", ms = latency.as_millis().to_string())),
+      ));
+    }
+    list.into()
+  }
+}
@@ -0,0 +1,79 @@
+//! Desktop notifications, throttled so a burst of events (such as every peer
+//! reconnecting after resume) arrives as one summary.
+
+use super::{Message, Window};
+use crate::dbus::send_notification;
+use crate::fl;
+use cosmic::{Action, Task};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Events arriving this soon after the first of a burst are sent together.
+const COALESCE_SECS: u64 = 2;
+/// Minimum gap between notifications; events in between wait for the next one.
+const MIN_INTERVAL_SECS: u64 = 10;
+/// Events listed in a summary before the rest are only counted.
+const SUMMARY_EVENTS_SHOWN: usize = 5;
+
+/// Events waiting to be sent, and when the last notification went out.
+#[derive(Default)]
+pub struct NotificationQueue {
+  pending: Vec<String>,
+  flush_scheduled: bool,
+  last_sent: Option<Instant>,
+}
+
+impl Window {
+  /// Queue a notification for `event`, unless notifications are off or the
+  /// popup is open and already shows it.
+  pub(super) fn notify(&mut self, event: String) -> Task<Action<Message>> {
+    if !self.config.notifications || self.paused() || self.popup.is_some() {
+      return Task::none();
+    }
+
+    let queue = &mut self.notifications;
+    queue.pending.push(event);
+    if queue.flush_scheduled {
+      return Task::none();
+    }
+    queue.flush_scheduled = true;
+
+    let since_last = queue.last_sent.map_or(Duration::MAX, |sent| sent.elapsed());
+    let delay = Duration::from_secs(COALESCE_SECS)
+      .max(Duration::from_secs(MIN_INTERVAL_SECS).saturating_sub(since_last));
+    cosmic::task::future(async move {
+      tokio::time::sleep(delay).await;
+      Message::FlushNotifications
+    })
+  }
+
+  /// Send the queued events: a single one as is, several as a summary.
+  pub(super) fn flush_notifications(&mut self) -> Task<Action<Message>> {
+    let queue = &mut self.notifications;
+    queue.flush_scheduled = false;
+    let events = std::mem::take(&mut queue.pending);
+
+    let (summary, body) = match events.as_slice() {
+      [] => return Task::none(),
+      [event] => (event.clone(), String::new()),
+      _ => {
+        let mut lines: Vec<String> = events.iter().take(SUMMARY_EVENTS_SHOWN).cloned().collect();
+        if events.len() > SUMMARY_EVENTS_SHOWN {
+          lines.push(fl!("notify-more", count = events.len() - SUMMARY_EVENTS_SHOWN));
+        }
+        (fl!("notify-summary", count = events.len()), lines.join("\n"))
+      }
+    };
+
+    let Some(conn) = self.dbus_conn.clone() else {
+      return Task::none();
+    };
+    queue.last_sent = Some(Instant::now());
+    Task::future(async move {
+      if let Err(e) = send_notification(&conn, &summary, &body).await {
+        warn!("Failed to send notification: {e}");
+      }
+    })
+    .discard()
+  }
+}
@@ -0,0 +1,60 @@
+//! Panel icons for each connection state, which users can replace with other
+//! icon names or image files, e.g. for high-contrast themes.
+
+use crate::config::TailscaleConfig;
+use std::path::{Path, PathBuf};
+
+/// Icon shown when nothing is configured for a state.
+const DEFAULT_ICON: &str = "tailscale-icon";
+/// Icon shown while a connection is being brought up.
+const CONNECTING_ICON: &str = "network-vpn-acquiring-symbolic";
+/// Extensions looked for in the icon directory, in order.
+const ICON_EXTENSIONS: [&str; 2] = ["svg", "png"];
+
+/// Where a panel icon comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PanelIcon {
+  /// Looked up in the icon theme.
+  Named(String),
+  File(PathBuf),
+}
+
+/// Panel icon for each connection state, resolved once from the config.
+#[derive(Debug, Clone)]
+pub struct PanelIcons {
+  pub connecting: PanelIcon,
+  pub connected: PanelIcon,
+  pub disconnected: PanelIcon,
+  pub exit_node: PanelIcon,
+}
+
+impl PanelIcons {
+  pub fn from_config(config: &TailscaleConfig) -> Self {
+    let dir = Some(config.icon_dir.trim())
+      .filter(|dir| !dir.is_empty())
+      .map(Path::new);
+    PanelIcons {
+      connecting: PanelIcon::Named(CONNECTING_ICON.to_string()),
+      connected: resolve(&config.icon_connected, dir, "connected"),
+      disconnected: resolve(&config.icon_disconnected, dir, "disconnected"),
+      exit_node: resolve(&config.icon_exit_node, dir, "exit-node"),
+    }
+  }
+}
+
+/// The configured icon if set, a file in `dir` named after the state, or the
+/// default icon. Settings with a `/` are file paths, anything else an icon name.
+fn resolve(setting: &str, dir: Option<&Path>, state: &str) -> PanelIcon {
+  let setting = setting.trim();
+  if setting.contains('/') {
+    return PanelIcon::File(PathBuf::from(setting));
+  }
+  if !setting.is_empty() {
+    return PanelIcon::Named(setting.to_string());
+  }
+  dir
+    .into_iter()
+    .flat_map(|dir| ICON_EXTENSIONS.map(|ext| dir.join(format!("{state}.{ext}"))))
+    .find(|path| path.is_file())
+    .map_or_else(|| PanelIcon::Named(DEFAULT_ICON.to_string()), PanelIcon::File)
+}
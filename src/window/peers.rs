@@ -0,0 +1,1091 @@
+//! Active peers and how recently a handshake with them happened, with a
+//! detail pane for pinging a peer.
+
+use super::{
+  busy_marker, derp_region_label, dropdown_width, format_age, Message, StagingMessage, Window,
+};
+#[cfg(feature = "taildrop")]
+use super::TaildropMessage;
+use crate::config::PeerSort;
+use crate::export::{export_peers, ExportFormat};
+use crate::fl;
+use crate::ids::{NodeId, PeerName};
+#[cfg(feature = "taildrop")]
+use crate::logic::CAP_FILE_SHARING;
+use crate::logic::{
+  launch_for_host, open_url, parse_mac, probe_web_url, run_ping, send_wake_on_lan, BackendState,
+  Peer, PingEvent, PingPath, PingReply, PingStats, PING_COUNT,
+};
+use cosmic::iced::{
+  futures::SinkExt,
+  widget::{column, row, vertical_space},
+  Alignment, Border, Color, Length, Subscription,
+};
+use cosmic::iced_widget::Row;
+use cosmic::widget::{
+  button, container, dropdown, flex_row, icon, search_input, settings, text, text_input, toggler,
+};
+use cosmic::{Action, Element, Task};
+use std::any::TypeId;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use tracing::{error, warn};
+
+/// Handshakes repeat every two minutes while traffic flows, so an online, active
+/// peer without one for longer than this hints at one-way connectivity.
+const STALE_HANDSHAKE_SECS: u64 = 180;
+/// How long a changed peer stays highlighted.
+const HIGHLIGHT_SECS: u64 = 5;
+/// Traffic between two refreshes above this counts as a burst worth highlighting.
+const TRAFFIC_JUMP_BYTES: u64 = 1_000_000;
+/// Ping runs kept per peer for the latency chart.
+const PING_HISTORY_LEN: usize = 12;
+/// Height of the latency chart; the slowest reply in the history fills it.
+const PING_CHART_HEIGHT: f32 = 40.0;
+const PING_BAR_WIDTH: f32 = 8.0;
+const ONLINE_DOT_SIZE: f32 = 8.0;
+
+/// Header a peer is listed under when grouping by owner, in display order.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PeerGroup {
+  Mine,
+  /// Login name of another user.
+  User(String),
+  /// Devices owned by ACL tags rather than a user.
+  Tagged,
+}
+
+impl PeerGroup {
+  fn of(peer: &Peer) -> Self {
+    if !peer.tags.is_empty() {
+      PeerGroup::Tagged
+    } else if peer.mine {
+      PeerGroup::Mine
+    } else {
+      PeerGroup::User(peer.owner.clone())
+    }
+  }
+
+  fn label(&self) -> String {
+    match self {
+      PeerGroup::Mine => fl!("peer-group-mine"),
+      PeerGroup::User(owner) if owner.is_empty() => fl!("peer-group-unknown"),
+      PeerGroup::User(owner) => owner.clone(),
+      PeerGroup::Tagged => fl!("peer-group-tagged"),
+    }
+  }
+}
+
+/// What changed about a peer since the previous refresh.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PeerChange {
+  CameOnline,
+  WentOffline,
+  Traffic,
+}
+
+/// Per-peer state from the previous refresh, and recent changes to highlight.
+///
+/// Keyed by node ID, so a peer renamed between refreshes keeps its history.
+#[derive(Default)]
+pub struct PeerHighlights {
+  /// Online state and total bytes by node; empty before the first refresh.
+  previous: HashMap<NodeId, (bool, u64)>,
+  changed: HashMap<NodeId, (PeerChange, Instant)>,
+}
+
+impl PeerHighlights {
+  /// Recent change to the peer `id`, if it is still highlighted.
+  fn get(&self, id: &NodeId) -> Option<PeerChange> {
+    self
+      .changed
+      .get(id)
+      .filter(|(_, at)| at.elapsed() < Duration::from_secs(HIGHLIGHT_SECS))
+      .map(|(change, _)| *change)
+  }
+}
+
+/// Ping history for peers the user pinged, the web pages peers serve, which
+/// peer's details are open, whether every peer is listed rather than just the
+/// active ones, and the text the list is filtered by.
+#[derive(Default)]
+pub struct PeerPings {
+  open: Option<NodeId>,
+  show_all: bool,
+  search: String,
+  /// Peers being pinged, with the run number that keys their subscription.
+  running: HashMap<NodeId, (PeerName, u64)>,
+  runs: u64,
+  /// Replies from the current or last run, shown as they arrive.
+  replies: HashMap<NodeId, Vec<PingReply>>,
+  history: HashMap<NodeId, VecDeque<PingStats>>,
+  errors: HashMap<NodeId, String>,
+  /// Web page each peer serves, probed when its details are first opened;
+  /// `None` if it serves none.
+  web_urls: HashMap<NodeId, Option<String>>,
+  /// Wake-on-LAN MAC address being edited for the open peer.
+  mac_draft: String,
+  /// Outcome of the last magic packet sent to each peer.
+  woken: HashMap<NodeId, Result<(), String>>,
+  /// Owner groups whose peers are folded away.
+  collapsed_groups: HashSet<PeerGroup>,
+}
+
+/// Messages handled by the peer list.
+#[derive(Clone, Debug)]
+pub enum PeersMessage {
+  ToggleAll,
+  SearchChanged(String),
+  /// Index into `PeerSort::ALL`.
+  SortSelected(usize),
+  HideStale(bool),
+  GroupByOwner(bool),
+  ToggleGroup(PeerGroup),
+  ToggleFavorite(PeerName),
+  UseAsExitNode(PeerName),
+  CopyDnsName(String),
+  /// Open a terminal with an SSH session to the machine name.
+  OpenSsh(String),
+  /// Run the connect action with this name for the machine name.
+  Connect(String, String),
+  WebProbed(NodeId, Option<String>),
+  OpenWebPage(String),
+  MacChanged(String),
+  SaveMac(PeerName),
+  Wake(NodeId, PeerName),
+  Woken(NodeId, Result<(), String>),
+  ToggleDetails(NodeId),
+  Ping(NodeId, PeerName),
+  PingEvent(NodeId, PingEvent),
+  Export(ExportFormat),
+  Exported(Result<PathBuf, String>),
+}
+
+impl PeersMessage {
+  /// Whether handling this message changes Tailscale state.
+  pub fn is_mutation(&self) -> bool {
+    matches!(self, PeersMessage::UseAsExitNode(_))
+  }
+}
+
+impl From<PeersMessage> for Message {
+  fn from(msg: PeersMessage) -> Self {
+    Message::Peers(msg)
+  }
+}
+
+/// Labels for the sort dropdown, in the order of `PeerSort::ALL`.
+static PEER_SORT_LABELS: LazyLock<Vec<String>> = LazyLock::new(|| {
+  PeerSort::ALL
+    .iter()
+    .map(|sort| match sort {
+      PeerSort::Name => fl!("sort-by-name"),
+      PeerSort::Online => fl!("sort-by-online"),
+      PeerSort::LastSeen => fl!("sort-by-last-seen"),
+      PeerSort::Owner => fl!("sort-by-owner"),
+    })
+    .collect()
+});
+
+/// Whether the peer's hostname, owner, one of its IPs, one of its tags or its
+/// note contains `search`, which is lowercase.
+fn peer_matches(peer: &Peer, note: Option<&str>, search: &str) -> bool {
+  peer.name.as_str().to_lowercase().contains(search)
+    || peer.owner.to_lowercase().contains(search)
+    || peer.ips.iter().any(|ip| ip.to_string().contains(search))
+    || peer.tags.iter().any(|tag| tag.to_lowercase().contains(search))
+    || note.is_some_and(|note| note.to_lowercase().contains(search))
+}
+
+/// "Handshake 42 seconds ago" or "Handshake 5 minutes ago".
+fn handshake_age(secs: u64) -> String {
+  if secs < 60 {
+    fl!("handshake-seconds", count = secs)
+  } else {
+    fl!("handshake-ago", age = format_age(secs))
+  }
+}
+
+impl Window {
+  pub(super) fn update_peers(&mut self, message: PeersMessage) -> Task<Action<Message>> {
+    let pings = &mut self.peer_pings;
+
+    match message {
+      PeersMessage::ToggleAll => {
+        pings.show_all = !pings.show_all;
+      }
+      PeersMessage::SearchChanged(search) => {
+        pings.search = search;
+      }
+      PeersMessage::SortSelected(idx) => {
+        if let Some(&sort) = PeerSort::ALL.get(idx)
+          && let Some(ref handler) = self.config_handler
+          && let Err(e) = self.config.set_peer_sort(handler, sort)
+        {
+          self.config_writes.record("peer sort", &e);
+        }
+      }
+      PeersMessage::UseAsExitNode(name) => {
+        // Edit mode stages the choice instead of applying it.
+        return if self.editing() {
+          self.update(StagingMessage::ExitNodeSelected(Some(name)).into())
+        } else {
+          self.update(Message::ExitNodeSelected(Some(name)))
+        };
+      }
+      PeersMessage::HideStale(hide) => {
+        if let Some(ref handler) = self.config_handler
+          && let Err(e) = self.config.set_hide_stale_peers(handler, hide)
+        {
+          self.config_writes.record("hide stale peers", &e);
+        }
+      }
+      PeersMessage::GroupByOwner(group) => {
+        if let Some(ref handler) = self.config_handler
+          && let Err(e) = self.config.set_group_peers_by_owner(handler, group)
+        {
+          self.config_writes.record("peer grouping", &e);
+        }
+      }
+      PeersMessage::ToggleGroup(group) => {
+        if !pings.collapsed_groups.remove(&group) {
+          pings.collapsed_groups.insert(group);
+        }
+      }
+      PeersMessage::CopyDnsName(name) => {
+        return cosmic::iced::clipboard::write(name);
+      }
+      PeersMessage::OpenSsh(host) => {
+        let command = self.config.ssh_command.clone();
+        return Task::future(async move {
+          if let Err(e) = launch_for_host(&command, &host).await {
+            warn!("Failed to open SSH session to {host}: {e}");
+          }
+        })
+        .discard();
+      }
+      PeersMessage::Connect(name, host) => {
+        let Some(action) =
+          self.config.connect_actions.iter().find(|action| action.name == name).cloned()
+        else {
+          return Task::none();
+        };
+        return Task::future(async move {
+          if let Err(e) = launch_for_host(&action.command, &host).await {
+            warn!("Failed to run {} for {host}: {e}", action.name);
+          }
+        })
+        .discard();
+      }
+      PeersMessage::ToggleFavorite(name) => {
+        let mut favorites = self.config.favorite_peers.clone();
+        if !favorites.remove(&name) {
+          favorites.insert(name);
+        }
+        if let Some(ref handler) = self.config_handler
+          && let Err(e) = self.config.set_favorite_peers(handler, favorites)
+        {
+          self.config_writes.record("favorite peers", &e);
+        }
+        #[cfg(feature = "taildrop")]
+        self.sync_taildrop_devices();
+      }
+      PeersMessage::WebProbed(id, url) => {
+        pings.web_urls.insert(id, url);
+      }
+      PeersMessage::OpenWebPage(url) => {
+        return Task::future(async move {
+          if let Err(e) = open_url(&url).await {
+            warn!("Failed to open {url}: {e}");
+          }
+        })
+        .discard();
+      }
+      PeersMessage::MacChanged(mac) => {
+        pings.mac_draft = mac;
+      }
+      PeersMessage::SaveMac(name) => {
+        let mut macs = self.config.peer_macs.clone();
+        let mac = pings.mac_draft.trim();
+        if mac.is_empty() {
+          macs.remove(&name);
+        } else if parse_mac(mac).is_some() {
+          macs.insert(name, mac.to_string());
+        } else {
+          return Task::none();
+        }
+        if let Some(ref handler) = self.config_handler
+          && let Err(e) = self.config.set_peer_macs(handler, macs)
+        {
+          self.config_writes.record("peer MAC addresses", &e);
+        }
+      }
+      PeersMessage::Wake(id, name) => {
+        let Some(mac) = self.config.peer_macs.get(&name).and_then(|mac| parse_mac(mac)) else {
+          return Task::none();
+        };
+        // Machines behind a subnet router this device uses may be reachable too.
+        let routes: Vec<String> = self
+          .subnet_routers
+          .iter()
+          .filter(|router| router.primary)
+          .flat_map(|router| router.routes.iter().cloned())
+          .collect();
+        return cosmic::task::future(async move {
+          let result = send_wake_on_lan(mac, &routes).await.map_err(|e| e.to_string());
+          Message::Peers(PeersMessage::Woken(id, result))
+        });
+      }
+      PeersMessage::Woken(id, result) => {
+        if let Err(e) = &result {
+          warn!("Failed to send Wake-on-LAN packet: {e}");
+        }
+        pings.woken.insert(id, result);
+      }
+      PeersMessage::ToggleDetails(id) => {
+        if pings.open.as_ref() == Some(&id) {
+          pings.open = None;
+          return Task::none();
+        }
+        pings.open = Some(id.clone());
+        pings.mac_draft = self
+          .peers
+          .iter()
+          .find(|peer| peer.id == id)
+          .and_then(|peer| self.config.peer_macs.get(&peer.name))
+          .cloned()
+          .unwrap_or_default();
+        // Probe once per peer; the pane shows the button when a page turns up.
+        if !pings.web_urls.contains_key(&id)
+          && let Some(peer) = self.peers.iter().find(|peer| peer.id == id && peer.online)
+          && let Some(ip) = peer.ips.first().copied()
+        {
+          let dns_name = peer.dns_name.clone();
+          return cosmic::task::future(async move {
+            let url = probe_web_url(ip, &dns_name).await;
+            Message::Peers(PeersMessage::WebProbed(id, url))
+          });
+        }
+      }
+      PeersMessage::Ping(id, name) => {
+        if !pings.running.contains_key(&id) {
+          pings.runs += 1;
+          pings.errors.remove(&id);
+          pings.replies.insert(id.clone(), Vec::new());
+          pings.running.insert(id, (name, pings.runs));
+        }
+      }
+      PeersMessage::PingEvent(id, PingEvent::Reply(reply)) => {
+        pings.replies.entry(id).or_default().push(reply);
+      }
+      PeersMessage::PingEvent(id, PingEvent::Finished(result)) => {
+        pings.running.remove(&id);
+        let replies = pings.replies.get(&id).map_or(&[][..], Vec::as_slice);
+        // A run that got some replies still counts, even if the CLI then failed.
+        if let Some(stats) = PingStats::from_replies(replies, PING_COUNT) {
+          let history = pings.history.entry(id).or_default();
+          if history.len() == PING_HISTORY_LEN {
+            history.pop_front();
+          }
+          history.push_back(stats);
+        } else if let Err(e) = result {
+          pings.errors.insert(id, e);
+        }
+      }
+      PeersMessage::Export(format) => {
+        self.peer_export_status = fl!("export-peers-saving");
+        let peers = self.peers.clone();
+        return cosmic::task::future(async move {
+          let result = export_peers(&peers, format).await.map_err(|e| e.to_string());
+          Message::Peers(PeersMessage::Exported(result))
+        });
+      }
+      PeersMessage::Exported(result) => {
+        self.peer_export_status = match result {
+          Ok(path) => fl!("export-peers-saved", path = path.display().to_string()),
+          Err(e) => {
+            error!("Failed to export peers: {e}");
+            fl!("export-peers-failed", error = e)
+          }
+        };
+      }
+    }
+    Task::none()
+  }
+
+  /// One subscription per running ping, streaming its replies.
+  pub(super) fn ping_subscriptions(&self) -> impl Iterator<Item = Subscription<Message>> + '_ {
+    self
+      .peer_pings
+      .running
+      .iter()
+      .map(|(id, (name, run))| ping_subscription(id.clone(), name.clone(), *run))
+  }
+
+  /// Compare `peers` with the previous refresh and highlight what changed,
+  /// returning a task that clears the highlights once they expire.
+  pub(super) fn track_peer_changes(&mut self, peers: &[Peer]) -> Task<Action<Message>> {
+    let highlights = &mut self.peer_highlights;
+    let first_refresh = highlights.previous.is_empty();
+    let now = Instant::now();
+    let mut any_changed = false;
+    let mut events = Vec::new();
+
+    let current: HashMap<NodeId, (bool, u64)> = peers
+      .iter()
+      .map(|peer| (peer.id.clone(), (peer.online, peer.rx_bytes + peer.tx_bytes)))
+      .collect();
+
+    for peer in peers {
+      let (online, bytes) = (peer.online, peer.rx_bytes + peer.tx_bytes);
+      let Some(&(was_online, prev_bytes)) = highlights.previous.get(&peer.id) else {
+        continue;
+      };
+      let change = if online && !was_online {
+        PeerChange::CameOnline
+      } else if !online && was_online {
+        PeerChange::WentOffline
+      } else if bytes.saturating_sub(prev_bytes) > TRAFFIC_JUMP_BYTES {
+        PeerChange::Traffic
+      } else {
+        continue;
+      };
+      highlights.changed.insert(peer.id.clone(), (change, now));
+      any_changed = true;
+      let name = peer.name.to_string();
+      match change {
+        PeerChange::CameOnline => events.push(fl!("notify-peer-online", peer = name)),
+        PeerChange::WentOffline => events.push(fl!("notify-peer-offline", peer = name)),
+        PeerChange::Traffic => {}
+      }
+    }
+
+    highlights.previous = current;
+    highlights
+      .changed
+      .retain(|_, (_, at)| at.elapsed() < Duration::from_secs(HIGHLIGHT_SECS));
+
+    if first_refresh || !any_changed {
+      return Task::none();
+    }
+    let mut tasks: Vec<_> = events.into_iter().map(|event| self.notify(event)).collect();
+    tasks.push(cosmic::task::future(async {
+      tokio::time::sleep(Duration::from_secs(HIGHLIGHT_SECS)).await;
+      Message::PrunePeerHighlights
+    }));
+    Task::batch(tasks)
+  }
+
+  /// Drop expired highlights so their rows return to normal.
+  pub(super) fn prune_peer_highlights(&mut self) {
+    self
+      .peer_highlights
+      .changed
+      .retain(|_, (_, at)| at.elapsed() < Duration::from_secs(HIGHLIGHT_SECS));
+  }
+
+  /// Devices on the tailnet. Location-based exit nodes such as Mullvad's show
+  /// up as peers too, but aren't devices of the tailnet.
+  pub(super) fn tailnet_peers(&self) -> impl Iterator<Item = &Peer> {
+    self
+      .peers
+      .iter()
+      .filter(|peer| self.located_exit_node(peer.name.as_str()).is_none())
+  }
+
+  /// "42 devices in tailnet (17 online)", counting this device, as a button
+  /// that lists them all; empty until the peers are known.
+  pub(super) fn tailnet_summary_view(&self) -> Element<'_, Message> {
+    if self.peers.is_empty() || self.limited {
+      return Row::new().into();
+    }
+
+    let this_online = usize::from(self.backend_state == BackendState::Running);
+    let (count, online) = self
+      .tailnet_peers()
+      .fold((1, this_online), |(count, online), peer| {
+        (count + 1, online + usize::from(peer.online))
+      });
+    settings::item(
+      fl!("tailnet-devices"),
+      button::text(fl!("tailnet-devices-count", count = count, online = online))
+        .on_press(PeersMessage::ToggleAll.into())
+        .tooltip(fl!("show-all-peers")),
+    )
+    .into()
+  }
+
+  /// Active peers with their handshake freshness, plus peers that just changed,
+  /// or every peer when asked for. A search looks through every peer. Each row
+  /// shows whether the peer is online, its OS and its owner. Empty without peers.
+  pub(super) fn peers_view(&self) -> Element<'_, Message> {
+    if self.peers.is_empty() {
+      return Row::new().into();
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let settable = !self.config.monitor_only && !self.transitioning();
+    let exit_node = self.shown_exit_node();
+    let show_all = self.peer_pings.show_all;
+    let search = self.peer_pings.search.trim().to_lowercase();
+    let stale_days = self.config.stale_peer_days;
+    let stale_before = now.saturating_sub_unsigned(stale_days.saturating_mul(86_400));
+    let stale = |peer: &Peer| {
+      peer.expired || (!peer.online && peer.last_seen.is_some_and(|seen| seen < stale_before))
+    };
+    let hide_stale = self.config.hide_stale_peers;
+    let hidden = if hide_stale {
+      self.tailnet_peers().filter(|peer| stale(peer)).count()
+    } else {
+      0
+    };
+    let mut shown: Vec<_> = self
+      .tailnet_peers()
+      .filter(|peer| !(hide_stale && stale(peer)))
+      .map(|peer| (peer, self.peer_highlights.get(&peer.id)))
+      .filter(|(peer, change)| {
+        if !search.is_empty() {
+          let note = self.config.peer_notes.get(&peer.name).map(String::as_str);
+          return peer_matches(peer, note, &search);
+        }
+        show_all
+          || peer.active
+          || change.is_some()
+          || self.peer_pings.open.as_ref() == Some(&peer.id)
+      })
+      .collect();
+    // Peers come sorted by name, which the stable sorts keep as a tiebreak.
+    match self.config.peer_sort {
+      PeerSort::Name => {}
+      PeerSort::Online => shown.sort_by_key(|(peer, _)| !peer.online),
+      PeerSort::LastSeen => shown.sort_by_key(|(peer, _)| {
+        Reverse(if peer.online { Some(now) } else { peer.last_seen })
+      }),
+      PeerSort::Owner => shown.sort_by(|(a, _), (b, _)| a.owner.cmp(&b.owner)),
+    }
+    let grouped = self.config.group_peers_by_owner;
+    if grouped {
+      shown.sort_by_cached_key(|(peer, _)| PeerGroup::of(peer));
+    }
+
+    // The header stays when no peer is active, so the full list is one click away.
+    let header = if show_all {
+      row!(
+        text(fl!("all-peers")).size(14).width(Length::Fill),
+        button::icon(icon::from_name("window-close-symbolic"))
+          .on_press(PeersMessage::ToggleAll.into())
+          .tooltip(fl!("show-active-peers")),
+      )
+    } else {
+      row!(
+        text(fl!("active-peers")).size(14).width(Length::Fill),
+        button::icon(icon::from_name("view-list-symbolic"))
+          .on_press(PeersMessage::ToggleAll.into())
+          .tooltip(fl!("show-all-peers")),
+      )
+    };
+    let mut list = column!(
+      header.align_y(Alignment::Center),
+      search_input(fl!("search-peers"), &self.peer_pings.search)
+        .on_input(|search| PeersMessage::SearchChanged(search).into())
+        .on_clear(PeersMessage::SearchChanged(String::new()).into())
+        .width(Length::Fill),
+      row!(
+        text(fl!("sort-peers")),
+        dropdown(
+          PEER_SORT_LABELS.as_slice(),
+          PeerSort::ALL.iter().position(|sort| *sort == self.config.peer_sort),
+          |idx| PeersMessage::SortSelected(idx).into(),
+        )
+        .width(dropdown_width(&PEER_SORT_LABELS)),
+      )
+      .align_y(Alignment::Center)
+      .spacing(8),
+      toggler(hide_stale)
+        .label(fl!("hide-stale-peers", days = self.config.stale_peer_days))
+        .on_toggle(|hide| PeersMessage::HideStale(hide).into()),
+      toggler(grouped)
+        .label(fl!("group-peers-by-owner"))
+        .on_toggle(|group| PeersMessage::GroupByOwner(group).into()),
+    )
+    .spacing(5);
+    if hidden > 0 {
+      list = list.push(text(fl!("stale-peers-hidden", count = hidden)).size(12));
+    }
+    let mut current_group = None;
+    for (peer, change) in shown.iter().copied() {
+      if grouped {
+        let group = PeerGroup::of(peer);
+        if current_group.as_ref() != Some(&group) {
+          let count = shown.iter().filter(|(peer, _)| PeerGroup::of(peer) == group).count();
+          list = list.push(self.peer_group_header(&group, count));
+          current_group = Some(group);
+        }
+        if current_group
+          .as_ref()
+          .is_some_and(|group| self.peer_pings.collapsed_groups.contains(group))
+        {
+          continue;
+        }
+      }
+
+      let age = peer
+        .last_handshake
+        .map(|handshake| u64::try_from(now - handshake).unwrap_or_default());
+      let label = age.map_or_else(|| fl!("handshake-never"), handshake_age);
+      let stale = peer.online && age.is_none_or(|secs| secs > STALE_HANDSHAKE_SECS);
+
+      let value: Element<'_, Message> = if let Some(change) = change {
+        let change = match change {
+          PeerChange::CameOnline => fl!("peer-came-online"),
+          PeerChange::WentOffline => fl!("peer-went-offline"),
+          PeerChange::Traffic => fl!("peer-traffic"),
+        };
+        text(format!("{change} · {label}"))
+          .class(cosmic::theme::Text::Accent)
+          .into()
+      } else if stale {
+        row!(
+          icon::from_name("dialog-warning-symbolic").size(16),
+          text(fl!("handshake-stale", handshake = label)),
+        )
+        .align_y(Alignment::Center)
+        .spacing(8)
+        .into()
+      } else {
+        text(label).into()
+      };
+
+      let open = self.peer_pings.open.as_ref() == Some(&peer.id);
+      let favorite = self.config.favorite_peers.contains(&peer.name);
+      let subtitle: Vec<&str> = [peer.owner.as_str(), peer.os.as_str()]
+        .into_iter()
+        .filter(|detail| !detail.is_empty())
+        .collect();
+      let mut name = column!(
+        text(peer.name.to_string()),
+        text(subtitle.join(" · ")).size(12),
+      );
+      if peer.active
+        && let Some(path) = self.connection_path(peer)
+      {
+        name = name.push(text(path).size(12));
+      }
+      if !peer.tags.is_empty() {
+        // The "tag:" prefix is implied on the row; details show it in full.
+        name = name.push(tag_chips(
+          peer.tags.iter().map(|tag| tag.strip_prefix("tag:").unwrap_or(tag)),
+        ));
+      }
+      if let Some(note) = self.config.peer_notes.get(&peer.name) {
+        name = name.push(text(note.clone()).size(12));
+      }
+      // Badges exit nodes, and switches to one that isn't in use.
+      let exit_node_button: Element<'_, Message> = if !peer.exit_node_option {
+        Row::new().into()
+      } else if exit_node == Some(peer.name.as_str()) {
+        button::icon(icon::from_name("network-vpn-symbolic"))
+          .class(cosmic::theme::Button::Suggested)
+          .tooltip(fl!("exit-node-in-use"))
+          .into()
+      } else {
+        button::icon(icon::from_name("network-vpn-symbolic"))
+          .on_press_maybe(settable.then(|| PeersMessage::UseAsExitNode(peer.name.clone()).into()))
+          .tooltip(fl!("use-as-exit-node"))
+          .into()
+      };
+      list = list.push(
+        row!(
+          online_dot(peer.online),
+          icon::from_name(os_icon(&peer.os)).size(16),
+          name.spacing(2).width(Length::Fill),
+          value,
+          exit_node_button,
+          button::icon(icon::from_name(if favorite {
+            "starred-symbolic"
+          } else {
+            "non-starred-symbolic"
+          }))
+          .on_press(PeersMessage::ToggleFavorite(peer.name.clone()).into())
+          .tooltip(if favorite {
+            fl!("unstar-peer")
+          } else {
+            fl!("star-peer")
+          }),
+          button::icon(icon::from_name(if open {
+            "go-up-symbolic"
+          } else {
+            "go-down-symbolic"
+          }))
+          .on_press(PeersMessage::ToggleDetails(peer.id.clone()).into())
+          .tooltip(fl!("peer-details")),
+        )
+        .align_y(Alignment::Center)
+        .spacing(8),
+      );
+      if open {
+        list = list.push(self.peer_details_view(peer));
+      }
+    }
+
+    list.into()
+  }
+
+  /// "Direct via 203.0.113.5:41641" or "Relayed via DERP fra (Frankfurt)",
+  /// as `tailscale status` shows it; `None` if neither is known.
+  fn connection_path(&self, peer: &Peer) -> Option<String> {
+    if !peer.cur_addr.is_empty() {
+      Some(fl!("path-direct", endpoint = peer.cur_addr.clone()))
+    } else if !peer.relay.is_empty() {
+      let region = derp_region_label(self.derp_region_by_code(&peer.relay), &peer.relay);
+      Some(fl!("path-relayed", region = region))
+    } else {
+      None
+    }
+  }
+
+  /// Owner group name with its peer count, folding the group away on click.
+  fn peer_group_header(&self, group: &PeerGroup, count: usize) -> Element<'_, Message> {
+    let collapsed = self.peer_pings.collapsed_groups.contains(group);
+    button::custom(
+      row!(
+        icon::from_name(if collapsed {
+          "go-next-symbolic"
+        } else {
+          "go-down-symbolic"
+        })
+        .size(16),
+        text(fl!("peer-group-header", group = group.label(), count = count)).size(14),
+      )
+      .align_y(Alignment::Center)
+      .spacing(8),
+    )
+    .class(cosmic::theme::Button::Text)
+    .on_press(PeersMessage::ToggleGroup(group.clone()).into())
+    .width(Length::Fill)
+    .into()
+  }
+
+  /// MAC address field for Wake-on-LAN, with a Wake button while the peer is
+  /// offline and the outcome of the last magic packet sent to it.
+  fn wake_on_lan_view(&self, peer: &Peer) -> Element<'_, Message> {
+    let pings = &self.peer_pings;
+    let draft = pings.mac_draft.trim();
+    let valid = draft.is_empty() || parse_mac(draft).is_some();
+    let saved = self.config.peer_macs.get(&peer.name).map_or("", String::as_str);
+
+    let mut field = row!(
+      text_input(fl!("mac-address-placeholder"), &pings.mac_draft)
+        .on_input(|mac| PeersMessage::MacChanged(mac).into())
+        .width(Length::Fill),
+      button::standard(fl!("save-mac-address")).on_press_maybe(
+        (valid && draft != saved).then(|| PeersMessage::SaveMac(peer.name.clone()).into())
+      ),
+    )
+    .align_y(Alignment::Center)
+    .spacing(8);
+    if !peer.online {
+      let wake = PeersMessage::Wake(peer.id.clone(), peer.name.clone());
+      field = field.push(
+        button::standard(fl!("wake-peer"))
+          .on_press_maybe(parse_mac(saved).is_some().then(|| wake.into())),
+      );
+    }
+
+    let status = if !valid {
+      Some(fl!("mac-address-invalid"))
+    } else {
+      match pings.woken.get(&peer.id) {
+        Some(Ok(())) => Some(fl!("wake-sent")),
+        Some(Err(e)) => Some(fl!("wake-failed", error = e.clone())),
+        None => None,
+      }
+    };
+    let mut content = column!(text(fl!("wake-on-lan")).size(14), field).spacing(5);
+    if let Some(status) = status {
+      content = content.push(text(status).size(12));
+    }
+    content.into()
+  }
+
+  /// Starred peers with their online state, each opening its details in the
+  /// peer list; empty when none of them are on the tailnet.
+  pub(super) fn favorites_view(&self) -> Element<'_, Message> {
+    let favorites: Vec<Element<'_, Message>> = self
+      .peers
+      .iter()
+      .filter(|peer| self.config.favorite_peers.contains(&peer.name))
+      .map(|peer| {
+        button::custom(
+          row!(online_dot(peer.online), text(peer.name.to_string()))
+            .align_y(Alignment::Center)
+            .spacing(5),
+        )
+        .class(cosmic::theme::Button::Text)
+        .on_press(PeersMessage::ToggleDetails(peer.id.clone()).into())
+        .into()
+      })
+      .collect();
+    if favorites.is_empty() {
+      return Row::new().into();
+    }
+
+    column!(
+      text(fl!("favorite-peers")).size(14),
+      flex_row(favorites).column_spacing(5).row_spacing(5),
+    )
+    .spacing(5)
+    .into()
+  }
+
+  /// Buttons that save the peer list as CSV or JSON; empty without peers.
+  pub(super) fn peer_export_view(&self) -> Element<'_, Message> {
+    if self.peers.is_empty() {
+      return Row::new().into();
+    }
+
+    column!(
+      settings::item(
+        fl!("export-peers"),
+        row!(
+          button::standard("CSV").on_press(PeersMessage::Export(ExportFormat::Csv).into()),
+          button::standard("JSON").on_press(PeersMessage::Export(ExportFormat::Json).into()),
+        )
+        .spacing(8),
+      ),
+      text(self.peer_export_status.clone()),
+    )
+    .spacing(5)
+    .into()
+  }
+
+  /// Quick actions for the peer (ping, copying its IPv4 address or machine
+  /// name, an SSH session, configured connect actions, its web page and
+  /// sending it files), its ACL tags, its Wake-on-LAN address with a button
+  /// to wake it while offline, then each reply of the current ping run and
+  /// whether it went direct, the latest round-trip stats, and a chart of
+  /// recent runs.
+  fn peer_details_view(&self, peer: &Peer) -> Element<'_, Message> {
+    let pings = &self.peer_pings;
+    let running = pings.running.contains_key(&peer.id);
+    let history = pings.history.get(&peer.id);
+
+    let mut actions: Vec<Element<'_, Message>> = vec![
+      row!(
+        button::standard(fl!("ping-peer")).on_press_maybe(
+          (!running).then(|| PeersMessage::Ping(peer.id.clone(), peer.name.clone()).into())
+        ),
+        busy_marker(running),
+      )
+      .align_y(Alignment::Center)
+      .spacing(8)
+      .into(),
+    ];
+    if let Some(ipv4) = peer.ips.iter().find(|ip| ip.is_ipv4()) {
+      let label = if self.copied_address == Some(*ipv4) {
+        fl!("copied")
+      } else {
+        fl!("copy-ipv4")
+      };
+      actions.push(button::standard(label).on_press(Message::CopyAddress(*ipv4)).into());
+    }
+    if !peer.dns_name.is_empty() {
+      actions.push(
+        button::standard(fl!("copy-machine-name"))
+          .on_press(PeersMessage::CopyDnsName(peer.dns_name.clone()).into())
+          .into(),
+      );
+    }
+    if peer.ssh && !peer.dns_name.is_empty() {
+      actions.push(
+        button::standard(fl!("open-ssh-session"))
+          .on_press(PeersMessage::OpenSsh(peer.dns_name.clone()).into())
+          .into(),
+      );
+    }
+    if !peer.dns_name.is_empty() {
+      for action in &self.config.connect_actions {
+        if action.peers.is_empty() || action.peers.contains(&peer.name) {
+          let connect = PeersMessage::Connect(action.name.clone(), peer.dns_name.clone());
+          actions.push(
+            button::standard(fl!("connect-action", action = action.name.clone()))
+              .on_press(connect.into())
+              .into(),
+          );
+        }
+      }
+    }
+    if let Some(Some(url)) = pings.web_urls.get(&peer.id) {
+      actions.push(
+        button::standard(fl!("open-in-browser"))
+          .on_press(PeersMessage::OpenWebPage(url.clone()).into())
+          .into(),
+      );
+    }
+    #[cfg(feature = "taildrop")]
+    if self.tailnet_allows(CAP_FILE_SHARING) {
+      actions.push(
+        button::standard(fl!("send-files-to-peer"))
+          .on_press(TaildropMessage::SendTo(peer.name.clone()).into())
+          .into(),
+      );
+    }
+
+    let mut details = column!(flex_row(actions).column_spacing(8).row_spacing(5))
+      .spacing(5)
+      .padding([0, 10]);
+    if !peer.tags.is_empty() {
+      details = details.push(
+        row!(text(fl!("peer-tags")), tag_chips(peer.tags.iter().map(String::as_str)))
+          .align_y(Alignment::Center)
+          .spacing(8),
+      );
+    }
+    details = details.push(self.wake_on_lan_view(peer));
+
+    for reply in pings.replies.get(&peer.id).into_iter().flatten() {
+      let latency = reply.latency.as_millis().to_string();
+      details = details.push(
+        text(match &reply.path {
+          PingPath::Direct(endpoint) => {
+            fl!("ping-reply-direct", ms = latency, endpoint = endpoint.clone())
+          }
+          PingPath::Relayed(region) => fl!(
+            "ping-reply-relayed",
+            ms = latency,
+            region = derp_region_label(self.derp_region_by_code(region), region)
+          ),
+        })
+        .size(12),
+      );
+    }
+    if let Some(error) = pings.errors.get(&peer.id) {
+      details = details.push(text(fl!("ping-failed", error = error.clone())).size(12));
+    }
+    if let Some(latest) = history.and_then(VecDeque::back) {
+      details = details
+        .push(text(fl!(
+          "ping-stats",
+          min = latest.min.as_millis().to_string(),
+          avg = latest.avg.as_millis().to_string(),
+          max = latest.max.as_millis().to_string(),
+          replies = latest.replies,
+          sent = latest.sent
+        ))
+        .size(12))
+        .push(ping_chart(history.into_iter().flatten()));
+    }
+    details.into()
+  }
+}
+
+fn ping_subscription(id: NodeId, peer: PeerName, run: u64) -> Subscription<Message> {
+  Subscription::run_with_id(
+    (TypeId::of::<PingEvent>(), run),
+    cosmic::iced::stream::channel(4, |mut output| async move {
+      let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+      let forward = async move {
+        while let Some(event) = rx.recv().await {
+          if output
+            .send(Message::Peers(PeersMessage::PingEvent(id.clone(), event)))
+            .await
+            .is_err()
+          {
+            break;
+          }
+        }
+      };
+      cosmic::iced::futures::future::join(run_ping(peer, tx), forward).await;
+    }),
+  )
+}
+
+/// Green dot for an online peer, grey for an offline one.
+fn online_dot(online: bool) -> Element<'static, Message> {
+  container(Row::new())
+    .width(ONLINE_DOT_SIZE)
+    .height(ONLINE_DOT_SIZE)
+    .class(cosmic::theme::Container::custom(move |theme| {
+      let color = if online {
+        Color::from(theme.cosmic().success_color())
+      } else {
+        let mut color = Color::from(theme.cosmic().on_bg_color());
+        color.a = 0.3;
+        color
+      };
+      cosmic::iced_widget::container::Style {
+        background: Some(color.into()),
+        border: Border {
+          radius: (ONLINE_DOT_SIZE / 2.0).into(),
+          ..Default::default()
+        },
+        ..Default::default()
+      }
+    }))
+    .into()
+}
+
+/// ACL tags as small chips, wrapping onto more lines when they don't fit.
+fn tag_chips<'a>(tags: impl Iterator<Item = &'a str>) -> Element<'static, Message> {
+  let chips: Vec<Element<'static, Message>> = tags
+    .map(|tag| {
+      container(text(tag.to_string()).size(11))
+        .padding([1, 6])
+        .class(cosmic::theme::Container::Card)
+        .into()
+    })
+    .collect();
+  flex_row(chips).column_spacing(4).row_spacing(4).into()
+}
+
+/// Symbolic icon for the kind of device an OS runs on, as reported by
+/// Tailscale ("linux", "windows", "macOS", "iOS", "android", ...).
+fn os_icon(os: &str) -> &'static str {
+  match os.to_ascii_lowercase().as_str() {
+    "ios" | "android" => "phone-symbolic",
+    "tvos" => "video-display-symbolic",
+    "" => "network-wired-symbolic",
+    _ => "computer-symbolic",
+  }
+}
+
+/// One bar per ping run, oldest first: the solid part reaches the fastest
+/// reply, the middle part the average, and the faint top the slowest.
+fn ping_chart<'a>(history: impl Iterator<Item = &'a PingStats> + Clone) -> Element<'a, Message> {
+  let slowest = history.clone().map(|stats| stats.max).max().unwrap_or_default();
+  let scale = |latency: Duration| {
+    if slowest.is_zero() {
+      0.0
+    } else {
+      PING_CHART_HEIGHT * (latency.as_secs_f32() / slowest.as_secs_f32())
+    }
+  };
+  let segment = |height: f32, alpha: f32| {
+    container(Row::new())
+      .width(PING_BAR_WIDTH)
+      .height(height)
+      .class(cosmic::theme::Container::custom(move |theme| {
+        let mut color = Color::from(theme.cosmic().accent_color());
+        color.a = alpha;
+        cosmic::iced_widget::container::Style {
+          background: Some(color.into()),
+          ..Default::default()
+        }
+      }))
+  };
+
+  let bars = history.map(|stats| -> Element<'a, Message> {
+    column!(
+      vertical_space().height(Length::Fill),
+      segment(scale(stats.max) - scale(stats.avg), 0.3),
+      segment(scale(stats.avg) - scale(stats.min), 0.6),
+      segment(scale(stats.min), 1.0),
+    )
+    .height(PING_CHART_HEIGHT)
+    .into()
+  });
+  Row::with_children(bars).spacing(2).into()
+}
@@ -0,0 +1,117 @@
+//! Collapsible debug view of the raw `tailscale status --json` output.
+
+use super::{Message, Window};
+use crate::fl;
+use crate::logic::fetch_raw_status;
+use cosmic::iced::{
+  widget::{column, row},
+  Length,
+};
+use cosmic::widget::{button, icon, scrollable, settings, text};
+use cosmic::{Action, Element, Task};
+use tracing::warn;
+
+const RAW_STATUS_HEIGHT: f32 = 300.0;
+
+/// Raw status view state; the JSON is only fetched while the view is open.
+#[derive(Default)]
+pub struct RawStatusState {
+  open: bool,
+  /// `None` while loading.
+  status: Option<Result<String, String>>,
+}
+
+/// Messages handled by the raw status view.
+#[derive(Clone, Debug)]
+pub enum RawStatusMessage {
+  Toggle,
+  Refresh,
+  Loaded(Result<String, String>),
+  Copy,
+}
+
+impl From<RawStatusMessage> for Message {
+  fn from(msg: RawStatusMessage) -> Self {
+    Message::RawStatus(msg)
+  }
+}
+
+impl Window {
+  pub(super) fn update_raw_status(&mut self, message: RawStatusMessage) -> Task<Action<Message>> {
+    let state = &mut self.raw_status;
+
+    match message {
+      RawStatusMessage::Toggle => {
+        state.open = !state.open;
+        if state.open {
+          return self.update_raw_status(RawStatusMessage::Refresh);
+        }
+      }
+      RawStatusMessage::Refresh => {
+        state.status = None;
+        return cosmic::task::future(async {
+          let result = fetch_raw_status().await.map_err(|e| e.to_string());
+          Message::RawStatus(RawStatusMessage::Loaded(result))
+        });
+      }
+      RawStatusMessage::Loaded(result) => {
+        if let Err(e) = &result {
+          warn!("Failed to fetch raw status: {e}");
+        }
+        state.status = Some(result);
+      }
+      RawStatusMessage::Copy => {
+        if let Some(Ok(status)) = &state.status {
+          return cosmic::iced::clipboard::write(status.clone());
+        }
+      }
+    }
+    Task::none()
+  }
+
+  /// "Raw status" row, with the pretty-printed JSON below it when open.
+  pub(super) fn raw_status_view(&self) -> Element<'_, Message> {
+    let state = &self.raw_status;
+
+    let header = settings::item(
+      fl!("raw-status"),
+      button::standard(fl!("raw-status-show"))
+        .trailing_icon(if state.open {
+          icon::from_name("go-up-symbolic")
+        } else {
+          icon::from_name("go-down-symbolic")
+        })
+        .on_press(RawStatusMessage::Toggle.into()),
+    );
+
+    if !state.open {
+      return column!(header).into();
+    }
+
+    let body: Element<'_, Message> = match &state.status {
+      None => text(fl!("loading")).into(),
+      Some(Err(e)) => text(fl!("last-error", error = e.clone())).into(),
+      Some(Ok(status)) => scrollable(text(status.as_str()).size(11).font(cosmic::font::mono()))
+        .height(Length::Fixed(RAW_STATUS_HEIGHT))
+        .width(Length::Fill)
+        .into(),
+    };
+
+    let copyable = matches!(state.status, Some(Ok(_)));
+    column!(
+      header,
+      row!(
+        button::standard(fl!("raw-status-refresh"))
+          .leading_icon(icon::from_name("view-refresh-symbolic"))
+          .on_press_maybe(state.status.is_some().then(|| RawStatusMessage::Refresh.into())),
+        button::standard(fl!("raw-status-copy"))
+          .leading_icon(icon::from_name("edit-copy-symbolic"))
+          .on_press_maybe(copyable.then(|| RawStatusMessage::Copy.into())),
+      )
+      .spacing(5),
+      body,
+    )
+    .spacing(5)
+    .into()
+  }
+}
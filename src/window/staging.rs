@@ -0,0 +1,217 @@
+//! Edit mode: preference changes are staged and applied with one `tailscale set`.
+
+use super::{ChangeSource, Message, Window};
+use crate::fl;
+use crate::ids::PeerName;
+use crate::logic::{apply_pref_changes, PrefChanges};
+use cosmic::iced::{widget::row, Alignment};
+use cosmic::widget::{button, settings};
+use cosmic::{Action, Element, Task};
+
+/// Staged preference changes while edit mode is on.
+#[derive(Default)]
+pub struct StagingState {
+  editing: bool,
+  changes: PrefChanges,
+  applying: bool,
+}
+
+impl StagingState {
+  /// Whether staged changes are being applied.
+  pub fn applying(&self) -> bool {
+    self.applying
+  }
+}
+
+/// Messages handled by edit mode.
+#[derive(Clone, Debug)]
+pub enum StagingMessage {
+  Edit,
+  /// Exit node picked from the dropdown; separate from `Message::ExitNodeSelected`,
+  /// which quick switches and D-Bus also use to apply immediately.
+  ExitNodeSelected(Option<PeerName>),
+  Apply,
+  Applied(PrefChanges, Result<(), String>),
+  Discard,
+}
+
+impl StagingMessage {
+  /// Whether handling this message changes Tailscale state.
+  pub fn is_mutation(&self) -> bool {
+    matches!(self, StagingMessage::Apply)
+  }
+}
+
+impl From<StagingMessage> for Message {
+  fn from(msg: StagingMessage) -> Self {
+    Message::Staging(msg)
+  }
+}
+
+/// `Some(value)` if it differs from `current`, so staging a value back clears it.
+fn stage<T: PartialEq>(value: T, current: &T) -> Option<T> {
+  (value != *current).then_some(value)
+}
+
+impl Window {
+  pub(super) fn update_staging(&mut self, message: StagingMessage) -> Task<Action<Message>> {
+    match message {
+      StagingMessage::Edit => {
+        self.staging.editing = true;
+      }
+      StagingMessage::ExitNodeSelected(node) => {
+        if !self.shown_is_exit_node()
+          && node.as_ref().is_none_or(|node| self.exit_node_available(node.as_str()))
+        {
+          self.stage_exit_node(node.map(|node| node.to_string()).unwrap_or_default());
+        }
+      }
+      StagingMessage::Discard => {
+        self.staging = StagingState::default();
+      }
+      StagingMessage::Apply => {
+        if self.staging.changes.is_empty() {
+          self.staging = StagingState::default();
+          return Task::none();
+        }
+
+        self.staging.applying = true;
+        let changes = self.staging.changes.clone();
+        return cosmic::task::future(async move {
+          let result = apply_pref_changes(&changes).await.map_err(|e| e.to_string());
+          Message::Staging(StagingMessage::Applied(changes, result))
+        });
+      }
+      StagingMessage::Applied(changes, result) => {
+        self.staging.applying = false;
+        if !self.settings_result(result, "staged settings") {
+          return Task::none();
+        }
+
+        self.commit_changes(&changes);
+        self.staging = StagingState::default();
+        return cosmic::task::future(async { Message::RefreshState });
+      }
+    }
+    Task::none()
+  }
+
+  /// Show applied changes right away instead of waiting for the refresh.
+  fn commit_changes(&mut self, changes: &PrefChanges) {
+    if let Some(ssh) = changes.ssh {
+      self.ssh = ssh;
+      self.pref_sources.ssh = ChangeSource::Applet;
+    }
+    if let Some(routes) = changes.accept_routes {
+      self.routes = routes;
+      self.pref_sources.routes = ChangeSource::Applet;
+    }
+    if let Some(is_exit_node) = changes.advertise_exit_node {
+      self.is_exit_node = is_exit_node;
+    }
+    if let Some(exit_node) = &changes.exit_node {
+      self.sync_exit_node(exit_node);
+      self.pref_sources.exit_node = ChangeSource::Applet;
+      if let Some(ref handler) = self.config_handler {
+        if let Err(e) = self.config.set_exit_node(handler, exit_node.as_str().into()) {
+          self.config_writes.record("exit node config", &e);
+        }
+        if !exit_node.is_empty()
+          && exit_node.as_str() != self.config.last_exit_node.as_str()
+          && let Err(e) = self.config.set_last_exit_node(handler, exit_node.as_str().into())
+        {
+          self.config_writes.record("last exit node", &e);
+        }
+      }
+    }
+    // After the exit node, so the setting is remembered for the new node.
+    if let Some(allow_lan) = changes.exit_node_allow_lan_access {
+      self.allow_lan = allow_lan;
+      self.save_allow_lan(allow_lan);
+    }
+  }
+
+  /// Whether preference changes are being staged instead of applied.
+  pub(super) fn editing(&self) -> bool {
+    self.staging.editing
+  }
+
+  pub(super) fn stage_ssh(&mut self, ssh: bool) {
+    self.staging.changes.ssh = stage(ssh, &self.ssh);
+  }
+
+  pub(super) fn stage_routes(&mut self, routes: bool) {
+    self.staging.changes.accept_routes = stage(routes, &self.routes);
+  }
+
+  fn stage_exit_node(&mut self, exit_node: String) {
+    if let Some(&allow_lan) = self.config.exit_node_lan_access.get(exit_node.as_str()) {
+      self.stage_allow_lan(allow_lan);
+    }
+    self.staging.changes.exit_node = stage(exit_node, &self.current_exit_node());
+  }
+
+  pub(super) fn stage_is_exit_node(&mut self, is_exit_node: bool) {
+    self.staging.changes.advertise_exit_node = stage(is_exit_node, &self.is_exit_node);
+  }
+
+  pub(super) fn stage_allow_lan(&mut self, allow_lan: bool) {
+    self.staging.changes.exit_node_allow_lan_access = stage(allow_lan, &self.allow_lan);
+  }
+
+  /// SSH as shown in the popup, including a staged change.
+  pub(super) fn shown_ssh(&self) -> bool {
+    self.staging.changes.ssh.unwrap_or(self.ssh)
+  }
+
+  pub(super) fn shown_routes(&self) -> bool {
+    self.staging.changes.accept_routes.unwrap_or(self.routes)
+  }
+
+  pub(super) fn shown_is_exit_node(&self) -> bool {
+    self.staging.changes.advertise_exit_node.unwrap_or(self.is_exit_node)
+  }
+
+  pub(super) fn shown_allow_lan(&self) -> bool {
+    self
+      .staging
+      .changes
+      .exit_node_allow_lan_access
+      .unwrap_or(self.allow_lan)
+  }
+
+  /// Exit node in use, including a staged change; `None` for none.
+  pub(super) fn shown_exit_node(&self) -> Option<&str> {
+    match &self.staging.changes.exit_node {
+      Some(name) => (!name.is_empty()).then_some(name.as_str()),
+      None => self.sel_exit_node.as_ref().map(PeerName::as_str),
+    }
+  }
+
+  /// Edit/Apply/Discard controls at the top of the settings section.
+  pub(super) fn staging_view(&self, mutable: bool) -> Element<'_, Message> {
+    if !self.staging.editing {
+      return settings::item(
+        fl!("edit-settings-hint"),
+        button::standard(fl!("edit-settings"))
+          .on_press_maybe(mutable.then_some(StagingMessage::Edit.into())),
+      )
+      .into();
+    }
+
+    let pending = self.staging.changes.len();
+    let can_apply = mutable && pending > 0 && !self.staging.applying;
+    settings::item(
+      fl!("staged-changes", count = pending),
+      row!(
+        button::standard(fl!("discard-changes"))
+          .on_press_maybe((!self.staging.applying).then_some(StagingMessage::Discard.into())),
+        button::suggested(fl!("apply-changes"))
+          .on_press_maybe(can_apply.then_some(StagingMessage::Apply.into())),
+      )
+      .align_y(Alignment::Center)
+      .spacing(5),
+    )
+    .into()
+  }
+}
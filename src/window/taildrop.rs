@@ -0,0 +1,868 @@
+//! Taildrop file transfers, compiled with the `taildrop` feature.
+
+use super::{dropdown_width, format_age, is_not_enabled_error, section_error, Message, Window};
+use crate::checksum::{sha256_files, FileChecksum};
+use crate::fl;
+use crate::history::{
+  append_record, clear_history, format_size, load_history, unix_secs, TransferDirection,
+  TransferRecord,
+};
+use crate::ids::PeerName;
+use crate::logic::{
+  clear_status, receive_dir, tailscale_receive, tailscale_send, waiting_file_count, Peer,
+  ReceiveReport, SendReport, CAP_FILE_SHARING,
+};
+use cosmic::dialog::file_chooser::{self, FileFilter};
+use cosmic::iced::{
+  alignment::Horizontal,
+  widget::{column, horizontal_space, row},
+  Alignment, Length, Subscription,
+};
+use cosmic::iced_widget::Row;
+use cosmic::widget::{button, dropdown, icon, scrollable, settings, text, text_input, toggler};
+use cosmic::{Action, Element, Task};
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::{error, warn};
+use url::Url;
+
+/// Label of the dropdown entry before the devices, meaning none is selected.
+const DEFAULT_DEVICE: &str = "Select";
+const STATUS_CLEAR_TIME: u64 = 5;
+const SOUND_TRANSFER_COMPLETE: &str = "complete";
+const HISTORY_HEIGHT: f32 = 240.0;
+/// How often the daemon's inbox is checked for files waiting to be received.
+const INBOX_POLL_SECS: u64 = 10;
+/// File names listed per history entry before the rest are summarized.
+const HISTORY_FILES_SHOWN: usize = 3;
+
+/// Taildrop section state.
+pub struct TaildropState {
+  /// Every device from the last refresh; `device_options` may be filtered.
+  all_devices: Vec<PeerName>,
+  /// Only list this user's own online devices, for sending to oneself.
+  own_devices_only: bool,
+  device_options: Vec<PeerName>,
+  /// `DEFAULT_DEVICE`, then a label for each of `device_options`.
+  device_labels: Vec<String>,
+  peer_note_draft: String,
+  selected_device: Option<PeerName>,
+  send_files: Vec<PathBuf>,
+  /// Large batch waiting for the user to confirm the send.
+  send_confirm: Option<PendingSend>,
+  send_file_status: String,
+  files_sent: bool,
+  receive_file_status: String,
+  sending_files: bool,
+  receiving_files: bool,
+  /// Received files waiting in the daemon's inbox, from the last inbox check.
+  waiting_files: usize,
+  /// A transfer finished while the popup was closed, and whether it succeeded;
+  /// the panel icon shows it until the popup is opened.
+  unseen_result: Option<bool>,
+  /// SHA-256 of the files from the last receive, `None` while computing.
+  checksums: Option<Vec<FileChecksum>>,
+  show_history: bool,
+  /// Loaded from disk the first time the History tab is opened.
+  history: Option<Vec<TransferRecord>>,
+}
+
+impl Default for TaildropState {
+  fn default() -> Self {
+    TaildropState {
+      all_devices: Vec::new(),
+      own_devices_only: false,
+      device_options: Vec::new(),
+      device_labels: vec![DEFAULT_DEVICE.to_string()],
+      peer_note_draft: String::new(),
+      selected_device: None,
+      send_files: Vec::new(),
+      send_confirm: None,
+      send_file_status: String::new(),
+      files_sent: false,
+      receive_file_status: String::new(),
+      sending_files: false,
+      receiving_files: false,
+      waiting_files: 0,
+      unseen_result: None,
+      checksums: Some(Vec::new()),
+      show_history: false,
+      history: None,
+    }
+  }
+}
+
+/// Summary of a batch over the confirmation threshold.
+#[derive(Clone, Copy)]
+struct PendingSend {
+  files: usize,
+  bytes: u64,
+}
+
+impl TaildropState {
+  /// Whether a send or receive is still running.
+  pub fn transfer_in_progress(&self) -> bool {
+    self.sending_files || self.receiving_files
+  }
+
+  /// Badge for the panel icon: activity while transferring, then the outcome
+  /// of a transfer that finished while the popup was closed.
+  pub fn panel_badge(&self) -> Option<&'static str> {
+    if self.transfer_in_progress() {
+      return Some("emblem-synchronizing-symbolic");
+    }
+    self.unseen_result.map(|succeeded| {
+      if succeeded {
+        "emblem-ok-symbolic"
+      } else {
+        "dialog-error-symbolic"
+      }
+    })
+  }
+
+  /// The popup was opened, so finished transfers no longer need a badge.
+  pub fn mark_results_seen(&mut self) {
+    self.unseen_result = None;
+  }
+
+  /// Forget the last receive result, e.g. when the popup is closed.
+  pub fn clear_receive_status(&mut self) {
+    self.receive_file_status = String::new();
+  }
+
+  /// Dropdown entry of the selected device, `DEFAULT_DEVICE` if none is.
+  fn selected_label_idx(&self) -> usize {
+    self
+      .selected_device
+      .as_ref()
+      .and_then(|dev| self.device_options.iter().position(|option| option == dev))
+      .map_or(0, |idx| idx + 1)
+  }
+}
+
+/// Messages handled by the Taildrop section.
+#[derive(Clone, Debug)]
+pub enum TaildropMessage {
+  /// `None` clears the selection.
+  DeviceSelected(Option<PeerName>),
+  /// Select a peer as the target and choose files to send it, e.g. from its
+  /// row in the peer list.
+  SendTo(PeerName),
+  OwnDevicesOnly(bool),
+  ChooseFiles,
+  FilesSelected(Vec<Url>),
+  SendFiles,
+  /// Total size of the selected files, to decide whether to confirm first.
+  SendSizeChecked(u64),
+  ConfirmSend,
+  CancelSend,
+  FilesSent(SendReport),
+  FileChoosingCancelled,
+  FileChoosingFailed(String),
+  ReceiveFiles,
+  FilesReceived(ReceiveReport),
+  CheckInbox,
+  InboxChecked(usize),
+  ClearSendStatus,
+  ClearReceiveStatus,
+  PeerNoteChanged(String),
+  SavePeerNote,
+  TogglePinDuringTransfers(bool),
+  ChecksumsComputed(Vec<FileChecksum>),
+  CopyChecksum(String),
+  ShowHistory(bool),
+  HistoryLoaded(Result<Vec<TransferRecord>, String>),
+  ClearHistory,
+}
+
+impl TaildropMessage {
+  /// Whether handling this message changes Tailscale state.
+  pub fn is_mutation(&self) -> bool {
+    matches!(
+      self,
+      TaildropMessage::SendFiles
+        | TaildropMessage::SendSizeChecked(_)
+        | TaildropMessage::ConfirmSend
+        | TaildropMessage::ReceiveFiles
+    )
+  }
+}
+
+impl From<TaildropMessage> for Message {
+  fn from(msg: TaildropMessage) -> Self {
+    Message::Taildrop(msg)
+  }
+}
+
+impl Window {
+  /// Rebuild the device list from the refreshed peers, starred ones first.
+  pub(super) fn sync_taildrop_devices(&mut self) {
+    let (favorites, others): (Vec<&Peer>, Vec<&Peer>) = self
+      .tailnet_peers()
+      .partition(|peer| self.config.favorite_peers.contains(&peer.name));
+    self.taildrop.all_devices = favorites
+      .into_iter()
+      .chain(others)
+      .map(|peer| peer.name.clone())
+      .collect();
+    self.refresh_device_options();
+  }
+
+  /// Rebuild the dropdown from `all_devices`, keeping only this user's online
+  /// devices when that filter is on.
+  fn refresh_device_options(&mut self) {
+    let state = &mut self.taildrop;
+    state.device_options = if state.own_devices_only {
+      let own = |dev: &PeerName| {
+        self
+          .peers
+          .iter()
+          .any(|peer| peer.mine && peer.online && peer.name == *dev)
+      };
+      state.all_devices.iter().filter(|dev| own(dev)).cloned().collect()
+    } else {
+      state.all_devices.clone()
+    };
+    self.refresh_device_labels();
+  }
+
+  /// Rebuild dropdown labels so peers with a note show it next to the hostname.
+  fn refresh_device_labels(&mut self) {
+    let labels = self
+      .taildrop
+      .device_options
+      .iter()
+      .map(|dev| match self.config.peer_notes.get(dev) {
+        Some(note) => format!("{dev} ({note})"),
+        None => dev.to_string(),
+      });
+    self.taildrop.device_labels = std::iter::once(DEFAULT_DEVICE.to_string())
+      .chain(labels)
+      .collect();
+  }
+
+  /// Start sending `files` to the device named `target`, e.g. on request over
+  /// D-Bus. Known peers are addressed by machine name or IP rather than by
+  /// their display name; anything else is passed to the CLI as given.
+  pub(super) fn send_files_to(
+    &mut self,
+    files: Vec<PathBuf>,
+    target: String,
+  ) -> Task<Action<Message>> {
+    self.taildrop.files_sent = true;
+    self.taildrop.sending_files = true;
+    let address = self
+      .peers
+      .iter()
+      .find(|peer| peer.name.as_str() == target)
+      .and_then(Peer::taildrop_target)
+      .unwrap_or_else(|| target.clone());
+    cosmic::task::future(async move {
+      let tx_status = tailscale_send(&files, &target, &address).await;
+      Message::Taildrop(TaildropMessage::FilesSent(tx_status))
+    })
+  }
+
+  /// Append a finished transfer to the on-disk history.
+  fn record_transfer(record: TransferRecord) -> Task<Action<Message>> {
+    cosmic::task::future(async move {
+      let result = append_record(record).await.map_err(|e| e.to_string());
+      Message::Taildrop(TaildropMessage::HistoryLoaded(result))
+    })
+  }
+
+  pub(super) fn update_taildrop(&mut self, message: TaildropMessage) -> Task<Action<Message>> {
+    let state = &mut self.taildrop;
+
+    match message {
+      TaildropMessage::ShowHistory(show) => {
+        state.show_history = show;
+        if show && state.history.is_none() {
+          return cosmic::task::future(async {
+            let result = load_history().await.map_err(|e| e.to_string());
+            Message::Taildrop(TaildropMessage::HistoryLoaded(result))
+          });
+        }
+      }
+      TaildropMessage::ChecksumsComputed(checksums) => {
+        state.checksums = Some(checksums);
+      }
+      TaildropMessage::CopyChecksum(sha256) => {
+        return cosmic::iced::clipboard::write(sha256);
+      }
+      TaildropMessage::HistoryLoaded(result) => match result {
+        Ok(records) => state.history = Some(records),
+        Err(e) => {
+          error!("Failed to update transfer history: {e}");
+          self.last_errors.taildrop = Some(e);
+        }
+      },
+      TaildropMessage::ClearHistory => {
+        state.history = Some(Vec::new());
+        return cosmic::task::future(async {
+          let result = clear_history().await.map(|()| Vec::new()).map_err(|e| e.to_string());
+          Message::Taildrop(TaildropMessage::HistoryLoaded(result))
+        });
+      }
+      TaildropMessage::PeerNoteChanged(note) => {
+        state.peer_note_draft = note;
+      }
+      TaildropMessage::SavePeerNote => {
+        if let Some(dev) = state.selected_device.clone() {
+          let mut notes = self.config.peer_notes.clone();
+          let note = state.peer_note_draft.trim();
+          if note.is_empty() {
+            notes.remove(&dev);
+          } else {
+            notes.insert(dev, note.to_string());
+          }
+
+          if let Some(ref handler) = self.config_handler
+            && let Err(e) = self.config.set_peer_notes(handler, notes)
+          {
+            self.config_writes.record("peer note", &e);
+          }
+          self.refresh_device_labels();
+        }
+      }
+      TaildropMessage::TogglePinDuringTransfers(pinned) => {
+        if let Some(ref handler) = self.config_handler
+          && let Err(e) = self.config.set_pin_during_transfers(handler, pinned)
+        {
+          self.config_writes.record("popup pin config", &e);
+        }
+      }
+      TaildropMessage::DeviceSelected(device) => {
+        // The list may have been refreshed since the choice was made.
+        if device.as_ref().is_none_or(|dev| state.device_options.contains(dev)) {
+          state.peer_note_draft = device
+            .as_ref()
+            .and_then(|dev| self.config.peer_notes.get(dev))
+            .cloned()
+            .unwrap_or_default();
+          state.selected_device = device;
+          state.send_confirm = None;
+
+          if state.files_sent {
+            state.files_sent = false;
+          }
+        }
+      }
+      TaildropMessage::SendTo(name) => {
+        state.show_history = false;
+        // The peer may be hidden by the own-devices filter.
+        if !state.device_options.contains(&name) {
+          state.own_devices_only = false;
+          self.refresh_device_options();
+        }
+        if !self.taildrop.device_options.contains(&name) {
+          return Task::none();
+        }
+        return Task::batch([
+          self.update_taildrop(TaildropMessage::DeviceSelected(Some(name))),
+          self.update_taildrop(TaildropMessage::ChooseFiles),
+        ]);
+      }
+      TaildropMessage::OwnDevicesOnly(own_only) => {
+        state.own_devices_only = own_only;
+        self.refresh_device_options();
+        // Keep the selection if the device is still listed.
+        let state = &self.taildrop;
+        let device = state
+          .selected_device
+          .clone()
+          .filter(|dev| state.device_options.contains(dev));
+        return self.update_taildrop(TaildropMessage::DeviceSelected(device));
+      }
+      TaildropMessage::ChooseFiles => {
+        return cosmic::task::future(async move {
+          let file_filter = FileFilter::new("Any").glob("*.*");
+          let dialog = file_chooser::open::Dialog::new()
+            .title(fl!("choose-files-title"))
+            .filter(file_filter);
+
+          let msg = match dialog.open_files().await {
+            Ok(file_responses) => {
+              TaildropMessage::FilesSelected(file_responses.urls().to_vec())
+            }
+            Err(file_chooser::Error::Cancelled) => TaildropMessage::FileChoosingCancelled,
+            Err(e) => TaildropMessage::FileChoosingFailed(e.to_string()),
+          };
+          Message::Taildrop(msg)
+        });
+      }
+      TaildropMessage::FilesSelected(urls) => {
+        for url in &urls {
+          if let Ok(path) = url.to_file_path() {
+            if path.exists() {
+              state.send_files.push(path);
+            }
+          } else {
+            warn!("Invalid file URL: {url}");
+          }
+        }
+
+        state.files_sent = false;
+        state.send_confirm = None;
+        return self.create_popup();
+      }
+      TaildropMessage::SendFiles => {
+        if state.selected_device.is_none() {
+          return Task::none();
+        }
+
+        let files = state.send_files.clone();
+        return cosmic::task::future(async move {
+          let mut bytes = 0;
+          for file in &files {
+            bytes += tokio::fs::metadata(file).await.map(|m| m.len()).unwrap_or_default();
+          }
+          Message::Taildrop(TaildropMessage::SendSizeChecked(bytes))
+        });
+      }
+      TaildropMessage::SendSizeChecked(bytes) => {
+        let files = state.send_files.len();
+        let max_files = self.config.confirm_send_files;
+        let max_bytes = self.config.confirm_send_mb.saturating_mul(1_000_000);
+        if (max_files > 0 && files > max_files) || (max_bytes > 0 && bytes > max_bytes) {
+          state.send_confirm = Some(PendingSend { files, bytes });
+          return Task::none();
+        }
+        return self.update_taildrop(TaildropMessage::ConfirmSend);
+      }
+      TaildropMessage::ConfirmSend => {
+        state.send_confirm = None;
+        let files = state.send_files.clone();
+
+        if let Some(dev) = state.selected_device.clone() {
+          return self.send_files_to(files, dev.to_string());
+        }
+      }
+      TaildropMessage::CancelSend => {
+        state.send_confirm = None;
+      }
+      TaildropMessage::FilesSent(report) => {
+        state.sending_files = false;
+        if self.popup.is_none() {
+          state.unseen_result = Some(report.failed == 0);
+        }
+        self.last_errors.taildrop = report.last_error.as_deref().map(|e| {
+          if is_not_enabled_error(e) {
+            fl!("not-enabled-for-tailnet")
+          } else {
+            e.to_string()
+          }
+        });
+        state.send_file_status = if report.failed == 0 {
+          fl!("files-sent-success", count = report.total, device = report.device.clone())
+        } else {
+          fl!(
+            "send-files-partial-fail",
+            failed = report.failed,
+            count = report.total,
+            device = report.device.clone()
+          )
+        };
+
+        state.send_files.clear();
+        state.selected_device = None;
+
+        let clear = cosmic::task::future(async {
+          clear_status(STATUS_CLEAR_TIME).await;
+          Message::Taildrop(TaildropMessage::ClearSendStatus)
+        });
+        let event = fl!(
+          "notify-files-sent",
+          count = report.total,
+          device = report.device.clone()
+        );
+        let record = Self::record_transfer(TransferRecord::new(
+          TransferDirection::Sent,
+          Some(report.device),
+          report.files,
+          report.last_error,
+        ));
+        if report.failed == 0 {
+          return Task::batch([
+            clear,
+            record,
+            self.alert_sound(SOUND_TRANSFER_COMPLETE),
+            self.notify(event),
+          ]);
+        }
+        return Task::batch([clear, record]);
+      }
+      TaildropMessage::FileChoosingCancelled => {
+        return self.create_popup();
+      }
+      TaildropMessage::FileChoosingFailed(err) => {
+        error!("Choosing a file or files went wrong: {err}");
+        self.last_errors.taildrop = Some(err);
+        return self.create_popup();
+      }
+      TaildropMessage::ReceiveFiles => {
+        state.receiving_files = true;
+        state.checksums = Some(Vec::new());
+        return cosmic::task::future(async move {
+          let rx_status = tailscale_receive().await;
+          Message::Taildrop(TaildropMessage::FilesReceived(rx_status))
+        });
+      }
+      TaildropMessage::CheckInbox => return Self::check_inbox(),
+      TaildropMessage::InboxChecked(count) => {
+        state.waiting_files = count;
+      }
+      TaildropMessage::FilesReceived(report) => {
+        state.receiving_files = false;
+        if self.popup.is_none() {
+          state.unseen_result = Some(!matches!(report, ReceiveReport::Failed(_)));
+        }
+        self.last_errors.taildrop = match &report {
+          ReceiveReport::Failed(err) => Some(err.clone()),
+          _ => None,
+        };
+        state.receive_file_status = match &report {
+          ReceiveReport::Received(files) => fl!("received-files-success", count = files.len()),
+          ReceiveReport::NoDownloadsDir => fl!("no-downloads-dir"),
+          ReceiveReport::TimedOut(seconds) => fl!("receive-timed-out", seconds = *seconds),
+          ReceiveReport::Failed(err) => fl!("receive-failed", error = err.clone()),
+        };
+
+        let clear = Task::batch([
+          cosmic::task::future(async {
+            clear_status(STATUS_CLEAR_TIME).await;
+            Message::Taildrop(TaildropMessage::ClearReceiveStatus)
+          }),
+          Self::check_inbox(),
+        ]);
+        return match report {
+          ReceiveReport::Received(files) if !files.is_empty() => {
+            let names = files.iter().map(|file| file.name.clone()).collect();
+            let checksums = match receive_dir() {
+              Some(dir) => {
+                state.checksums = None;
+                cosmic::task::future(async move {
+                  let checksums = sha256_files(&dir, names).await;
+                  Message::Taildrop(TaildropMessage::ChecksumsComputed(checksums))
+                })
+              }
+              None => Task::none(),
+            };
+
+            let event = fl!("notify-files-received", count = files.len());
+            let record =
+              TransferRecord::new(TransferDirection::Received, None, files, None);
+            Task::batch([
+              clear,
+              checksums,
+              Self::record_transfer(record),
+              self.alert_sound(SOUND_TRANSFER_COMPLETE),
+              self.notify(event),
+            ])
+          }
+          ReceiveReport::Failed(err) => {
+            let record =
+              TransferRecord::new(TransferDirection::Received, None, Vec::new(), Some(err));
+            Task::batch([clear, Self::record_transfer(record)])
+          }
+          _ => clear,
+        };
+      }
+      TaildropMessage::ClearSendStatus => {
+        if !state.sending_files {
+          state.send_file_status = String::new();
+        }
+      }
+      TaildropMessage::ClearReceiveStatus => {
+        if !state.receiving_files {
+          state.receive_file_status = String::new();
+        }
+      }
+    }
+    Task::none()
+  }
+
+  /// Count the files waiting in the inbox; this fails quietly for users who
+  /// aren't allowed to receive, leaving the count at zero.
+  fn check_inbox() -> Task<Action<Message>> {
+    cosmic::task::future(async {
+      Message::Taildrop(TaildropMessage::InboxChecked(
+        waiting_file_count().await.unwrap_or_default(),
+      ))
+    })
+  }
+
+  /// Taildrop controls followed by the transfer status.
+  pub(super) fn taildrop_view(&self, mutable: bool) -> Element<'_, Message> {
+    let state = &self.taildrop;
+    let allowed = self.tailnet_allows(CAP_FILE_SHARING);
+    let mutable = mutable && allowed;
+
+    let peer_note_row: Element<'_, Message> = if state.selected_device.is_some() {
+      row!(
+        text_input(fl!("peer-note-placeholder"), &state.peer_note_draft)
+          .on_input(|note| TaildropMessage::PeerNoteChanged(note).into())
+          .width(Length::Fill),
+        button::standard(fl!("save-note")).on_press(TaildropMessage::SavePeerNote.into())
+      )
+      .align_y(Alignment::Center)
+      .spacing(10)
+      .padding(5)
+      .into()
+    } else {
+      Row::new().into()
+    };
+
+    let tab = |label: String, history: bool| {
+      let msg = Message::Taildrop(TaildropMessage::ShowHistory(history));
+      if state.show_history == history {
+        button::suggested(label).on_press(msg)
+      } else {
+        button::standard(label).on_press(msg)
+      }
+    };
+
+    let title_text = if state.waiting_files > 0 {
+      fl!("tail-drop-waiting", count = state.waiting_files)
+    } else {
+      fl!("tail-drop")
+    };
+    let title: Element<'_, Message> = if allowed {
+      text(title_text).into()
+    } else {
+      column!(
+        text(title_text),
+        text(fl!("not-enabled-for-tailnet")).size(12),
+      )
+      .into()
+    };
+
+    let header = row!(
+      title,
+      tab(fl!("transfer-tab"), false),
+      tab(fl!("history-tab"), true),
+      horizontal_space().width(Length::Fill),
+      toggler(self.config.pin_during_transfers)
+        .label(fl!("pin-during-transfers"))
+        .on_toggle(|pinned| TaildropMessage::TogglePinDuringTransfers(pinned).into())
+    )
+    .align_y(Alignment::Center)
+    .spacing(10);
+
+    if state.show_history {
+      return column!(header, self.history_view()).into();
+    }
+
+    let options = state.device_options.clone();
+    let taildrop_elements: Vec<Element<'_, Message>> = vec![Element::from(
+      column!(
+        header,
+        row!(
+          column!(
+            dropdown(&state.device_labels, Some(state.selected_label_idx()), move |idx| {
+              // Resolved now, so the message names the device picked even if
+              // the list is refreshed before it is handled.
+              let device = idx.checked_sub(1).and_then(|idx| options.get(idx).cloned());
+              TaildropMessage::DeviceSelected(device).into()
+            })
+            .width(dropdown_width(&state.device_labels)),
+            toggler(state.own_devices_only)
+              .label(fl!("own-devices-only"))
+              .on_toggle(|own_only| TaildropMessage::OwnDevicesOnly(own_only).into()),
+          )
+          .spacing(5)
+          .align_x(Horizontal::Left)
+          .padding(5),
+          horizontal_space().width(Length::Fill),
+          column!(
+            button::standard(fl!("select-files"))
+              .on_press(TaildropMessage::ChooseFiles.into())
+              .width(220)
+              .tooltip(fl!("select-files-tooltip"))
+          )
+          .align_x(Horizontal::Right)
+          .padding(5)
+        )
+        .align_y(Alignment::Center)
+        .spacing(25),
+        peer_note_row,
+        row!(
+          column!(if !state.send_files.is_empty() {
+            button::standard(fl!("send-files"))
+              .on_press_maybe(mutable.then_some(TaildropMessage::SendFiles.into()))
+              .width(110)
+              .tooltip(fl!("send-files-tooltip"))
+          } else {
+            button::standard(fl!("send-files"))
+              .width(110)
+              .tooltip(fl!("send-files-tooltip"))
+          })
+          .align_x(Horizontal::Left)
+          .padding(5),
+          horizontal_space().width(Length::Fill),
+          column!(
+            button::standard(fl!("receive-files"))
+              .on_press_maybe(mutable.then_some(TaildropMessage::ReceiveFiles.into()))
+              .width(220)
+              .tooltip(fl!("receive-files-tooltip"))
+          )
+          .align_x(Horizontal::Right)
+          .padding(5)
+        )
+        .align_y(Alignment::Center)
+        .spacing(25),
+        self.send_confirm_view(),
+      )
+      .align_x(Alignment::Center),
+    )];
+
+    let taildrop_row = Row::with_children(taildrop_elements);
+
+    let taildrop_status_elements: Vec<Element<'_, Message>> = vec![Element::from(column!(
+      row!(text(fl!("send-receive-status"))
+        .width(Length::Fill)
+        .align_x(Horizontal::Center))
+      .height(30)
+      .align_y(Alignment::Center),
+      row!(if !state.send_file_status.is_empty() {
+        text(state.send_file_status.clone())
+      } else if state.files_sent
+        && let Some(dev) = &state.selected_device
+      {
+        text(fl!(
+          "sending-files",
+          count = state.send_files.len(),
+          device = dev.to_string()
+        ))
+      } else if state.selected_device.is_none() && !state.files_sent {
+        text(fl!("choose-device-first"))
+      } else {
+        text("")
+      }),
+      row!(text(state.receive_file_status.clone())),
+      self.checksums_view(),
+      section_error(self.last_errors.taildrop.as_deref()),
+    ))];
+
+    let tx_rx_status_row = Row::with_children(taildrop_status_elements);
+
+    column!(taildrop_row, tx_rx_status_row).into()
+  }
+
+  /// Summary of a large batch with Cancel/Send, shown before it starts.
+  fn send_confirm_view(&self) -> Element<'_, Message> {
+    let state = &self.taildrop;
+    let Some(pending) = state.send_confirm else {
+      return Row::new().into();
+    };
+
+    column!(
+      text(fl!(
+        "confirm-send",
+        count = pending.files,
+        size = format_size(pending.bytes),
+        device = state.selected_device.as_ref().map(ToString::to_string).unwrap_or_default()
+      )),
+      row!(
+        button::standard(fl!("cancel")).on_press(TaildropMessage::CancelSend.into()),
+        button::suggested(fl!("send-files")).on_press(TaildropMessage::ConfirmSend.into()),
+      )
+      .spacing(10),
+    )
+    .spacing(5)
+    .padding(5)
+    .into()
+  }
+
+  /// SHA-256 of each file from the last receive, with a copy button.
+  fn checksums_view(&self) -> Element<'_, Message> {
+    let Some(checksums) = &self.taildrop.checksums else {
+      return text(fl!("computing-checksums")).into();
+    };
+
+    let mut list = column!().spacing(5);
+    for checksum in checksums {
+      list = list.push(match &checksum.sha256 {
+        Ok(sha256) => settings::item(
+          checksum.name.clone(),
+          row!(
+            text(sha256.clone()).size(11),
+            button::icon(icon::from_name("edit-copy-symbolic"))
+              .on_press(TaildropMessage::CopyChecksum(sha256.clone()).into())
+              .tooltip(fl!("copy-checksum")),
+          )
+          .align_y(Alignment::Center)
+          .spacing(5),
+        ),
+        Err(err) => settings::item(
+          checksum.name.clone(),
+          text(fl!("checksum-failed", error = err.clone())),
+        ),
+      });
+    }
+    list.into()
+  }
+
+  /// Past transfers, newest first.
+  fn history_view(&self) -> Element<'_, Message> {
+    let Some(history) = &self.taildrop.history else {
+      return text(fl!("loading")).into();
+    };
+
+    if history.is_empty() {
+      return text(fl!("history-empty")).into();
+    }
+
+    let now = unix_secs();
+    let mut list = column!().spacing(5);
+    for record in history.iter().rev() {
+      let title = match (record.direction, &record.peer) {
+        (TransferDirection::Sent, Some(peer)) => fl!("history-sent", device = peer.clone()),
+        (TransferDirection::Sent, None) => fl!("history-sent-unknown"),
+        (TransferDirection::Received, _) => fl!("history-received"),
+      };
+
+      let mut files: Vec<String> = record
+        .files
+        .iter()
+        .take(HISTORY_FILES_SHOWN)
+        .map(|file| format!("{} ({})", file.name, format_size(file.size)))
+        .collect();
+      if record.files.len() > HISTORY_FILES_SHOWN {
+        files.push(fl!("history-more-files", count = record.files.len() - HISTORY_FILES_SHOWN));
+      }
+
+      let outcome = match &record.error {
+        None => fl!("history-ok"),
+        Some(err) if record.files.iter().any(|file| file.ok) => {
+          fl!("history-partial", error = err.clone())
+        }
+        Some(err) => fl!("history-failed", error = err.clone()),
+      };
+
+      list = list.push(settings::item(
+        format!("{title} - {}", format_age(now.saturating_sub(record.timestamp))),
+        text(if files.is_empty() {
+          outcome
+        } else {
+          format!("{}\n{outcome}", files.join(", "))
+        }),
+      ));
+    }
+
+    column!(
+      scrollable(list).height(Length::Fixed(HISTORY_HEIGHT)),
+      button::standard(fl!("clear-history")).on_press(TaildropMessage::ClearHistory.into()),
+    )
+    .spacing(5)
+    .padding(5)
+    .into()
+  }
+}
+
+/// Inbox watcher: periodically counts files waiting to be received, so they
+/// show in the section title even with notifications off.
+pub(super) fn inbox_subscription() -> Subscription<Message> {
+  cosmic::iced::time::every(Duration::from_secs(INBOX_POLL_SECS))
+    .map(|_| TaildropMessage::CheckInbox.into())
+}
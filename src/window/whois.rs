@@ -0,0 +1,107 @@
+//! Lookup of the node and user behind a tailnet address.
+
+use super::{busy_marker, Message, Window};
+use crate::fl;
+use crate::logic::{whois_lookup, WhoisInfo};
+use cosmic::iced::{
+  widget::{column, row},
+  Alignment, Length,
+};
+use cosmic::widget::{button, settings, text, text_input};
+use cosmic::{Action, Element, Task};
+
+/// The address being typed and the last lookup.
+#[derive(Default)]
+pub struct WhoisState {
+  draft: String,
+  running: bool,
+  result: Option<Result<WhoisInfo, String>>,
+}
+
+/// Messages handled by the whois lookup.
+#[derive(Clone, Debug)]
+pub enum WhoisMessage {
+  DraftChanged(String),
+  Lookup,
+  Finished(Result<WhoisInfo, String>),
+}
+
+impl From<WhoisMessage> for Message {
+  fn from(msg: WhoisMessage) -> Self {
+    Message::Whois(msg)
+  }
+}
+
+impl Window {
+  pub(super) fn update_whois(&mut self, message: WhoisMessage) -> Task<Action<Message>> {
+    let state = &mut self.whois;
+
+    match message {
+      WhoisMessage::DraftChanged(draft) => {
+        state.draft = draft;
+      }
+      WhoisMessage::Lookup => {
+        let query = state.draft.trim().to_string();
+        if !state.running && !query.is_empty() {
+          state.running = true;
+          return cosmic::task::future(async move {
+            let result = whois_lookup(&query).await.map_err(|e| e.to_string());
+            Message::Whois(WhoisMessage::Finished(result))
+          });
+        }
+      }
+      WhoisMessage::Finished(result) => {
+        state.running = false;
+        state.result = Some(result);
+      }
+    }
+    Task::none()
+  }
+
+  /// Input for a 100.x address or name, and who it belongs to.
+  pub(super) fn whois_view(&self) -> Element<'_, Message> {
+    let state = &self.whois;
+
+    let mut content = column!(
+      text(fl!("whois")).size(14),
+      row!(
+        text_input(fl!("whois-placeholder"), &state.draft)
+          .on_input(|draft| WhoisMessage::DraftChanged(draft).into())
+          .on_submit(|_| WhoisMessage::Lookup.into())
+          .width(Length::Fill),
+        button::standard(fl!("whois-lookup")).on_press_maybe(
+          (!state.running && !state.draft.trim().is_empty()).then_some(WhoisMessage::Lookup.into())
+        ),
+        busy_marker(state.running),
+      )
+      .align_y(Alignment::Center)
+      .spacing(8),
+    )
+    .spacing(5);
+
+    match &state.result {
+      None => {}
+      Some(Err(e)) => content = content.push(text(fl!("whois-failed", error = e.clone()))),
+      Some(Ok(info)) => {
+        let or_unknown = |value: &str| {
+          if value.is_empty() {
+            fl!("not-available")
+          } else {
+            value.to_string()
+          }
+        };
+        let tags = if info.tags.is_empty() {
+          fl!("whois-no-tags")
+        } else {
+          info.tags.join(", ")
+        };
+        content = content
+          .push(settings::item(fl!("whois-address"), text(info.address.to_string())))
+          .push(settings::item(fl!("whois-machine"), text(or_unknown(&info.machine))))
+          .push(settings::item(fl!("whois-owner"), text(or_unknown(&info.owner))))
+          .push(settings::item(fl!("whois-tags"), text(tags)));
+      }
+    }
+    content.into()
+  }
+}